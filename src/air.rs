@@ -0,0 +1,209 @@
+use heapless::String;
+use ufmt::uwrite;
+
+use crate::messages::{AIR_VENTING, GAS_BASELINE, GAS_NOW, LEARNING};
+use crate::preferences::{Hysteresis, MAX_VENT_ANGLE};
+use crate::timer::format_minutes_seconds;
+
+use panic_probe as _;
+
+/// How long in seconds the gas resistance is averaged for once the sensor has warmed up,
+/// before its baseline is trusted
+pub const BASELINE_LEARN_S: u32 = 3600;
+/// How many percent the gas resistance must recover by before the vent closes again,
+/// so it does not flap while the air is borderline
+pub const AIR_VENT_HYSTERESIS: u8 = 5;
+/// How far in degrees the roof vent opens for stale air
+pub const AIR_VENT_ANGLE: u8 = MAX_VENT_ANGLE / 2;
+/// How much a cleaner reading pulls the baseline up, in 1/N of the difference
+const BASELINE_RISE: u32 = 8;
+/// How much a staler reading pulls the baseline down, in 1/N of the difference.
+/// It is much slower than [BASELINE_RISE], so the sensor aging is followed but bad air is not learned
+const BASELINE_FALL: u32 = 256;
+
+/// Tracks the gas resistance against a learned baseline, and opens the vent once the air goes stale.
+/// A lower gas resistance means more volatile compounds in the air
+///
+/// - **learning_since**: When the baseline started being learned in seconds since boot,
+///   or None before the first warmed-up reading
+/// - **sum**, **count**: The readings averaged while the baseline is learned
+/// - **baseline**: The baseline gas resistance in ohms, or None until it was learned
+/// - **vent_control**: Whether the vent is open for stale air, on once the gas resistance drops
+///   past [crate::preferences::Preferences::air_venting] and off once it recovers by [AIR_VENT_HYSTERESIS]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::air::{AirQuality, BASELINE_LEARN_S};
+///
+/// let mut air = AirQuality::new();
+/// // Learn the baseline for the first hour
+/// assert!(!air.update(100_000, 0, Some(20)));
+/// assert_eq!(air.learning_remaining(60).unwrap().as_str(), "59:00");
+/// assert!(!air.update(100_000, BASELINE_LEARN_S, Some(20)));
+/// assert_eq!(air.baseline(), Some(100_000));
+///
+/// // Open the vent once it drops more than 20% below the baseline, and close it once it recovers
+/// assert!(!air.update(85_000, BASELINE_LEARN_S + 10, Some(20)));
+/// assert!(air.update(75_000, BASELINE_LEARN_S + 20, Some(20)));
+/// assert_eq!(air.deviation(75_000), Some(-24));
+/// assert_eq!(air.format_reading(75_000).as_str(), "Now -24% Venting");
+/// assert!(air.update(82_000, BASELINE_LEARN_S + 30, Some(20)));
+/// assert!(!air.update(90_000, BASELINE_LEARN_S + 40, Some(20)));
+///
+/// // Disabled, the baseline is still kept
+/// assert!(!air.update(50_000, BASELINE_LEARN_S + 50, None));
+/// assert!(air.baseline().is_some());
+/// ```
+pub struct AirQuality {
+    learning_since: Option<u32>,
+    sum: u64,
+    count: u32,
+    baseline: Option<u32>,
+    vent_control: Hysteresis<u8>,
+}
+
+impl Default for AirQuality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AirQuality {
+    /// Creates a new instance of AirQuality, with no baseline yet
+    ///
+    /// returns a new instance of AirQuality
+    pub fn new() -> AirQuality {
+        Self {
+            learning_since: None,
+            sum: 0,
+            count: 0,
+            baseline: None,
+            vent_control: Hysteresis::rising((0, 0)),
+        }
+    }
+
+    /// Forgets the baseline, so it is learned again. Call this whenever the sensor is set up,
+    /// since its gas heater starts cold
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Feeds a new reading in. Only readings from a warmed-up sensor should be fed in;
+    /// see [crate::zone::Zone::is_warmed_up()]
+    ///
+    /// - param gas_resistance: The gas resistance in ohms
+    /// - param now: The current time in seconds since boot
+    /// - param drop_percent: How many percent below the baseline the vent opens,
+    ///   or None if the vent is not opened for stale air
+    ///
+    /// returns true if the vent should be open for stale air
+    pub fn update(&mut self, gas_resistance: u32, now: u32, drop_percent: Option<u8>) -> bool {
+        let Some(baseline) = self.baseline else {
+            // Average the first hour
+            let since = *self.learning_since.get_or_insert(now);
+            self.sum += gas_resistance as u64;
+            self.count += 1;
+            if now.wrapping_sub(since) >= BASELINE_LEARN_S {
+                self.baseline = Some((self.sum / self.count as u64) as u32);
+            }
+            return false;
+        };
+
+        // Follow cleaner air quickly, and staler air only slowly
+        let baseline = if gas_resistance > baseline {
+            baseline + (gas_resistance - baseline) / BASELINE_RISE
+        } else {
+            baseline - (baseline - gas_resistance) / BASELINE_FALL
+        };
+        self.baseline = Some(baseline);
+
+        let Some(threshold) = drop_percent else {
+            self.vent_control.reset();
+            return false;
+        };
+        self.vent_control
+            .set_points((threshold.saturating_sub(AIR_VENT_HYSTERESIS), threshold));
+        let drop = baseline.saturating_sub(gas_resistance) as u64 * 100 / baseline.max(1) as u64;
+        self.vent_control.update(drop as u8)
+    }
+
+    /// Gets the baseline gas resistance
+    ///
+    /// returns the baseline in ohms, or None while it is still learned
+    pub fn baseline(&self) -> Option<u32> {
+        self.baseline
+    }
+
+    /// Checks if the vent is open for stale air
+    ///
+    /// returns true from when the air went stale until it recovered
+    pub fn is_venting(&self) -> bool {
+        self.vent_control.is_on()
+    }
+
+    /// Works out how far a reading is from the baseline
+    ///
+    /// - param gas_resistance: The gas resistance in ohms
+    ///
+    /// returns the difference in percent of the baseline, negative for staler air,
+    /// or None while the baseline is still learned
+    pub fn deviation(&self, gas_resistance: u32) -> Option<i32> {
+        let baseline = self.baseline?.max(1) as i64;
+        Some(((gas_resistance as i64 - baseline) * 100 / baseline) as i32)
+    }
+
+    /// Formats how long is left until the baseline is learned, rounded up to the second: `MM:SS`
+    ///
+    /// - param now: The current time in seconds since boot
+    ///
+    /// returns a [String] of length 8 containing the formatted time, or None once it was learned
+    pub fn learning_remaining(&self, now: u32) -> Option<String<8>> {
+        if self.baseline.is_some() {
+            return None;
+        }
+        let elapsed = self
+            .learning_since
+            .map_or(0, |since| now.wrapping_sub(since));
+        Some(format_minutes_seconds(
+            BASELINE_LEARN_S.saturating_sub(elapsed) * 1000,
+        ))
+    }
+
+    /// Formats the baseline for the LCD: `Base: 85kOhm`, or `Learning 59:00` while it is learned
+    ///
+    /// - param now: The current time in seconds since boot
+    ///
+    /// returns a [String] of length 16 containing the formatted baseline
+    pub fn format_baseline(&self, now: u32) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.baseline {
+            Some(baseline) => uwrite!(str, "{}: {}kOhm", GAS_BASELINE, baseline / 1000).unwrap(),
+            None => {
+                let remaining = self.learning_remaining(now).unwrap_or_default();
+                uwrite!(str, "{} {}", LEARNING, remaining.as_str()).unwrap();
+            }
+        }
+        str
+    }
+
+    /// Formats a reading against the baseline for the LCD: `Now -24% Venting`, or `Now 80kOhm`
+    /// while the baseline is learned
+    ///
+    /// - param gas_resistance: The gas resistance in ohms
+    ///
+    /// returns a [String] of length 24 containing the formatted reading
+    pub fn format_reading(&self, gas_resistance: u32) -> String<24> {
+        let mut str: String<24> = String::new();
+        match self.deviation(gas_resistance) {
+            Some(deviation) => {
+                let sign = if deviation > 0 { "+" } else { "" };
+                uwrite!(str, "{} {}{}%", GAS_NOW, sign, deviation).unwrap();
+                if self.is_venting() {
+                    uwrite!(str, " {}", AIR_VENTING).unwrap();
+                }
+            }
+            None => uwrite!(str, "{} {}kOhm", GAS_NOW, gas_resistance / 1000).unwrap(),
+        }
+        str
+    }
+}
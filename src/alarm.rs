@@ -0,0 +1,146 @@
+use crate::messages::{TOO_COLD, TOO_DRY, TOO_HOT, TOO_HUMID};
+use crate::preferences::{Hysteresis, Preferences};
+
+use panic_probe as _;
+
+/// How many degrees Fahrenheit, or percent of humidity, a reading must come back within its alarm bounds
+/// before the alarm clears
+pub const ALARM_MARGIN: u8 = 2;
+
+/// A reading outside of its alarm bounds
+///
+/// - **TooCold**, **TooHot**: The temperature is outside of [Preferences::temperature_alarm]
+/// - **TooDry**, **TooHumid**: The humidity is outside of [Preferences::humidity_alarm]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alarm {
+    TooCold,
+    TooHot,
+    TooDry,
+    TooHumid,
+}
+
+impl Alarm {
+    /// Gets the alarm's warning for the LCD and logs
+    ///
+    /// returns the warning; Ex: `TOO HOT`
+    pub fn name(self) -> &'static str {
+        match self {
+            Alarm::TooCold => TOO_COLD,
+            Alarm::TooHot => TOO_HOT,
+            Alarm::TooDry => TOO_DRY,
+            Alarm::TooHumid => TOO_HUMID,
+        }
+    }
+
+    /// Checks if the alarm is about the temperature rather than the humidity
+    ///
+    /// returns true for [Alarm::TooCold] and [Alarm::TooHot]
+    pub fn is_temperature(self) -> bool {
+        matches!(self, Alarm::TooCold | Alarm::TooHot)
+    }
+}
+
+/// Watches the readings against their alarm bounds. These are separate from the ranges the outputs follow,
+/// so the vent can open at 80°F while the alarm only sounds at 95°F, when the vent must have failed
+///
+/// - **cold**, **hot**, **dry**, **humid**: One [Hysteresis] per bound, each clearing once the reading
+///   is back within it by [ALARM_MARGIN]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::alarm::{Alarm, ReadingAlarms};
+/// use gem_rs::preferences::{should_heat, should_humidify, Hysteresis, Preferences};
+///
+/// let mut preferences = Preferences::default();
+/// preferences.temperature = (60, 80); // The vent opens above 80°F
+/// preferences.temperature_alarm = (32, 95); // The alarm sounds above 95°F
+/// let mut vent = Hysteresis::rising(preferences.vent_points());
+/// let mut alarms = ReadingAlarms::new(&preferences);
+///
+/// // Hot enough to vent, but not to sound the alarm
+/// assert!(vent.update(85));
+/// assert_eq!(alarms.update(85, 65), None);
+///
+/// // The vent did not cool it down
+/// assert!(vent.update(96));
+/// assert_eq!(alarms.update(96, 65), Some(Alarm::TooHot));
+/// assert_eq!(alarms.update(94, 65), Some(Alarm::TooHot)); // Still within the margin
+/// assert_eq!(alarms.update(93, 65), None);
+///
+/// // Cold enough to heat, but not to sound the alarm
+/// assert!(should_heat(50, false, &preferences));
+/// assert_eq!(alarms.update(50, 65), None);
+/// assert_eq!(alarms.update(31, 65), Some(Alarm::TooCold));
+///
+/// // Dry enough to water, but not to sound the alarm
+/// assert!(should_humidify(50, false, &preferences));
+/// assert_eq!(alarms.update(70, 50), None);
+/// assert_eq!(alarms.update(70, 19), Some(Alarm::TooDry));
+/// assert_eq!(alarms.active(), Some(Alarm::TooDry));
+/// ```
+pub struct ReadingAlarms {
+    cold: Hysteresis<u8>,
+    hot: Hysteresis<u8>,
+    dry: Hysteresis<u8>,
+    humid: Hysteresis<u8>,
+}
+
+impl ReadingAlarms {
+    /// Creates a new instance of ReadingAlarms, with no alarm sounding
+    ///
+    /// - param preferences: The [Preferences] holding the alarm bounds
+    ///
+    /// returns a new instance of ReadingAlarms
+    pub fn new(preferences: &Preferences) -> ReadingAlarms {
+        let mut alarms = Self {
+            cold: Hysteresis::falling((0, 0)),
+            hot: Hysteresis::rising((0, 0)),
+            dry: Hysteresis::falling((0, 0)),
+            humid: Hysteresis::rising((0, 0)),
+        };
+        alarms.set_bounds(preferences);
+        alarms
+    }
+
+    /// Moves every alarm to the bounds in the [Preferences]. Call this after the preferences were edited
+    ///
+    /// - param preferences: The [Preferences] holding the alarm bounds
+    pub fn set_bounds(&mut self, preferences: &Preferences) {
+        let (cold, hot) = preferences.temperature_alarm;
+        let (dry, humid) = preferences.humidity_alarm;
+        self.cold
+            .set_points((cold, cold.saturating_add(ALARM_MARGIN)));
+        self.hot.set_points((hot.saturating_sub(ALARM_MARGIN), hot));
+        self.dry.set_points((dry, dry.saturating_add(ALARM_MARGIN)));
+        self.humid
+            .set_points((humid.saturating_sub(ALARM_MARGIN), humid));
+    }
+
+    /// Feeds a new reading in
+    ///
+    /// - param temp: The temperature in Fahrenheit
+    /// - param humidity: The relative humidity percentage
+    ///
+    /// returns the [Alarm] that should be sounding, or None if every reading is within its bounds
+    pub fn update(&mut self, temp: u8, humidity: u8) -> Option<Alarm> {
+        self.cold.update(temp);
+        self.hot.update(temp);
+        self.dry.update(humidity);
+        self.humid.update(humidity);
+        self.active()
+    }
+
+    /// Gets the alarm that is sounding. The temperature comes first, since it harms plants faster
+    ///
+    /// returns the [Alarm], or None if every reading is within its bounds
+    pub fn active(&self) -> Option<Alarm> {
+        [
+            (self.cold.is_on(), Alarm::TooCold),
+            (self.hot.is_on(), Alarm::TooHot),
+            (self.dry.is_on(), Alarm::TooDry),
+            (self.humid.is_on(), Alarm::TooHumid),
+        ]
+        .into_iter()
+        .find_map(|(on, alarm)| on.then_some(alarm))
+    }
+}
@@ -0,0 +1,131 @@
+use heapless::String;
+use rp_pico::hal::adc::AdcPin;
+use rp_pico::hal::gpio::bank0::Gpio29;
+use rp_pico::hal::gpio::{FunctionSioInput, Pin, PullNone};
+use ufmt::uwrite;
+
+use crate::messages::BATTERY;
+use crate::preferences::{BatterySettings, Hysteresis};
+use crate::sensors::format_hundredths;
+
+use panic_probe as _;
+
+/// The battery's pin. On a Pico, ADC3 reads VSYS through the board's own 3:1 divider,
+/// so a battery powering VSYS needs no extra wiring
+pub type BatteryPin = AdcPin<Pin<Gpio29, FunctionSioInput, PullNone>>;
+
+/// The ADC's reference voltage in millivolts
+pub const ADC_REFERENCE_MV: u32 = 3300;
+/// The ADC's largest 12 bit reading, at [ADC_REFERENCE_MV]
+pub const ADC_FULL_SCALE: u32 = 4095;
+/// The Pico's VSYS divider ratio in hundredths: 3.00
+pub const PICO_VSYS_DIVIDER: u16 = 300;
+/// How many hundredths of a volt the battery must recover by before it is no longer low,
+/// so the power saving does not flicker as the load changes
+pub const BATTERY_HYSTERESIS: u16 = 20;
+
+/// Works out the battery voltage from a raw ADC reading
+///
+/// - param raw: The raw reading from [crate::soil::read_raw()]
+/// - param divider: The voltage divider's ratio in hundredths; Ex: [PICO_VSYS_DIVIDER]
+///
+/// returns the battery voltage in hundredths of a volt
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::battery::{battery_volts, PICO_VSYS_DIVIDER};
+///
+/// assert_eq!(battery_volts(4095, PICO_VSYS_DIVIDER), 990); // 3.3V on the pin
+/// assert_eq!(battery_volts(1675, PICO_VSYS_DIVIDER), 404);
+/// assert_eq!(battery_volts(1675, 100), 134); // No divider
+/// ```
+pub fn battery_volts(raw: u16, divider: u16) -> u16 {
+    let millivolts = raw as u32 * ADC_REFERENCE_MV * divider as u32 / (ADC_FULL_SCALE * 100);
+    (millivolts / 10).min(u16::MAX as u32) as u16
+}
+
+/// Keeps track of the battery voltage and whether it is low
+///
+/// - **volts**: The last battery voltage in hundredths of a volt
+/// - **low_control**: Whether the battery is low, on below [BatterySettings::low_voltage]
+///   and off once it recovers by [BATTERY_HYSTERESIS]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::battery::BatteryMonitor;
+/// use gem_rs::preferences::BatterySettings;
+///
+/// let settings = BatterySettings::default(); // Low below 3.40V
+/// let mut battery = BatteryMonitor::new();
+/// assert!(!battery.update(Some(1675), &settings)); // 4.04V
+/// assert_eq!(battery.format().as_str(), "Battery: 4.04V");
+///
+/// assert!(battery.update(Some(1400), &settings)); // 3.38V
+/// assert!(battery.update(Some(1420), &settings)); // 3.43V is not enough to recover
+/// assert!(!battery.update(Some(1500), &settings)); // 3.62V
+///
+/// // A failed reading keeps the last one
+/// assert!(!battery.update(None, &settings));
+/// assert_eq!(battery.volts, 362);
+/// ```
+pub struct BatteryMonitor {
+    pub volts: u16,
+    low_control: Hysteresis<u16>,
+}
+
+impl Default for BatteryMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatteryMonitor {
+    /// Creates a new instance of BatteryMonitor, with nothing read yet
+    ///
+    /// returns a new instance of BatteryMonitor
+    pub fn new() -> BatteryMonitor {
+        Self {
+            volts: 0,
+            low_control: Hysteresis::falling((0, 0)),
+        }
+    }
+
+    /// Feeds a new reading in
+    ///
+    /// - param raw: The raw reading from [crate::soil::read_raw()], or None if the ADC could not be read
+    /// - param settings: The [BatterySettings]
+    ///
+    /// returns true if the battery is low
+    pub fn update(&mut self, raw: Option<u16>, settings: &BatterySettings) -> bool {
+        self.low_control.set_points((
+            settings.low_voltage,
+            settings.low_voltage.saturating_add(BATTERY_HYSTERESIS),
+        ));
+        if let Some(raw) = raw {
+            self.volts = battery_volts(raw, settings.divider);
+        }
+        self.low_control.update(self.volts)
+    }
+
+    /// Checks if the battery is low
+    ///
+    /// returns true from when the battery dropped below its threshold until it recovered
+    pub fn is_low(&self) -> bool {
+        self.low_control.is_on()
+    }
+
+    /// Formats the battery voltage: `Battery: 4.04V`
+    ///
+    /// returns a [String] of length 16 containing the formatted voltage
+    pub fn format(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        uwrite!(
+            str,
+            "{}: {}V",
+            BATTERY,
+            format_hundredths(self.volts).as_str()
+        )
+        .unwrap();
+        str
+    }
+}
@@ -0,0 +1,317 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::InputPin;
+
+use panic_probe as _;
+
+/// The amount of consecutive samples a button must read the same before its state changes
+pub const DEBOUNCE_SAMPLES: u8 = 5;
+
+/// The amount of consecutive 1ms samples the smoke detector must read high before the fire alarm starts.
+/// This ignores brief spikes, e.g. from power fluctuations
+pub const SMOKE_SAMPLES: u16 = 1000;
+
+/// Debounces a button or other input that is sampled once every tick
+///
+/// - **count**: How many consecutive samples differed from the current state
+/// - **samples**: How many consecutive samples must differ before the state changes
+/// - **pressed**: The current stable state of the button
+///
+/// ## Example:
+/// ```rust
+/// use embedded_hal_mock::eh1::digital::{Mock, State::*, Transaction};
+/// use gem_rs::buttons::Debouncer;
+///
+/// // A bouncing press that then settles
+/// let samples = [High, Low, High, Low, High, High, High, High, High, High];
+/// let mut pin = Mock::new(&samples.map(Transaction::get));
+/// let mut debouncer = Debouncer::new();
+/// let presses = (0..10).filter(|_| debouncer.update(&mut pin)).count();
+/// assert_eq!(presses, 1); // The bouncing press is only reported once
+/// pin.done();
+/// ```
+pub struct Debouncer {
+    count: u16,
+    samples: u16,
+    pressed: bool,
+}
+
+impl Default for Debouncer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debouncer {
+    /// Creates a new instance of Debouncer for a released button
+    ///
+    /// returns a new instance of Debouncer
+    pub fn new() -> Debouncer {
+        Self::with_samples(DEBOUNCE_SAMPLES as u16)
+    }
+
+    /// Creates a new instance of Debouncer for a released input that must hold
+    /// a new state for longer than a button, e.g. [SMOKE_SAMPLES] for the smoke detector
+    ///
+    /// - param samples: How many consecutive samples must differ before the state changes
+    ///
+    /// returns a new instance of Debouncer
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use embedded_hal_mock::eh1::digital::{Mock, State, Transaction};
+    /// use gem_rs::buttons::Debouncer;
+    ///
+    /// // A brief spike: high for 50 samples, then low
+    /// let spike: Vec<Transaction> = (0..200)
+    ///     .map(|sample| Transaction::get(if sample < 50 { State::High } else { State::Low }))
+    ///     .collect();
+    /// let mut spike = Mock::new(&spike);
+    /// let mut debouncer = Debouncer::with_samples(100);
+    /// assert!(!(0..200).any(|_| debouncer.update(&mut spike)));
+    /// spike.done();
+    ///
+    /// // Smoke stays high, and is reported on the 100th sample
+    /// let mut smoke = Mock::new(&vec![Transaction::get(State::High); 100]);
+    /// let mut debouncer = Debouncer::with_samples(100);
+    /// assert!((0..200).any(|_| debouncer.update(&mut smoke)));
+    /// assert!(debouncer.is_pressed());
+    /// smoke.done();
+    /// ```
+    pub fn with_samples(samples: u16) -> Debouncer {
+        Self {
+            count: 0,
+            samples,
+            pressed: false,
+        }
+    }
+
+    /// Samples the button
+    ///
+    /// **NOTE:** This function should be called every tick
+    ///
+    /// - param pin: The button's pin
+    ///
+    /// returns true only on the tick the button becomes stably pressed
+    pub fn update<P: InputPin>(&mut self, pin: &mut P) -> bool {
+        let high = pin.is_high().unwrap_or(false);
+        if high == self.pressed {
+            self.count = 0;
+            return false;
+        }
+
+        self.count += 1;
+        if self.count >= self.samples {
+            self.count = 0;
+            self.pressed = high;
+            return high;
+        }
+        false
+    }
+
+    /// Checks if the button is stably pressed
+    ///
+    /// returns true if the button is pressed
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+}
+
+/// How many ticks after a press other presses still count as part of the same chord
+pub const CHORD_WINDOW: u16 = 50;
+
+/// The buttons pressed together
+///
+/// - **up**: The Up button was pressed
+/// - **down**: The Down button was pressed
+/// - **select**: The Select button was pressed
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ButtonPresses {
+    pub up: bool,
+    pub down: bool,
+    pub select: bool,
+}
+
+impl ButtonPresses {
+    /// Checks if any button was pressed
+    ///
+    /// returns true if at least one button was pressed
+    pub fn any(&self) -> bool {
+        self.up || self.down || self.select
+    }
+
+    /// Checks if several buttons were pressed together
+    ///
+    /// returns true if at least two buttons were pressed
+    pub fn is_chord(&self) -> bool {
+        (self.up as u8 + self.down as u8 + self.select as u8) > 1
+    }
+}
+
+/// Debounces the Up, Down, and Select buttons together, so presses that happen at once are
+/// reported at once. A press is reported [CHORD_WINDOW] ticks after it happens,
+/// together with every other button pressed or held by then
+///
+/// - **up**, **down**, **select**: Each button's [Debouncer]
+/// - **pending**: The buttons pressed in the current chord window, if one is open
+/// - **window**: Ticks left until the pending presses are reported
+///
+/// ## Example:
+/// ```rust
+/// use embedded_hal_mock::eh1::digital::{Mock, State, Transaction};
+/// use gem_rs::buttons::{ButtonPad, ButtonPresses, CHORD_WINDOW, DEBOUNCE_SAMPLES};
+///
+/// // A button that reads the same for every sample
+/// fn button(held: bool, samples: usize) -> Mock {
+///     let state = if held { State::High } else { State::Low };
+///     Mock::new(&vec![Transaction::get(state); samples])
+/// }
+///
+/// // A press is reported once it is debounced and its chord window closes
+/// let reported = (DEBOUNCE_SAMPLES as u16 + CHORD_WINDOW) as usize;
+///
+/// // Up and Down held together, then held some more
+/// let samples = reported + 100;
+/// let mut up = button(true, samples);
+/// let mut down = button(true, samples);
+/// let mut select = button(false, samples);
+/// let mut pad = ButtonPad::new();
+/// let presses = (0..reported).find_map(|_| pad.update(&mut up, &mut down, &mut select));
+/// assert_eq!(presses, Some(ButtonPresses { up: true, down: true, select: false }));
+/// assert!(presses.unwrap().is_chord());
+/// // Holding them does not repeat the chord
+/// assert!((0..100).all(|_| pad.update(&mut up, &mut down, &mut select).is_none()));
+/// for mut pin in [up, down, select] {
+///     pin.done();
+/// }
+///
+/// // Select on its own
+/// let mut up = button(false, reported);
+/// let mut down = button(false, reported);
+/// let mut select = button(true, reported);
+/// let mut pad = ButtonPad::new();
+/// let presses = (0..reported).find_map(|_| pad.update(&mut up, &mut down, &mut select));
+/// assert_eq!(presses, Some(ButtonPresses { up: false, down: false, select: true }));
+/// assert!(!presses.unwrap().is_chord());
+/// for mut pin in [up, down, select] {
+///     pin.done();
+/// }
+/// ```
+pub struct ButtonPad {
+    up: Debouncer,
+    down: Debouncer,
+    select: Debouncer,
+    pending: Option<ButtonPresses>,
+    window: u16,
+}
+
+impl Default for ButtonPad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ButtonPad {
+    /// Creates a new instance of ButtonPad with every button released
+    ///
+    /// returns a new instance of ButtonPad
+    pub fn new() -> ButtonPad {
+        Self {
+            up: Debouncer::new(),
+            down: Debouncer::new(),
+            select: Debouncer::new(),
+            pending: None,
+            window: 0,
+        }
+    }
+
+    /// Samples the buttons
+    ///
+    /// **NOTE:** This function should be called every tick
+    ///
+    /// - param up: The Up button's pin
+    /// - param down: The Down button's pin
+    /// - param select: The Select button's pin
+    ///
+    /// returns the [ButtonPresses] once a chord window closes, otherwise None
+    pub fn update(
+        &mut self,
+        up: &mut impl InputPin,
+        down: &mut impl InputPin,
+        select: &mut impl InputPin,
+    ) -> Option<ButtonPresses> {
+        let pressed = ButtonPresses {
+            up: self.up.update(up),
+            down: self.down.update(down),
+            select: self.select.update(select),
+        };
+
+        if pressed.any() && self.pending.is_none() {
+            self.pending = Some(ButtonPresses::default());
+            self.window = CHORD_WINDOW;
+        }
+        let pending = self.pending.as_mut()?;
+        pending.up |= pressed.up;
+        pending.down |= pressed.down;
+        pending.select |= pressed.select;
+
+        if self.window > 0 {
+            self.window -= 1;
+            return None;
+        }
+        // Buttons held from before the window still count
+        let mut presses = self.pending.take()?;
+        presses.up |= self.up.is_pressed();
+        presses.down |= self.down.is_pressed();
+        presses.select |= self.select.is_pressed();
+        Some(presses)
+    }
+
+    /// Gets which buttons are held down right now, once debounced
+    ///
+    /// returns the held [ButtonPresses]
+    pub fn held(&self) -> ButtonPresses {
+        ButtonPresses {
+            up: self.up.is_pressed(),
+            down: self.down.is_pressed(),
+            select: self.select.is_pressed(),
+        }
+    }
+}
+
+/// Checks if a button is pressed by sampling it [DEBOUNCE_SAMPLES] times, 1ms apart.
+/// This is meant for loops that only poll the buttons occasionally
+///
+/// - param pin: The button's pin
+/// - param delay: Delay instance
+///
+/// returns true if every sample read pressed
+///
+/// ## Example:
+/// ```rust
+/// use embedded_hal_mock::eh1::delay::NoopDelay;
+/// use embedded_hal_mock::eh1::digital::{Mock, State::*, Transaction};
+/// use gem_rs::buttons::is_pressed;
+///
+/// let mut delay = NoopDelay::new();
+///
+/// // Held through every sample
+/// let mut held = Mock::new(&[High; 5].map(Transaction::get));
+/// assert!(is_pressed(&mut held, &mut delay));
+/// held.done();
+///
+/// // Released while sampling; the rest of the samples are skipped
+/// let mut released = Mock::new(&[High, High, Low].map(Transaction::get));
+/// assert!(!is_pressed(&mut released, &mut delay));
+/// released.done();
+/// ```
+pub fn is_pressed<P: InputPin>(pin: &mut P, delay: &mut impl DelayNs) -> bool {
+    for sample in 0..DEBOUNCE_SAMPLES {
+        if sample > 0 {
+            delay.delay_ms(1);
+        }
+        if !pin.is_high().unwrap_or(false) {
+            return false;
+        }
+    }
+    true
+}
@@ -0,0 +1,207 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+use crate::timer::FROST_BEEP_TIME;
+
+use panic_probe as _;
+
+/// How the buzzer sounds during a fire: three beeps, then a pause, like a smoke alarm
+pub const FIRE_PATTERN: BuzzerPattern = BuzzerPattern {
+    durations: &[500, 500, 500, 500, 500, 1500],
+    repeats: true,
+};
+/// How the buzzer sounds while it is freezing: an even beep
+pub const FROST_PATTERN: BuzzerPattern = BuzzerPattern {
+    durations: &[FROST_BEEP_TIME, FROST_BEEP_TIME],
+    repeats: true,
+};
+/// How the buzzer sounds while a reading is outside of its alarm bounds: a long beep, then a long pause
+pub const READING_ALARM_PATTERN: BuzzerPattern = BuzzerPattern {
+    durations: &[1000, 2000],
+    repeats: true,
+};
+/// How the buzzer sounds when a sensor stops answering: two long beeps
+pub const SENSOR_FAULT_PATTERN: BuzzerPattern = BuzzerPattern {
+    durations: &[500, 1000, 500, 1000],
+    repeats: false,
+};
+/// How the buzzer sounds when the sprinklers start: two short chirps
+pub const WATERING_CHIRP: BuzzerPattern = BuzzerPattern {
+    durations: &[50, 100, 50, 100],
+    repeats: false,
+};
+
+/// A sequence of beeps, so each alarm can be told apart by ear
+///
+/// - **durations**: How long in milliseconds the buzzer is on, then off, then on again, and so on.
+///   It should end on an off duration, so the buzzer is quiet once the pattern is over
+/// - **repeats**: Whether the pattern starts over once it is over, or plays only once
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::buzzer::{BuzzerPattern, FIRE_PATTERN, WATERING_CHIRP};
+///
+/// let beep = BuzzerPattern { durations: &[100, 200], repeats: true };
+/// assert_eq!(beep.period_ms(), 300);
+/// assert!(beep.is_on(0));
+/// assert!(!beep.is_on(100));
+/// assert!(beep.is_on(300)); // It started over
+///
+/// // A chirp plays once
+/// assert!(WATERING_CHIRP.is_on(0));
+/// assert!(!WATERING_CHIRP.is_on(WATERING_CHIRP.period_ms()));
+///
+/// // The fire alarm beeps three times, then pauses
+/// assert!(FIRE_PATTERN.is_on(2000));
+/// assert!(!FIRE_PATTERN.is_on(2600));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BuzzerPattern {
+    pub durations: &'static [u32],
+    pub repeats: bool,
+}
+
+impl BuzzerPattern {
+    /// Gets how long the pattern plays before it is over or starts over
+    ///
+    /// returns the pattern's length in milliseconds
+    pub fn period_ms(&self) -> u32 {
+        self.durations.iter().sum()
+    }
+
+    /// Checks if the buzzer is on at a point in the pattern
+    ///
+    /// - param elapsed_ms: The milliseconds since the pattern started
+    ///
+    /// returns true if the buzzer should be sounding
+    pub fn is_on(&self, elapsed_ms: u32) -> bool {
+        let period = self.period_ms();
+        if period == 0 || (!self.repeats && elapsed_ms >= period) {
+            return false;
+        }
+
+        let mut time = elapsed_ms % period;
+        for (index, duration) in self.durations.iter().enumerate() {
+            if time < *duration {
+                // Even steps are on, odd steps are off
+                return index % 2 == 0;
+            }
+            time -= duration;
+        }
+        false
+    }
+}
+
+/// Plays a [BuzzerPattern] without holding up the main loop
+///
+/// - **pattern**: The pattern being played, or None if the buzzer is quiet
+/// - **elapsed_ms**: The milliseconds since the pattern started
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::buzzer::{BuzzerPlayer, FROST_PATTERN, WATERING_CHIRP};
+///
+/// let mut player = BuzzerPlayer::new();
+/// assert!(!player.tick());
+///
+/// player.play(WATERING_CHIRP);
+/// let beeps = (0..WATERING_CHIRP.period_ms()).filter(|_| player.tick()).count();
+/// assert_eq!(beeps, 100);
+/// assert!(!player.is_playing()); // It played once
+///
+/// player.play(FROST_PATTERN);
+/// for _ in 0..5000 {
+///     player.tick();
+/// }
+/// assert!(player.is_playing()); // It keeps going until stopped
+/// player.stop();
+/// assert!(!player.tick());
+/// ```
+pub struct BuzzerPlayer {
+    pattern: Option<BuzzerPattern>,
+    elapsed_ms: u32,
+}
+
+impl Default for BuzzerPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuzzerPlayer {
+    /// Creates a new, quiet instance of BuzzerPlayer
+    ///
+    /// returns a new instance of BuzzerPlayer
+    pub fn new() -> BuzzerPlayer {
+        Self {
+            pattern: None,
+            elapsed_ms: 0,
+        }
+    }
+
+    /// Starts playing a pattern from the beginning, in place of whatever was playing
+    ///
+    /// - param pattern: The [BuzzerPattern] to play
+    pub fn play(&mut self, pattern: BuzzerPattern) {
+        self.pattern = Some(pattern);
+        self.elapsed_ms = 0;
+    }
+
+    /// Stops playing
+    pub fn stop(&mut self) {
+        self.pattern = None;
+    }
+
+    /// Checks if a pattern is playing
+    ///
+    /// returns true until a pattern that plays once is over, or it is stopped
+    pub fn is_playing(&self) -> bool {
+        self.pattern.is_some()
+    }
+
+    /// Moves the pattern along
+    ///
+    /// **NOTE:** This function should be called every millisecond
+    ///
+    /// returns true if the buzzer should be sounding
+    pub fn tick(&mut self) -> bool {
+        let Some(pattern) = self.pattern else {
+            return false;
+        };
+        let on = pattern.is_on(self.elapsed_ms);
+        self.elapsed_ms += 1;
+        if self.elapsed_ms >= pattern.period_ms() {
+            if pattern.repeats {
+                self.elapsed_ms = 0;
+            } else {
+                self.pattern = None;
+            }
+        }
+        on
+    }
+}
+
+/// Plays a pattern once, waiting until it is over
+///
+/// **NOTE:** This holds up the caller, so it is only for when nothing else needs to run;
+/// use a [BuzzerPlayer] otherwise
+///
+/// - param buzzer: Buzzer Pin
+/// - param pattern: The [BuzzerPattern] to play
+/// - param delay: Delay instance
+pub fn play_pattern(
+    buzzer: &mut impl OutputPin,
+    pattern: &BuzzerPattern,
+    delay: &mut impl DelayNs,
+) {
+    for (index, duration) in pattern.durations.iter().enumerate() {
+        // Even steps are on, odd steps are off
+        if index % 2 == 0 {
+            buzzer.set_high().unwrap();
+        } else {
+            buzzer.set_low().unwrap();
+        }
+        delay.delay_ms(*duration);
+    }
+    buzzer.set_low().unwrap();
+}
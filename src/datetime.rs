@@ -0,0 +1,691 @@
+use heapless::String;
+use ufmt::uwrite;
+
+use crate::messages::{DATE_DMY, DATE_ISO, DATE_MDY, WEEKDAYS};
+use crate::preferences::inclusive_iterator;
+
+use panic_probe as _;
+
+/// The earliest year the clock can be set to; the DS3231 counts years from 2000
+pub const MIN_YEAR: u16 = 2000;
+/// The latest year the clock can be set to; the DS3231's century bit runs out after it
+pub const MAX_YEAR: u16 = 2199;
+/// The minutes in a day, as counted by [DateTime::minutes_of_day()]
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// How the time of day is displayed
+///
+/// - **TwentyFourHour**: `13:05:00`
+/// - **TwelveHour**: `1:05:00 PM`
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClockFormat {
+    TwentyFourHour,
+    TwelveHour,
+}
+
+/// How the date is displayed
+///
+/// - **Dmy**: `31/12/2024`
+/// - **Mdy**: `12/31/2024`
+/// - **Iso**: `2024-12-31`, as in ISO 8601
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateFormat {
+    Dmy,
+    Mdy,
+    Iso,
+}
+
+impl DateFormat {
+    /// Gets the format after this one, wrapping to the first
+    ///
+    /// returns the next DateFormat
+    pub fn next(self) -> DateFormat {
+        match self {
+            DateFormat::Dmy => DateFormat::Mdy,
+            DateFormat::Mdy => DateFormat::Iso,
+            DateFormat::Iso => DateFormat::Dmy,
+        }
+    }
+
+    /// Gets the format's name for the LCD
+    ///
+    /// returns the name; Ex: `D/M/Y`
+    pub fn name(self) -> &'static str {
+        match self {
+            DateFormat::Dmy => DATE_DMY,
+            DateFormat::Mdy => DATE_MDY,
+            DateFormat::Iso => DATE_ISO,
+        }
+    }
+}
+
+/// A day of the week
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::Weekday;
+///
+/// assert_eq!(Weekday::Monday.mask(), 0b000_0010);
+/// assert_eq!(Weekday::Saturday.name(), "Sat");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Weekday {
+    Sunday,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+impl Weekday {
+    /// Every day of the week, starting on Sunday like [DateTime::day_of_week()]
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Sunday,
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+    ];
+
+    /// Gets the day's bit in a set of weekdays, e.g. [crate::preferences::WateringWindow::days]
+    ///
+    /// returns the bit: Sunday = bit 0, Saturday = bit 6
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Gets the day's short name for the LCD
+    ///
+    /// returns the name; Ex: Mon
+    pub fn name(self) -> &'static str {
+        WEEKDAYS[self as usize]
+    }
+}
+
+/// A calendar date and time of day
+///
+/// - **second**: The second (0-59)
+/// - **minute**: The minute (0-59)
+/// - **hour**: The hour (0-23)
+/// - **day**: The day of the month, starting at 1
+/// - **month**: The month (1-12)
+/// - **year**: The year, from [MIN_YEAR] to [MAX_YEAR]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::DateTime;
+///
+/// let mut date = DateTime::new(2023, 12, 31, 23, 59, 59); // 23:59:59 Dec 31 2023
+/// date.tick(); // Roll over into the new year
+/// assert_eq!(date, DateTime::new(2024, 1, 1, 0, 0, 0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateTime {
+    pub second: u8,
+    pub minute: u8,
+    pub hour: u8,
+    pub day: u8,
+    pub month: u8,
+    pub year: u16,
+}
+
+impl Default for DateTime {
+    fn default() -> Self {
+        Self::new(2000, 1, 1, 0, 0, 0) // 00:00:00 Jan 1 2000
+    }
+}
+
+impl DateTime {
+    /// Creates a new instance of DateTime
+    ///
+    /// - param year: The year
+    /// - param month: The month (1-12)
+    /// - param day: The day (1-31)
+    /// - param hour: The hour (0-23)
+    /// - param minute: The minute (0-59)
+    /// - param second: The second (0-59)
+    ///
+    /// returns a new instance of DateTime
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+        Self {
+            second,
+            minute,
+            hour,
+            day,
+            month,
+            year,
+        }
+    }
+
+    /// Checks that every field is within its range
+    ///
+    /// returns true if the date and time exist, and the year is from [MIN_YEAR] to [MAX_YEAR]
+    pub fn is_valid(&self) -> bool {
+        (MIN_YEAR..=MAX_YEAR).contains(&self.year)
+            && self.second <= 59
+            && self.minute <= 59
+            && self.hour <= 23
+            && (1..=12).contains(&self.month)
+            && self.day != 0
+            && self.day <= self.days_in_month()
+    }
+
+    /// Clamps every field to the nearest valid value
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// let mut date = DateTime::new(2023, 2, 30, 25, 0, 0); // Hour 25, Feb 30
+    /// assert!(!date.is_valid());
+    /// date.normalize();
+    /// assert_eq!(date, DateTime::new(2023, 2, 28, 23, 0, 0));
+    ///
+    /// let mut date = DateTime::new(0, 2, 29, 0, 0, 0); // A year that wrapped around
+    /// date.normalize();
+    /// assert_eq!(date, DateTime::new(2000, 2, 29, 0, 0, 0));
+    /// ```
+    pub fn normalize(&mut self) {
+        self.year = self.year.clamp(MIN_YEAR, MAX_YEAR);
+        self.second = self.second.min(59);
+        self.minute = self.minute.min(59);
+        self.hour = self.hour.min(23);
+        self.month = self.month.clamp(1, 12);
+        self.day = self.day.clamp(1, self.days_in_month());
+    }
+
+    /// Increments the time by 1 second, rolling over minutes, hours, days, months, and years
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// let mut date = DateTime::new(2024, 2, 28, 23, 59, 59);
+    /// date.tick();
+    /// assert_eq!(date, DateTime::new(2024, 2, 29, 0, 0, 0)); // Leap day
+    ///
+    /// let mut date = DateTime::new(2023, 2, 28, 23, 59, 59);
+    /// date.tick();
+    /// assert_eq!(date, DateTime::new(2023, 3, 1, 0, 0, 0));
+    ///
+    /// let mut date = DateTime::new(2024, 4, 30, 12, 59, 59);
+    /// date.tick();
+    /// assert_eq!(date, DateTime::new(2024, 4, 30, 13, 0, 0)); // No day rollover
+    /// date.hour = 23;
+    /// date.minute = 59;
+    /// date.second = 59;
+    /// date.tick();
+    /// assert_eq!(date, DateTime::new(2024, 5, 1, 0, 0, 0)); // 30-day month
+    /// ```
+    pub fn tick(&mut self) {
+        self.tick_by(1);
+    }
+
+    /// Advances the time by any amount of seconds, rolling over minutes, hours, days, months, and years.
+    /// This lets the clock catch up after the main loop stalls
+    ///
+    /// - param seconds: The amount of seconds to advance by
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// let mut date = DateTime::new(2024, 1, 31, 23, 59, 30);
+    /// date.tick_by(45);
+    /// assert_eq!(date, DateTime::new(2024, 2, 1, 0, 0, 15)); // Month boundary
+    ///
+    /// date.tick_by(28 * 86_400); // 28 days
+    /// assert_eq!(date, DateTime::new(2024, 2, 29, 0, 0, 15)); // Leap day
+    ///
+    /// let mut date = DateTime::new(2023, 12, 31, 12, 0, 0);
+    /// date.tick_by(12 * 3600 + 60 * 86_400); // Half a day, then 60 days
+    /// assert_eq!(date, DateTime::new(2024, 3, 1, 0, 0, 0)); // Year boundary, through Feb 29
+    ///
+    /// let mut date = DateTime::new(2000, 1, 1, 0, 0, 0);
+    /// date.tick_by(366 * 86_400 + 365 * 86_400); // 2000 was a leap year
+    /// assert_eq!(date, DateTime::new(2002, 1, 1, 0, 0, 0));
+    ///
+    /// // The clock stops at the end of the last year rather than wrapping
+    /// let mut date = DateTime::new(2199, 12, 31, 23, 59, 0);
+    /// date.tick_by(120);
+    /// assert_eq!(date, DateTime::new(2199, 12, 31, 23, 59, 59));
+    /// ```
+    pub fn tick_by(&mut self, seconds: u32) {
+        // Carry each unit into the next
+        let seconds = self.second as u32 + seconds;
+        self.second = (seconds % 60) as u8;
+        let minutes = self.minute as u32 + seconds / 60;
+        self.minute = (minutes % 60) as u8;
+        let hours = self.hour as u32 + minutes / 60;
+        self.hour = (hours % 24) as u8;
+        let mut days = hours / 24;
+
+        // Handle month and year rollovers
+        while days > 0 {
+            let days_left = (self.days_in_month() - self.day) as u32;
+            if days <= days_left {
+                self.day += days as u8;
+                break;
+            }
+            // Move to the first day of the next month
+            days -= days_left + 1;
+            self.day = 1;
+            self.month += 1;
+            if self.month > 12 {
+                if self.year >= MAX_YEAR {
+                    // Hold at the last second that can be kept
+                    *self = DateTime::new(MAX_YEAR, 12, 31, 23, 59, 59);
+                    return;
+                }
+                self.month = 1;
+                self.year += 1;
+            }
+        }
+    }
+
+    /// Moves the time one hour forwards or backwards, rolling over days, months, and years
+    ///
+    /// - param forward: Whether to move forwards; If false, move backwards
+    ///
+    /// returns the shifted DateTime
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// let date = DateTime::new(2024, 1, 1, 0, 30, 0);
+    /// assert_eq!(date.shift_hour(false), DateTime::new(2023, 12, 31, 23, 30, 0));
+    /// assert_eq!(date.shift_hour(false).shift_hour(true), date);
+    /// ```
+    pub fn shift_hour(&self, forward: bool) -> DateTime {
+        let mut date = *self;
+        if forward {
+            date.hour += 1;
+            if date.hour >= 24 {
+                date.hour = 0;
+                date.day += 1;
+                if date.day > date.days_in_month() {
+                    date.day = 1;
+                    date.month += 1;
+                    if date.month > 12 {
+                        date.month = 1;
+                        date.year += 1;
+                    }
+                }
+            }
+        } else if date.hour > 0 {
+            date.hour -= 1;
+        } else {
+            date.hour = 23;
+            date.day -= 1;
+            if date.day == 0 {
+                date.month -= 1;
+                if date.month == 0 {
+                    date.month = 12;
+                    date.year -= 1;
+                }
+                date.day = date.days_in_month();
+            }
+        }
+        date
+    }
+
+    /// Formats the date as `HH:MM:SS` and `DD/MM/YYYY`.
+    /// With [ClockFormat::TwelveHour] the time is `H:MM:SS AM` instead
+    /// Days and months are stored 1-based, so they are printed as-is
+    ///
+    /// - param clock_format: The [ClockFormat] to display the time in
+    /// - param date_format: The [DateFormat] to display the date in
+    ///
+    /// returns: `(HH:MM:SS, DD/MM/YYYY)`, with the date ordered by the [DateFormat]
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{ClockFormat, DateFormat, DateTime};
+    ///
+    /// let mut date = DateTime::new(2024, 1, 1, 0, 0, 0);
+    /// let (time, day) = date.format(ClockFormat::TwentyFourHour, DateFormat::Dmy);
+    /// assert_eq!(time.as_str(), "00:00:00");
+    /// assert_eq!(day.as_str(), "01/01/2024");
+    /// let old = DateTime::new(999, 1, 1, 0, 0, 0);
+    /// assert_eq!(old.format(ClockFormat::TwentyFourHour, DateFormat::Dmy).1.as_str(), "01/01/0999");
+    ///
+    /// assert_eq!(date.format(ClockFormat::TwelveHour, DateFormat::Dmy).0.as_str(), "12:00:00 AM"); // Midnight
+    /// date.hour = 12;
+    /// assert_eq!(date.format(ClockFormat::TwelveHour, DateFormat::Dmy).0.as_str(), "12:00:00 PM"); // Noon
+    /// date.hour = 13;
+    /// assert_eq!(date.format(ClockFormat::TwelveHour, DateFormat::Dmy).0.as_str(), "1:00:00 PM");
+    ///
+    /// // Each date format
+    /// let date = DateTime::new(2024, 12, 31, 23, 59, 59);
+    /// assert_eq!(date.format(ClockFormat::TwentyFourHour, DateFormat::Dmy).1.as_str(), "31/12/2024");
+    /// assert_eq!(date.format(ClockFormat::TwentyFourHour, DateFormat::Mdy).1.as_str(), "12/31/2024");
+    /// assert_eq!(date.format(ClockFormat::TwentyFourHour, DateFormat::Iso).1.as_str(), "2024-12-31");
+    /// ```
+    pub fn format(
+        &self,
+        clock_format: ClockFormat,
+        date_format: DateFormat,
+    ) -> (String<11>, String<10>) {
+        // Format the date as a string
+        let mut val1: String<11> = String::new();
+        let mut val2: String<10> = String::new();
+        // Format time
+        match clock_format {
+            ClockFormat::TwentyFourHour => {
+                uwrite!(
+                    &mut val1,
+                    "{}:{}:{}",
+                    pad_number(self.hour).as_str(),
+                    pad_number(self.minute).as_str(),
+                    pad_number(self.second).as_str(),
+                )
+                .unwrap();
+            }
+            ClockFormat::TwelveHour => {
+                let (hour, suffix) = match self.hour {
+                    0 => (12, "AM"),
+                    1..=11 => (self.hour, "AM"),
+                    12 => (12, "PM"),
+                    _ => (self.hour - 12, "PM"),
+                };
+                uwrite!(
+                    &mut val1,
+                    "{}:{}:{} {}",
+                    hour,
+                    pad_number(self.minute).as_str(),
+                    pad_number(self.second).as_str(),
+                    suffix,
+                )
+                .unwrap();
+            }
+        }
+
+        // Format date
+        let day = pad_number(self.day);
+        let month = pad_number(self.month);
+        let year = pad_to::<4>(self.year);
+        match date_format {
+            DateFormat::Dmy => uwrite!(
+                &mut val2,
+                "{}/{}/{}",
+                day.as_str(),
+                month.as_str(),
+                year.as_str()
+            ),
+            DateFormat::Mdy => uwrite!(
+                &mut val2,
+                "{}/{}/{}",
+                month.as_str(),
+                day.as_str(),
+                year.as_str()
+            ),
+            DateFormat::Iso => uwrite!(
+                &mut val2,
+                "{}-{}-{}",
+                year.as_str(),
+                month.as_str(),
+                day.as_str()
+            ),
+        }
+        .unwrap();
+
+        (val1, val2)
+    }
+
+    /// Formats the date and time as ISO 8601, whatever the display settings are,
+    /// so logs and bridges can parse it
+    ///
+    /// returns: `YYYY-MM-DDTHH:MM:SS`
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// let date = DateTime::new(2024, 3, 9, 7, 5, 0);
+    /// assert_eq!(date.format_iso().as_str(), "2024-03-09T07:05:00");
+    /// ```
+    pub fn format_iso(&self) -> String<19> {
+        let mut str: String<19> = String::new();
+        uwrite!(
+            &mut str,
+            "{}-{}-{}T{}:{}:{}",
+            pad_to::<4>(self.year).as_str(),
+            pad_number(self.month).as_str(),
+            pad_number(self.day).as_str(),
+            pad_number(self.hour).as_str(),
+            pad_number(self.minute).as_str(),
+            pad_number(self.second).as_str(),
+        )
+        .unwrap();
+        str
+    }
+
+    /// Gets the next index for the current day depending on the month and leap year
+    ///
+    /// - param increment: If the values are incrementing (not decrementing)
+    ///
+    /// returns the next day's index
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// let date = DateTime::new(2024, 2, 29, 0, 0, 0); // Feb 29 2024
+    /// assert_eq!(date.change_days(true), 1); // Wraps using days_in_month
+    /// ```
+    pub fn change_days(&self, increment: bool) -> u8 {
+        inclusive_iterator(self.day, 1, self.days_in_month(), increment)
+    }
+
+    /// Gets the time of day as minutes since midnight, for comparing against schedules
+    ///
+    /// returns the minutes since midnight (0-1439)
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// assert_eq!(DateTime::new(2024, 5, 31, 0, 0, 59).minutes_of_day(), 0); // Midnight
+    /// assert_eq!(DateTime::new(2024, 5, 31, 12, 0, 0).minutes_of_day(), 720); // Noon
+    /// assert_eq!(DateTime::new(2024, 5, 31, 23, 59, 0).minutes_of_day(), 1439);
+    /// ```
+    pub fn minutes_of_day(&self) -> u16 {
+        time_to_minutes((self.minute, self.hour))
+    }
+
+    /// Gets the time of day as seconds since midnight
+    ///
+    /// returns the seconds since midnight (0-86399)
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// assert_eq!(DateTime::new(2024, 5, 31, 0, 0, 0).seconds_of_day(), 0); // Midnight
+    /// assert_eq!(DateTime::new(2024, 5, 31, 12, 0, 30).seconds_of_day(), 43_230);
+    /// assert_eq!(DateTime::new(2024, 5, 31, 23, 59, 59).seconds_of_day(), 86_399);
+    /// ```
+    pub fn seconds_of_day(&self) -> u32 {
+        self.minutes_of_day() as u32 * 60 + self.second as u32
+    }
+
+    /// Gets the amount of days in the current month
+    ///
+    /// returns the amount of days in the month
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    ///
+    /// let mut date = DateTime::new(2024, 2, 1, 0, 0, 0); // February in a leap year
+    /// assert_eq!(date.days_in_month(), 29);
+    /// date.year = 1900; // Not a leap year
+    /// assert_eq!(date.days_in_month(), 28);
+    /// ```
+    pub fn days_in_month(&self) -> u8 {
+        days_in(self.month, self.year)
+    }
+
+    /// Calculates the day of the week using Sakamoto's method
+    ///
+    /// returns the day of the week: 0 = Sunday, 6 = Saturday
+    pub fn day_of_week(&self) -> u8 {
+        const OFFSETS: [u16; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let year = if self.month < 3 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        ((year + year / 4 - year / 100
+            + year / 400
+            + OFFSETS[self.month as usize - 1]
+            + self.day as u16)
+            % 7) as u8
+    }
+
+    /// Gets the day of the week
+    ///
+    /// returns the [Weekday]
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{DateTime, Weekday};
+    ///
+    /// assert_eq!(DateTime::new(2023, 12, 25, 0, 0, 0).weekday(), Weekday::Monday);
+    /// assert_eq!(DateTime::new(2024, 2, 29, 0, 0, 0).weekday(), Weekday::Thursday); // Leap day
+    /// assert_eq!(DateTime::new(2000, 2, 29, 0, 0, 0).weekday(), Weekday::Tuesday); // 2000 is a leap year
+    /// assert_eq!(DateTime::new(1900, 2, 28, 0, 0, 0).weekday(), Weekday::Wednesday);
+    /// assert_eq!(DateTime::new(1900, 3, 1, 0, 0, 0).weekday(), Weekday::Thursday); // 1900 is not
+    /// assert_eq!(DateTime::new(1999, 12, 31, 0, 0, 0).weekday(), Weekday::Friday);
+    /// assert_eq!(DateTime::new(2000, 1, 1, 0, 0, 0).weekday(), Weekday::Saturday); // New century
+    /// assert_eq!(DateTime::new(2100, 3, 1, 0, 0, 0).weekday(), Weekday::Monday);
+    /// ```
+    pub fn weekday(&self) -> Weekday {
+        Weekday::ALL[self.day_of_week() as usize]
+    }
+
+    /// Calculates if it is leap year
+    ///
+    /// - param year: The current year
+    ///
+    /// returns if the year is leap year
+    pub fn is_leap_year(year: u16) -> bool {
+        year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+    }
+}
+
+/// Finds the day of a month's nth Sunday
+///
+/// - param week: Which Sunday: 1-4, or 5 for the last Sunday
+/// - param month: The month (1-12)
+/// - param year: The year
+///
+/// returns the day of the month
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::nth_sunday;
+///
+/// assert_eq!(nth_sunday(2, 3, 2024), 10); // Second Sunday of March 2024
+/// assert_eq!(nth_sunday(5, 10, 2024), 27); // Last Sunday of October 2024
+/// ```
+pub fn nth_sunday(week: u8, month: u8, year: u16) -> u8 {
+    let first_day = DateTime::new(year, month, 1, 0, 0, 0).day_of_week();
+    let first_sunday = 1 + (7 - first_day) % 7;
+    let day = first_sunday + 7 * (week.clamp(1, 5) - 1);
+    if day > days_in(month, year) {
+        day - 7
+    } else {
+        day
+    }
+}
+
+/// Converts a scheduled time to minutes since midnight, like [DateTime::minutes_of_day()]
+///
+/// - param time: The time as stored in schedules: Min, Hour
+///
+/// returns the minutes since midnight
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::time_to_minutes;
+///
+/// assert_eq!(time_to_minutes((30, 6)), 390); // 06:30
+/// ```
+pub fn time_to_minutes(time: (u8, u8)) -> u16 {
+    time.1 as u16 * 60 + time.0 as u16
+}
+
+/// Pads a number with a zero before it if < 10
+///
+/// - param num: number to be padded
+///
+/// returns: [String] with formatted value
+pub fn pad_number(num: u8) -> String<5> {
+    pad_to::<2>(num as u16)
+}
+
+/// Pads a number with zeros before it until it is `WIDTH` digits long.
+/// Numbers that are already `WIDTH` digits or longer are left as-is
+///
+/// **NOTE: `WIDTH` must be at most 5, the digits in [u16::MAX]; wider widths do not compile**
+///
+/// - param num: number to be padded
+///
+/// returns: [String] with formatted value
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::pad_to;
+///
+/// assert_eq!(pad_to::<4>(7).as_str(), "0007");        // Single digit
+/// assert_eq!(pad_to::<4>(2024).as_str(), "2024");     // Exactly the width
+/// assert_eq!(pad_to::<2>(12345).as_str(), "12345");   // Over the width
+/// assert_eq!(pad_to::<2>(0).as_str(), "00");
+/// ```
+///
+/// A width that does not fit is caught when building:
+/// ```compile_fail
+/// gem_rs::datetime::pad_to::<6>(7);
+/// ```
+pub fn pad_to<const WIDTH: usize>(num: u16) -> String<5> {
+    const { assert!(WIDTH <= 5, "pad_to only fits 5 digits") };
+    let mut padded = String::new();
+    let mut digits = 1;
+    let mut rest = num / 10;
+    while rest > 0 {
+        digits += 1;
+        rest /= 10;
+    }
+    for _ in digits..WIDTH {
+        padded.push('0').unwrap();
+    }
+    uwrite!(padded, "{}", num).unwrap();
+    padded
+}
+
+/// Gets the amount of days in a month
+///
+/// - param month: The month (1-12)
+/// - param year: The year
+///
+/// returns the amount of days in the month
+fn days_in(month: u8, year: u16) -> u8 {
+    match month {
+        2 => {
+            // Feb
+            if DateTime::is_leap_year(year) {
+                29
+            } else {
+                28
+            }
+        }
+        4 | 6 | 9 | 11 => 30, // Apr, Jun, Sep, Nov
+        _ => 31,              // Other months
+    }
+}
@@ -0,0 +1,907 @@
+use crate::datetime::{ClockFormat, DateTime, Weekday, MAX_YEAR, MIN_YEAR};
+use crate::overrides::{cycle_override, Overrides};
+use crate::preferences::{
+    inclusive_iterator, DstRule, LightWindow, Preferences, MAX_AIR_DROP, MAX_AMBIENT_THRESHOLD,
+    MAX_AUTO_CYCLE, MAX_BATTERY_DIVIDER, MAX_GAS_WARMUP_MINUTES, MAX_HUMIDITY_OFFSET,
+    MAX_HYSTERESIS_MARGIN, MAX_LOW_BATTERY, MAX_SMOOTHING, MAX_TEMP_OFFSET, MAX_VPD,
+    MAX_WATERING_DURATION, MAX_WATERING_WINDOWS, MIN_AIR_DROP, MIN_AMBIENT_THRESHOLD,
+    MIN_AUTO_CYCLE, MIN_BATTERY_DIVIDER, SENSOR_INTERVALS,
+};
+use crate::screen::Screen;
+use crate::sensors::PressureUnit;
+use crate::soil::DEFAULT_CALIBRATION;
+
+use panic_probe as _;
+
+/// The parts of the date edited from [Screen::Date], in the order they are edited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateField {
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+    ClockFormat,
+    DateFormat,
+    Dst,
+}
+
+impl DateField {
+    /// Gets the field edited after this one
+    ///
+    /// returns the next DateField, or None after the last one
+    pub fn next(self) -> Option<DateField> {
+        match self {
+            DateField::Minute => Some(DateField::Hour),
+            DateField::Hour => Some(DateField::Day),
+            DateField::Day => Some(DateField::Month),
+            DateField::Month => Some(DateField::Year),
+            DateField::Year => Some(DateField::ClockFormat),
+            DateField::ClockFormat => Some(DateField::DateFormat),
+            DateField::DateFormat => Some(DateField::Dst),
+            DateField::Dst => None,
+        }
+    }
+}
+
+/// The setting being edited. The main loop advances it one button press at a time, so the
+/// sensors are still read and the smoke detector still watched while a setting is changed
+///
+/// - **Temperature**, **Humidity**, **SoilRange**, **VpdRange**: A range; `lower` is whether its lower bound is selected
+/// - **TemperatureAlarm**, **HumidityAlarm**: The alarm bounds edited after the range, like a range;
+///   see [Preferences::temperature_alarm]
+/// - **Date**: A [DateField] of a copy of the local date, which is applied after the last field
+/// - **WateringPick**: Which watering window to edit, up to `last`; the slot after the last window adds one
+/// - **WateringTime**: One of a watering window's times: start hour, start minute, end hour, end minute
+/// - **WateringDays**: Whether a watering window runs on a day of the week: 0 = Sunday
+/// - **WateringDuration**: How long to water at the start of each window
+/// - **PollInterval**: The index of the sensor poll interval in [SENSOR_INTERVALS]
+/// - **PressureUnit**: The [PressureUnit] pressure is shown in
+/// - **Override**: One of the [Overrides]: vent, sprinklers, heater, buzzer
+/// - **Margin**: One of the [crate::preferences::HysteresisMargins]: vent, sprinklers, heater
+/// - **Battery**: One of the [crate::preferences::BatterySettings]: low battery threshold, divider, power saving
+/// - **AirVenting**: How far the gas resistance may drop before the vent opens; see [Preferences::air_venting]
+/// - **TempOffset**, **HumidityOffset**, **Smoothing**, **GasWarmup**: The sensor calibration
+/// - **SoilSensor**, **LightSchedule**: Whether the soil sensor or grow light is used
+/// - **SoilPoint**: The raw soil sensor readings: Dry, Wet. `wet` is whether the wet one is being taken
+/// - **LightTime**: One of the grow light's times, like [EditState::WateringTime]; applied after the last one
+/// - **AmbientThreshold**: One of the [crate::preferences::AmbientSettings] thresholds: backlight, grow light
+/// - **AmbientPoint**: The raw light sensor readings: Dark, Bright, taken after the thresholds.
+///   `lit` is whether the bright one is being taken
+/// - **Feature**: One of the optional features: watering, soil sensor, grow light
+/// - **AutoCycle**: How long each screen is shown for while they cycle on their own, edited after the features;
+///   see [Preferences::auto_cycle_seconds]
+/// - **Reset**: Whether resetting every setting was confirmed
+/// - **History**: Which watering event is shown, up to `last`; 0 is the newest. Nothing is edited;
+///   see [EditState::history()]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::edit::EditState;
+/// use gem_rs::overrides::Overrides;
+/// use gem_rs::preferences::Preferences;
+/// use gem_rs::screen::Screen;
+///
+/// let mut preferences = Preferences::default(); // 60-80°F
+/// let mut overrides = Overrides::default();
+/// let mut watering_index = 0;
+///
+/// let mut edit = EditState::start(Screen::Temperature, &preferences, watering_index).unwrap();
+/// edit.adjust(false, &mut preferences, &mut overrides); // DOWN
+/// edit = edit.select(&mut preferences, &mut watering_index).unwrap(); // On to the upper bound
+/// assert_eq!(edit, EditState::Temperature { lower: false });
+/// edit.adjust(true, &mut preferences, &mut overrides); // UP
+/// edit.adjust(true, &mut preferences, &mut overrides);
+/// assert_eq!(preferences.temperature, (59, 82));
+///
+/// // The alarm bounds come after the range, and are kept apart from it
+/// edit = edit.select(&mut preferences, &mut watering_index).unwrap();
+/// assert_eq!(edit, EditState::TemperatureAlarm { lower: true });
+/// edit = edit.select(&mut preferences, &mut watering_index).unwrap();
+/// edit.adjust(true, &mut preferences, &mut overrides);
+/// assert!(edit.select(&mut preferences, &mut watering_index).is_none()); // Done
+/// assert_eq!(preferences.temperature_alarm, (32, 96));
+/// assert_eq!(preferences.temperature, (59, 82));
+///
+/// // Screens without settings have nothing to edit
+/// assert!(EditState::start(Screen::Stats, &preferences, watering_index).is_none());
+/// ```
+///
+/// Driven by the buttons, with mocked pins and delays:
+/// ```rust
+/// use embedded_hal_mock::eh1::delay::NoopDelay;
+/// use embedded_hal_mock::eh1::digital::{Mock, State, Transaction};
+/// use gem_rs::buttons::{is_pressed, DEBOUNCE_SAMPLES};
+/// use gem_rs::edit::EditState;
+/// use gem_rs::overrides::Overrides;
+/// use gem_rs::preferences::Preferences;
+/// use gem_rs::screen::Screen;
+///
+/// // A button held through every sample, or one that reads released straight away
+/// fn button(held: bool) -> Mock {
+///     if held {
+///         Mock::new(&vec![Transaction::get(State::High); DEBOUNCE_SAMPLES as usize])
+///     } else {
+///         Mock::new(&[Transaction::get(State::Low)])
+///     }
+/// }
+///
+/// let mut delay = NoopDelay::new();
+/// let mut preferences = Preferences::default(); // 60-80°F
+/// let mut overrides = Overrides::default();
+/// let mut watering_index = 0;
+/// let mut edit = EditState::start(Screen::Temperature, &preferences, watering_index);
+///
+/// // UP, SELECT, DOWN, DOWN, SELECT
+/// let presses = [(true, false, false), (false, false, true), (false, true, false)];
+/// for (up, down, select) in [presses[0], presses[1], presses[2], presses[2], presses[1]] {
+///     let (mut up, mut down, mut select) = (button(up), button(down), button(select));
+///     let state = edit.as_mut().unwrap();
+///     if is_pressed(&mut up, &mut delay) {
+///         state.adjust(true, &mut preferences, &mut overrides);
+///     }
+///     if is_pressed(&mut down, &mut delay) {
+///         state.adjust(false, &mut preferences, &mut overrides);
+///     }
+///     if is_pressed(&mut select, &mut delay) {
+///         edit = state.select(&mut preferences, &mut watering_index);
+///     }
+///     up.done();
+///     down.done();
+///     select.done();
+/// }
+/// assert_eq!(preferences.temperature, (61, 78));
+/// assert_eq!(edit, Some(EditState::TemperatureAlarm { lower: true }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditState {
+    Temperature { lower: bool },
+    Humidity { lower: bool },
+    TemperatureAlarm { lower: bool },
+    HumidityAlarm { lower: bool },
+    Date { field: DateField, date: DateTime },
+    WateringPick { index: u8, last: u8 },
+    WateringTime { window: usize, field: u8 },
+    WateringDays { window: usize, day: u8 },
+    WateringDuration { window: usize },
+    PollInterval { index: u8 },
+    PressureUnit,
+    Override { field: u8 },
+    Margin { field: u8 },
+    Battery { field: u8 },
+    AirVenting,
+    TempOffset,
+    HumidityOffset,
+    Smoothing,
+    GasWarmup,
+    SoilSensor { enabled: bool },
+    SoilPoint { wet: bool, calibration: (u16, u16) },
+    SoilRange { lower: bool },
+    VpdRange { lower: bool },
+    LightSchedule { enabled: bool },
+    LightTime { field: u8, window: LightWindow },
+    AmbientThreshold { field: u8 },
+    AmbientPoint { lit: bool, calibration: (u16, u16) },
+    Feature { field: u8 },
+    AutoCycle,
+    Reset { confirmed: bool },
+    History { entry: u8, last: u8 },
+}
+
+impl EditState {
+    /// Starts editing a screen's settings, for when SELECT is pressed
+    ///
+    /// - param screen: The [Screen] being shown
+    /// - param preferences: [Preferences] instance
+    /// - param watering_index: The watering window being shown
+    ///
+    /// returns the first EditState, or None if the screen has no settings
+    pub fn start(
+        screen: Screen,
+        preferences: &Preferences,
+        watering_index: usize,
+    ) -> Option<EditState> {
+        let state = match screen {
+            Screen::Temperature => EditState::Temperature { lower: true },
+            Screen::Humidity => EditState::Humidity { lower: true },
+            Screen::Vpd => EditState::VpdRange { lower: true },
+            // Edit in local time; it is converted back to standard time at the end
+            Screen::Date => EditState::Date {
+                field: DateField::Minute,
+                date: preferences.local_date(),
+            },
+            Screen::Watering => {
+                let last = preferences.watering.len().min(MAX_WATERING_WINDOWS - 1) as u8;
+                EditState::WateringPick {
+                    index: (watering_index as u8).min(last),
+                    last,
+                }
+            }
+            Screen::Pressure => EditState::PollInterval {
+                index: SENSOR_INTERVALS
+                    .iter()
+                    .position(|interval| *interval == preferences.sensor_interval_ms)
+                    .unwrap_or(1) as u8,
+            },
+            Screen::Overrides => EditState::Override { field: 0 },
+            Screen::Margins => EditState::Margin { field: 0 },
+            Screen::Battery => EditState::Battery { field: 0 },
+            Screen::AirQuality => EditState::AirVenting,
+            Screen::Calibration => EditState::TempOffset,
+            Screen::Soil => EditState::SoilSensor {
+                enabled: preferences.soil_calibration.is_some(),
+            },
+            Screen::Light => EditState::LightSchedule {
+                enabled: preferences.light.is_some(),
+            },
+            Screen::Ambient => EditState::AmbientThreshold { field: 0 },
+            Screen::Features => EditState::Feature { field: 0 },
+            Screen::Reset => EditState::Reset { confirmed: false },
+            // SELECT switches zones on the zone screen, switches readings on the graph screen, and pages
+            // through the watering history on the history screen with EditState::history().
+            // The rest have no configuration
+            Screen::Zone
+            | Screen::Graph
+            | Screen::History
+            | Screen::DewPoint
+            | Screen::Sensors
+            | Screen::Stats
+            | Screen::Uptime
+            | Screen::Memory
+            | Screen::Diagnostics => return None,
+        };
+        Some(state)
+    }
+
+    /// Starts paging through a zone's watering history, for when SELECT is pressed on [Screen::History]
+    ///
+    /// - param events: How many events the zone's [crate::history::WateringHistory] has
+    ///
+    /// returns the newest event's EditState, or None if the sprinklers have not run yet
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::edit::EditState;
+    /// use gem_rs::overrides::Overrides;
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// let mut overrides = Overrides::default();
+    /// assert!(EditState::history(0).is_none());
+    ///
+    /// let mut edit = EditState::history(3).unwrap();
+    /// edit.adjust(true, &mut preferences, &mut overrides); // UP goes back in time
+    /// assert_eq!(edit, EditState::History { entry: 1, last: 2 });
+    /// assert!(edit.select(&mut preferences, &mut 0).is_none());
+    /// ```
+    pub fn history(events: usize) -> Option<EditState> {
+        let last = events.checked_sub(1)?;
+        Some(EditState::History {
+            entry: 0,
+            last: last as u8,
+        })
+    }
+
+    /// Changes the value being edited, for when UP or DOWN is pressed.
+    /// Settings that are on or off are flipped by either button
+    ///
+    /// - param up: true for UP, false for DOWN
+    /// - param preferences: [Preferences] instance
+    /// - param overrides: The manual [Overrides]
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{DateTime, MIN_YEAR};
+    /// use gem_rs::edit::{DateField, EditState};
+    /// use gem_rs::overrides::Overrides;
+    /// use gem_rs::preferences::Preferences;
+    /// use gem_rs::screen::Screen;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// let mut overrides = Overrides::default();
+    ///
+    /// // The date is edited on a copy, which wraps within each field
+    /// let mut edit = EditState::start(Screen::Date, &preferences, 0).unwrap();
+    /// let minute = preferences.date.minute;
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.date.minute, minute); // Not applied yet
+    /// let EditState::Date { field: DateField::Minute, date } = edit else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(date.minute, (minute + 1) % 60);
+    ///
+    /// // Feb 29 becomes Feb 28 outside of leap years
+    /// let field = DateField::Year;
+    /// let mut edit = EditState::Date { field, date: DateTime::new(2024, 2, 29, 0, 0, 0) };
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert_eq!(edit, EditState::Date { field, date: DateTime::new(2025, 2, 28, 0, 0, 0) });
+    ///
+    /// // The year stops at the ends of its range rather than wrapping
+    /// let first = DateTime::new(MIN_YEAR, 1, 1, 0, 0, 0);
+    /// let mut edit = EditState::Date { field, date: first };
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// assert_eq!(edit, EditState::Date { field, date: first });
+    ///
+    /// // Overrides cycle between automatic and forced off or on
+    /// let mut edit = EditState::start(Screen::Overrides, &preferences, 0).unwrap();
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// assert_eq!(overrides.vent, Some(true));
+    ///
+    /// // Margins stop at 0, which switches right at the threshold
+    /// let mut edit = EditState::start(Screen::Margins, &preferences, 0).unwrap();
+    /// for _ in 0..5 {
+    ///     edit.adjust(false, &mut preferences, &mut overrides);
+    /// }
+    /// assert_eq!(preferences.margins.vent, 0);
+    ///
+    /// // The low battery threshold moves in steps of 0.05V
+    /// let mut edit = EditState::start(Screen::Battery, &preferences, 0).unwrap();
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.battery.low_voltage, 345);
+    ///
+    /// // Air venting starts off, and turns off again below its smallest threshold
+    /// let mut edit = EditState::start(Screen::AirQuality, &preferences, 0).unwrap();
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.air_venting, Some(10));
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.air_venting, None);
+    ///
+    /// // Brightness thresholds start off, like air venting
+    /// let mut edit = EditState::start(Screen::Ambient, &preferences, 0).unwrap();
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.ambient.backlight_dark, Some(10));
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.ambient.backlight_dark, None);
+    ///
+    /// // The screens cycle on their own from the shortest time up, and stop again below it
+    /// let mut edit = EditState::AutoCycle;
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.auto_cycle_seconds, Some(3));
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// edit.adjust(false, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.auto_cycle_seconds, None);
+    /// ```
+    pub fn adjust(&mut self, up: bool, preferences: &mut Preferences, overrides: &mut Overrides) {
+        match self {
+            EditState::Temperature { lower } => {
+                let (low, high) = &mut preferences.temperature;
+                step_bound(if *lower { low } else { high }, up);
+            }
+            EditState::Humidity { lower } => {
+                let (low, high) = &mut preferences.humidity;
+                step_bound(if *lower { low } else { high }, up);
+            }
+            EditState::TemperatureAlarm { lower } => {
+                let (low, high) = &mut preferences.temperature_alarm;
+                step_bound(if *lower { low } else { high }, up);
+            }
+            EditState::HumidityAlarm { lower } => {
+                let (low, high) = &mut preferences.humidity_alarm;
+                step_bound(if *lower { low } else { high }, up);
+            }
+            EditState::SoilRange { lower } => {
+                let (low, high) = &mut preferences.soil_moisture;
+                step_bound(if *lower { low } else { high }, up);
+            }
+            EditState::VpdRange { lower } => {
+                let (low, high) = &mut preferences.vpd;
+                let bound = if *lower { low } else { high };
+                *bound = if up {
+                    (*bound + VPD_STEP).min(MAX_VPD)
+                } else {
+                    bound.saturating_sub(VPD_STEP)
+                };
+            }
+            EditState::Date { field, date } => match field {
+                DateField::Minute => date.minute = inclusive_iterator(date.minute, 0, 59, up),
+                DateField::Hour => date.hour = inclusive_iterator(date.hour, 0, 23, up),
+                DateField::Day => {
+                    date.day = inclusive_iterator(date.day, 1, date.days_in_month(), up)
+                }
+                DateField::Month => {
+                    date.month = inclusive_iterator(date.month, 1, 12, up);
+                    // The day may not exist in the new month
+                    date.day = date.day.min(date.days_in_month());
+                }
+                DateField::Year => {
+                    // Stop at the ends rather than wrapping, so holding a button can't skip centuries
+                    date.year = if up {
+                        date.year.saturating_add(1).min(MAX_YEAR)
+                    } else {
+                        date.year.saturating_sub(1).max(MIN_YEAR)
+                    };
+                    // Feb 29 does not exist outside of leap years
+                    date.day = date.day.min(date.days_in_month());
+                }
+                DateField::ClockFormat => {
+                    preferences.clock_format = match preferences.clock_format {
+                        ClockFormat::TwentyFourHour => ClockFormat::TwelveHour,
+                        ClockFormat::TwelveHour => ClockFormat::TwentyFourHour,
+                    }
+                }
+                DateField::DateFormat => preferences.date_format = preferences.date_format.next(),
+                DateField::Dst => {
+                    // Off -> US -> EU -> Off
+                    if !preferences.dst_enabled {
+                        preferences.dst_enabled = true;
+                        preferences.dst_rule = DstRule::US;
+                    } else if preferences.dst_rule == DstRule::US {
+                        preferences.dst_rule = DstRule::EU;
+                    } else {
+                        preferences.dst_enabled = false;
+                    }
+                }
+            },
+            EditState::WateringPick { index, last } => {
+                *index = inclusive_iterator(*index, 0, *last, up)
+            }
+            EditState::History { entry, last } => *entry = inclusive_iterator(*entry, 0, *last, up),
+            EditState::WateringTime { window, field } => {
+                match preferences.watering.get_mut(*window) {
+                    Some(watering) => {
+                        let (value, max) =
+                            window_field(&mut watering.start, &mut watering.end, *field);
+                        *value = inclusive_iterator(*value, 0, max, up);
+                    }
+                    // Changing a time in the empty slot adds a window there
+                    None => {
+                        preferences.set_default_watering_time();
+                    }
+                }
+            }
+            EditState::WateringDays { window, day } => {
+                if let Some(watering) = preferences.watering.get_mut(*window) {
+                    watering.toggle_day(Weekday::ALL[*day as usize]);
+                }
+            }
+            EditState::WateringDuration { .. } => {
+                preferences.watering_duration_minutes = inclusive_iterator(
+                    preferences.watering_duration_minutes,
+                    0,
+                    MAX_WATERING_DURATION,
+                    up,
+                )
+            }
+            EditState::PollInterval { index } => {
+                *index = inclusive_iterator(*index, 0, (SENSOR_INTERVALS.len() - 1) as u8, up)
+            }
+            EditState::PressureUnit => {
+                preferences.pressure_unit = match preferences.pressure_unit {
+                    PressureUnit::Hectopascals => PressureUnit::InchesOfMercury,
+                    PressureUnit::InchesOfMercury => PressureUnit::Hectopascals,
+                }
+            }
+            EditState::Override { field } => {
+                let state = match field {
+                    0 => &mut overrides.vent,
+                    1 => &mut overrides.sprinklers,
+                    2 => &mut overrides.heater,
+                    _ => &mut overrides.buzzer,
+                };
+                *state = cycle_override(*state, up);
+            }
+            EditState::Margin { field } => {
+                let margin = match field {
+                    0 => &mut preferences.margins.vent,
+                    1 => &mut preferences.margins.sprinklers,
+                    _ => &mut preferences.margins.heater,
+                };
+                *margin = if up {
+                    (*margin + 1).min(MAX_HYSTERESIS_MARGIN)
+                } else {
+                    margin.saturating_sub(1)
+                }
+            }
+            EditState::Battery { field } => {
+                let battery = &mut preferences.battery;
+                match field {
+                    0 => {
+                        battery.low_voltage = if up {
+                            (battery.low_voltage + BATTERY_STEP).min(MAX_LOW_BATTERY)
+                        } else {
+                            battery.low_voltage.saturating_sub(BATTERY_STEP)
+                        }
+                    }
+                    1 => {
+                        battery.divider = if up {
+                            (battery.divider + BATTERY_STEP).min(MAX_BATTERY_DIVIDER)
+                        } else {
+                            (battery.divider - BATTERY_STEP).max(MIN_BATTERY_DIVIDER)
+                        }
+                    }
+                    _ => battery.power_saving = !battery.power_saving,
+                }
+            }
+            EditState::AirVenting => {
+                // Off sits below the smallest threshold
+                preferences.air_venting = match (preferences.air_venting, up) {
+                    (None, true) => Some(MIN_AIR_DROP),
+                    (None, false) => None,
+                    (Some(percent), true) => Some((percent + AIR_DROP_STEP).min(MAX_AIR_DROP)),
+                    (Some(percent), false) => percent
+                        .checked_sub(AIR_DROP_STEP)
+                        .filter(|percent| *percent >= MIN_AIR_DROP),
+                }
+            }
+            EditState::AmbientThreshold { field } => {
+                let threshold = match field {
+                    0 => &mut preferences.ambient.backlight_dark,
+                    _ => &mut preferences.ambient.grow_cutoff,
+                };
+                // Off sits below the lowest threshold
+                *threshold = match (*threshold, up) {
+                    (None, true) => Some(MIN_AMBIENT_THRESHOLD),
+                    (None, false) => None,
+                    (Some(percent), true) => {
+                        Some((percent + AMBIENT_STEP).min(MAX_AMBIENT_THRESHOLD))
+                    }
+                    (Some(percent), false) => percent
+                        .checked_sub(AMBIENT_STEP)
+                        .filter(|percent| *percent >= MIN_AMBIENT_THRESHOLD),
+                }
+            }
+            EditState::TempOffset => {
+                let step = if up { 1 } else { -1 };
+                preferences.temp_offset =
+                    (preferences.temp_offset + step).clamp(-MAX_TEMP_OFFSET, MAX_TEMP_OFFSET);
+            }
+            EditState::HumidityOffset => {
+                let step = if up { 1 } else { -1 };
+                preferences.humidity_offset = (preferences.humidity_offset + step)
+                    .clamp(-MAX_HUMIDITY_OFFSET, MAX_HUMIDITY_OFFSET);
+            }
+            EditState::Smoothing => {
+                preferences.smoothing = if up {
+                    (preferences.smoothing + 1).min(MAX_SMOOTHING)
+                } else {
+                    preferences.smoothing.saturating_sub(1).max(1)
+                }
+            }
+            EditState::GasWarmup => {
+                preferences.gas_warmup_minutes = if up {
+                    (preferences.gas_warmup_minutes + 1).min(MAX_GAS_WARMUP_MINUTES)
+                } else {
+                    preferences.gas_warmup_minutes.saturating_sub(1)
+                }
+            }
+            EditState::SoilSensor { enabled } | EditState::LightSchedule { enabled } => {
+                *enabled = !*enabled
+            }
+            EditState::SoilPoint { .. } => {
+                // The points come from the sensor; see EditState::set_soil_reading()
+            }
+            EditState::AmbientPoint { .. } => {
+                // The points come from the sensor; see EditState::set_ambient_reading()
+            }
+            EditState::LightTime { field, window } => {
+                let (value, max) = window_field(&mut window.start, &mut window.end, *field);
+                *value = inclusive_iterator(*value, 0, max, up);
+            }
+            EditState::Feature { field } => match field {
+                0 if preferences.watering.is_empty() => {
+                    preferences.set_default_watering_time();
+                }
+                0 => preferences.watering.clear(),
+                1 if preferences.soil_calibration.is_none() => {
+                    preferences.soil_calibration = Some(DEFAULT_CALIBRATION)
+                }
+                1 => preferences.soil_calibration = None,
+                _ if preferences.light.is_none() => {
+                    preferences.light = Some(LightWindow::default())
+                }
+                _ => preferences.light = None,
+            },
+            EditState::AutoCycle => {
+                // Off sits below the shortest time
+                preferences.auto_cycle_seconds = match (preferences.auto_cycle_seconds, up) {
+                    (None, true) => Some(MIN_AUTO_CYCLE),
+                    (None, false) => None,
+                    (Some(seconds), true) => Some((seconds + 1).min(MAX_AUTO_CYCLE)),
+                    (Some(seconds), false) => seconds
+                        .checked_sub(1)
+                        .filter(|seconds| *seconds >= MIN_AUTO_CYCLE),
+                }
+            }
+            EditState::Reset { confirmed } => *confirmed = !*confirmed,
+        }
+    }
+
+    /// Records a raw soil sensor reading as the calibration point being taken.
+    /// Does nothing unless this is [EditState::SoilPoint]
+    ///
+    /// - param raw: The raw reading; see [crate::soil::read_raw()]
+    pub fn set_soil_reading(&mut self, raw: u16) {
+        if let EditState::SoilPoint { wet, calibration } = self {
+            if *wet {
+                calibration.1 = raw;
+            } else {
+                calibration.0 = raw;
+            }
+        }
+    }
+
+    /// Records a raw light sensor reading as the calibration point being taken.
+    /// Does nothing unless this is [EditState::AmbientPoint]
+    ///
+    /// - param raw: The raw reading; see [crate::soil::read_raw()]
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::edit::EditState;
+    /// use gem_rs::preferences::Preferences;
+    /// use gem_rs::screen::Screen;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// let mut edit = EditState::start(Screen::Ambient, &preferences, 0).unwrap();
+    /// for _ in 0..2 {
+    ///     edit = edit.select(&mut preferences, &mut 0).unwrap(); // Past both thresholds
+    /// }
+    /// edit.set_ambient_reading(150); // Covered
+    /// edit = edit.select(&mut preferences, &mut 0).unwrap();
+    /// edit.set_ambient_reading(3900); // In daylight
+    /// assert!(edit.select(&mut preferences, &mut 0).is_none());
+    /// assert_eq!(preferences.ambient.calibration, (150, 3900));
+    /// ```
+    pub fn set_ambient_reading(&mut self, raw: u16) {
+        if let EditState::AmbientPoint { lit, calibration } = self {
+            if *lit {
+                calibration.1 = raw;
+            } else {
+                calibration.0 = raw;
+            }
+        }
+    }
+
+    /// Moves on to the next setting, for when SELECT is pressed.
+    /// Values edited on a copy are applied once their last field is done
+    ///
+    /// - param preferences: [Preferences] instance
+    /// - param watering_index: The watering window being shown, moved to the edited window
+    ///
+    /// returns the next EditState, or None once the screen's settings are done
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::edit::EditState;
+    /// use gem_rs::overrides::Overrides;
+    /// use gem_rs::preferences::Preferences;
+    /// use gem_rs::screen::Screen;
+    ///
+    /// let mut preferences = Preferences::default(); // No watering windows
+    /// let mut overrides = Overrides::default();
+    /// let mut watering_index = 0;
+    ///
+    /// // Pick the empty slot, and change its start hour to add a window there
+    /// let mut edit = EditState::start(Screen::Watering, &preferences, watering_index).unwrap();
+    /// edit = edit.select(&mut preferences, &mut watering_index).unwrap();
+    /// edit.adjust(true, &mut preferences, &mut overrides); // Adds 00:00 to 01:00
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert_eq!(preferences.watering[0].start, (0, 1));
+    ///
+    /// // Through the other 3 times and the 7 days, on to the duration
+    /// for _ in 0..3 + 7 + 1 {
+    ///     edit = edit.select(&mut preferences, &mut watering_index).unwrap();
+    /// }
+    /// assert_eq!(edit, EditState::WateringDuration { window: 0 });
+    /// assert!(edit.select(&mut preferences, &mut watering_index).is_none());
+    ///
+    /// // Resetting only happens once it is confirmed
+    /// let mut edit = EditState::start(Screen::Reset, &preferences, watering_index).unwrap();
+    /// edit.adjust(true, &mut preferences, &mut overrides);
+    /// assert!(edit.select(&mut preferences, &mut watering_index).is_none());
+    /// assert!(preferences.watering.is_empty());
+    /// ```
+    pub fn select(
+        self,
+        preferences: &mut Preferences,
+        watering_index: &mut usize,
+    ) -> Option<EditState> {
+        let next = match self {
+            EditState::Temperature { lower: true } => EditState::Temperature { lower: false },
+            EditState::Humidity { lower: true } => EditState::Humidity { lower: false },
+            EditState::Temperature { lower: false } => EditState::TemperatureAlarm { lower: true },
+            EditState::Humidity { lower: false } => EditState::HumidityAlarm { lower: true },
+            EditState::TemperatureAlarm { lower: true } => {
+                EditState::TemperatureAlarm { lower: false }
+            }
+            EditState::HumidityAlarm { lower: true } => EditState::HumidityAlarm { lower: false },
+            EditState::SoilRange { lower: true } => EditState::SoilRange { lower: false },
+            EditState::VpdRange { lower: true } => EditState::VpdRange { lower: false },
+            EditState::Date { field, mut date } => match field.next() {
+                Some(field) => EditState::Date { field, date },
+                None => {
+                    // The day may be past the end of a month picked after it
+                    date.normalize();
+                    preferences.set_local_date(date);
+                    return None;
+                }
+            },
+            EditState::WateringPick { index, .. } => EditState::WateringTime {
+                window: index as usize,
+                field: 0,
+            },
+            // Leaving the empty slot as it is adds nothing
+            EditState::WateringTime { window, .. } if window >= preferences.watering.len() => {
+                return self.remove(preferences, watering_index);
+            }
+            EditState::WateringTime { window, field } if field < 3 => EditState::WateringTime {
+                window,
+                field: field + 1,
+            },
+            EditState::WateringTime { window, .. } => EditState::WateringDays { window, day: 0 },
+            EditState::WateringDays { window, day } if day < 6 => EditState::WateringDays {
+                window,
+                day: day + 1,
+            },
+            EditState::WateringDays { window, .. } => EditState::WateringDuration { window },
+            EditState::WateringDuration { window } => {
+                *watering_index = window.min(preferences.watering.len().max(1) - 1);
+                return None;
+            }
+            EditState::PollInterval { index } => {
+                // Used from the next sensor countdown onwards
+                preferences.sensor_interval_ms = SENSOR_INTERVALS[index as usize];
+                EditState::PressureUnit
+            }
+            EditState::Override { field } if field < 3 => EditState::Override { field: field + 1 },
+            EditState::Margin { field } if field < 2 => EditState::Margin { field: field + 1 },
+            EditState::Battery { field } if field < 2 => EditState::Battery { field: field + 1 },
+            EditState::TempOffset => EditState::HumidityOffset,
+            EditState::HumidityOffset => EditState::Smoothing,
+            EditState::Smoothing => EditState::GasWarmup,
+            EditState::SoilSensor { enabled: true } => EditState::SoilPoint {
+                wet: false,
+                calibration: preferences.soil_calibration.unwrap_or((0, 0)),
+            },
+            EditState::SoilSensor { enabled: false } => {
+                preferences.soil_calibration = None;
+                return None;
+            }
+            EditState::SoilPoint {
+                wet: false,
+                calibration,
+            } => EditState::SoilPoint {
+                wet: true,
+                calibration,
+            },
+            EditState::SoilPoint {
+                wet: true,
+                calibration: (dry, wet),
+            } => {
+                // Identical points cannot tell dry soil from wet soil
+                preferences.soil_calibration = (dry != wet).then_some((dry, wet));
+                EditState::SoilRange { lower: true }
+            }
+            EditState::LightSchedule { enabled: true } => EditState::LightTime {
+                field: 0,
+                window: preferences.light.unwrap_or_default(),
+            },
+            EditState::LightSchedule { enabled: false } => {
+                preferences.light = None;
+                return None;
+            }
+            EditState::LightTime { field, window } if field < 3 => EditState::LightTime {
+                field: field + 1,
+                window,
+            },
+            EditState::LightTime { window, .. } => {
+                // The window may wrap past midnight, so any times are allowed
+                preferences.light = Some(window);
+                return None;
+            }
+            EditState::AmbientThreshold { field: 0 } => EditState::AmbientThreshold { field: 1 },
+            EditState::AmbientThreshold { .. } => EditState::AmbientPoint {
+                lit: false,
+                calibration: preferences.ambient.calibration,
+            },
+            EditState::AmbientPoint {
+                lit: false,
+                calibration,
+            } => EditState::AmbientPoint {
+                lit: true,
+                calibration,
+            },
+            EditState::AmbientPoint {
+                lit: true,
+                calibration: (dark, bright),
+            } => {
+                // Identical points cannot tell dark from bright, so the old calibration is kept
+                if dark != bright {
+                    preferences.ambient.calibration = (dark, bright);
+                }
+                return None;
+            }
+            EditState::Feature { field } if field < 2 => EditState::Feature { field: field + 1 },
+            EditState::Feature { .. } => {
+                *watering_index = 0;
+                EditState::AutoCycle
+            }
+            EditState::Reset { confirmed } => {
+                if confirmed {
+                    preferences.reset();
+                    *watering_index = 0;
+                }
+                return None;
+            }
+            EditState::TemperatureAlarm { .. }
+            | EditState::HumidityAlarm { .. }
+            | EditState::SoilRange { .. }
+            | EditState::VpdRange { .. }
+            | EditState::PressureUnit
+            | EditState::Override { .. }
+            | EditState::Margin { .. }
+            | EditState::Battery { .. }
+            | EditState::AirVenting
+            | EditState::AutoCycle
+            | EditState::History { .. }
+            | EditState::GasWarmup => return None,
+        };
+        Some(next)
+    }
+
+    /// Removes the watering window being edited, for when UP and DOWN are pressed together
+    ///
+    /// - param preferences: [Preferences] instance
+    /// - param watering_index: The watering window being shown, kept within the windows left
+    ///
+    /// returns None once the window was removed, or this EditState if it is not editing a watering window's times
+    pub fn remove(
+        self,
+        preferences: &mut Preferences,
+        watering_index: &mut usize,
+    ) -> Option<EditState> {
+        match self {
+            EditState::WateringTime { window, .. } => {
+                preferences.remove_watering_window(window);
+                *watering_index = window.min(preferences.watering.len().max(1) - 1);
+                None
+            }
+            _ => Some(self),
+        }
+    }
+}
+
+/// How many hundredths of a kPa each press moves a bound of [Preferences::vpd] by
+const VPD_STEP: u16 = 5;
+
+/// How many hundredths each press moves a [crate::preferences::BatterySettings] voltage or divider by
+const BATTERY_STEP: u16 = 5;
+
+/// How many percent each press moves [Preferences::air_venting] by
+const AIR_DROP_STEP: u8 = 5;
+
+/// How many percent each press moves a [crate::preferences::AmbientSettings] threshold by
+const AMBIENT_STEP: u8 = 5;
+
+/// Moves one bound of a percentage or temperature range by one, within 0 to 100
+///
+/// - param bound: The bound being edited
+/// - param up: Whether to raise it
+fn step_bound(bound: &mut u8, up: bool) {
+    *bound = if up {
+        bound.saturating_add(1).min(100)
+    } else {
+        bound.saturating_sub(1)
+    };
+}
+
+/// Gets one of a window's times, in the order they are edited: start hour, start minute, end hour, end minute
+///
+/// - param start: The window's start: Minute, Hour
+/// - param end: The window's end: Minute, Hour
+/// - param field: Which time, from 0 to 3
+///
+/// returns the time and its largest value
+fn window_field<'a>(start: &'a mut (u8, u8), end: &'a mut (u8, u8), field: u8) -> (&'a mut u8, u8) {
+    match field {
+        0 => (&mut start.1, 23),
+        1 => (&mut start.0, 59),
+        2 => (&mut end.1, 23),
+        _ => (&mut end.0, 59),
+    }
+}
@@ -0,0 +1,206 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::preferences::{PreferenceStore, Preferences, PREFERENCES_BYTES};
+use crate::zone::ZONE_COUNT;
+
+use panic_probe as _;
+
+/// The AT24C32's I2C address with A0-A2 pulled high, as on DS3231 RTC modules
+pub const AT24C32_ADDRESS: u8 = 0x57;
+/// The AT24C32's size in bytes
+pub const EEPROM_BYTES: usize = 4096;
+/// The AT24C32's page size in bytes. A write wraps around within its page, so it must not cross one
+pub const PAGE_BYTES: usize = 32;
+/// The bytes kept for one zone's [Preferences]: [PREFERENCES_BYTES] rounded up to whole pages,
+/// so every slot starts on a page
+pub const SLOT_BYTES: usize = PREFERENCES_BYTES.div_ceil(PAGE_BYTES) * PAGE_BYTES;
+/// How many times the AT24C32 is polled, 1ms apart, while it finishes writing a page.
+/// Its write cycle takes at most 10ms
+const WRITE_CYCLE_POLLS: u8 = 20;
+
+// Every zone's slot has to fit
+const _: () = assert!(SLOT_BYTES * ZONE_COUNT <= EEPROM_BYTES);
+
+/// Errors that can occur while saving to the EEPROM
+///
+/// - **Bus**: The EEPROM did not respond on the I2C bus, or did not finish writing in time
+/// - **Verify**: The bytes read back differ from the ones written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EepromError {
+    Bus,
+    Verify,
+}
+
+/// An AT24C32 EEPROM holding one zone's [Preferences], for boards that keep them on the I2C bus.
+/// The preferences are stored as [Preferences::to_bytes()] at the start of the zone's slot:
+///
+/// - **0x000..**: Zone 1's slot, [SLOT_BYTES] long
+/// - **[SLOT_BYTES]..**: Zone 2's slot, and so on
+///
+/// The rest of the chip is unused. Only pages that changed are written, to spare the chip's write endurance
+///
+/// ## Example:
+/// ```rust
+/// use embedded_hal::delay::DelayNs;
+/// use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, Operation};
+/// use gem_rs::eeprom::{Eeprom, EEPROM_BYTES, PAGE_BYTES};
+/// use gem_rs::preferences::{PreferenceStore, Preferences};
+///
+/// // A blank AT24C32, or none at all
+/// struct Chip {
+///     present: bool,
+///     memory: [u8; EEPROM_BYTES],
+///     pointer: usize,
+/// }
+///
+/// impl ErrorType for Chip {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl I2c for Chip {
+///     fn transaction(&mut self, _: u8, operations: &mut [Operation]) -> Result<(), ErrorKind> {
+///         if !self.present {
+///             return Err(ErrorKind::Other);
+///         }
+///         for operation in operations {
+///             match operation {
+///                 // The RP2040 cannot send an address without any data
+///                 Operation::Write([]) => return Err(ErrorKind::Other),
+///                 Operation::Write(bytes) => {
+///                     self.pointer = (bytes[0] as usize) << 8 | bytes[1] as usize;
+///                     let page = self.pointer - self.pointer % PAGE_BYTES;
+///                     for (i, byte) in bytes[2..].iter().enumerate() {
+///                         // Writes wrap around within the page
+///                         self.memory[page + (self.pointer + i) % PAGE_BYTES] = *byte;
+///                     }
+///                 }
+///                 Operation::Read(buffer) => {
+///                     for byte in buffer.iter_mut() {
+///                         *byte = self.memory[self.pointer];
+///                         self.pointer += 1;
+///                     }
+///                 }
+///             }
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// struct Delay;
+///
+/// impl DelayNs for Delay {
+///     fn delay_ns(&mut self, _: u32) {}
+/// }
+///
+/// let mut eeprom = Eeprom::new(Chip { present: true, memory: [0xFF; EEPROM_BYTES], pointer: 0 }, Delay, 0);
+/// assert!(eeprom.load().is_none()); // Nothing saved yet
+///
+/// let mut preferences = Preferences::default();
+/// preferences.temperature = (55, 85);
+/// assert_eq!(eeprom.save(&preferences), Ok(()));
+/// assert!(eeprom.load() == Some(preferences));
+///
+/// let mut missing = Eeprom::new(Chip { present: false, memory: [0xFF; EEPROM_BYTES], pointer: 0 }, Delay, 0);
+/// assert!(missing.load().is_none());
+/// assert!(missing.save(&Preferences::default()).is_err());
+/// ```
+pub struct Eeprom<I2C, D> {
+    i2c: I2C,
+    delay: D,
+    slot: usize,
+}
+
+impl<I2C: I2c, D: DelayNs> Eeprom<I2C, D> {
+    /// Creates a new instance of Eeprom
+    ///
+    /// - param i2c: The I2C bus the AT24C32 is on
+    /// - param delay: Delay instance, for waiting on the write cycle
+    /// - param slot: The zone's index, which picks the slot its preferences are kept in
+    ///
+    /// returns a new instance of Eeprom
+    pub fn new(i2c: I2C, delay: D, slot: usize) -> Eeprom<I2C, D> {
+        Self { i2c, delay, slot }
+    }
+
+    /// Reads bytes from the EEPROM
+    ///
+    /// - param address: The memory address to start at
+    /// - param buffer: The buffer to fill
+    ///
+    /// returns [EepromError::Bus] if the EEPROM did not respond
+    fn read(&mut self, address: usize, buffer: &mut [u8]) -> Result<(), EepromError> {
+        self.i2c
+            .write_read(AT24C32_ADDRESS, &(address as u16).to_be_bytes(), buffer)
+            .map_err(|_| EepromError::Bus)
+    }
+
+    /// Writes bytes within one page, then waits for the EEPROM to finish writing them.
+    /// The EEPROM does not answer until the write cycle is over, so it is polled by setting its
+    /// address pointer, since the RP2040's I2C cannot send a write without data
+    ///
+    /// - param address: The memory address to start at
+    /// - param bytes: The bytes to write; they must not cross a page boundary
+    ///
+    /// returns [EepromError::Bus] if the EEPROM did not respond or finish in time
+    fn write_page(&mut self, address: usize, bytes: &[u8]) -> Result<(), EepromError> {
+        let mut buffer = [0; 2 + PAGE_BYTES];
+        buffer[..2].copy_from_slice(&(address as u16).to_be_bytes());
+        buffer[2..2 + bytes.len()].copy_from_slice(bytes);
+        self.i2c
+            .write(AT24C32_ADDRESS, &buffer[..2 + bytes.len()])
+            .map_err(|_| EepromError::Bus)?;
+
+        for _ in 0..WRITE_CYCLE_POLLS {
+            self.delay.delay_ms(1);
+            if self.i2c.write(AT24C32_ADDRESS, &buffer[..2]).is_ok() {
+                return Ok(());
+            }
+        }
+        Err(EepromError::Bus)
+    }
+}
+
+impl<I2C: I2c, D: DelayNs> PreferenceStore for Eeprom<I2C, D> {
+    type Error = EepromError;
+
+    /// Saves the preferences into the zone's slot, then reads them back to make sure they stuck
+    ///
+    /// - param preferences: [Preferences] instance
+    ///
+    /// returns [EepromError::Bus] if the EEPROM did not respond, or [EepromError::Verify] if the write did not stick
+    fn save(&mut self, preferences: &Preferences) -> Result<(), EepromError> {
+        let start = self.slot * SLOT_BYTES;
+        let bytes = preferences.to_bytes();
+        let mut stored = [0; PREFERENCES_BYTES];
+        self.read(start, &mut stored)?;
+
+        // The slot starts on a page, so each chunk fills one page
+        for (index, (page, old)) in bytes
+            .chunks(PAGE_BYTES)
+            .zip(stored.chunks(PAGE_BYTES))
+            .enumerate()
+        {
+            if page != old {
+                self.write_page(start + index * PAGE_BYTES, page)?;
+            }
+        }
+
+        self.read(start, &mut stored)?;
+        if stored != bytes {
+            return Err(EepromError::Verify);
+        }
+        Ok(())
+    }
+
+    /// Loads the preferences from the zone's slot
+    ///
+    /// returns the [Preferences], or None if the EEPROM did not respond or holds nothing valid
+    fn load(&mut self) -> Option<Preferences> {
+        let mut bytes = [0; PREFERENCES_BYTES];
+        self.read(self.slot * SLOT_BYTES, &mut bytes).ok()?;
+        Preferences::from_bytes(&bytes)
+            .ok()
+            .filter(|preferences| preferences.validate().is_ok())
+    }
+}
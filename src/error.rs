@@ -0,0 +1,71 @@
+use hd44780_driver::error::Error as LcdError;
+
+use crate::messages::{DISPLAY_ERROR, NO_SENSOR, SENSOR_ERROR};
+use crate::sensors::SensorError;
+
+use panic_probe as _;
+
+/// Faults the controller recovers from instead of panicking, each shown on the LCD with a code.
+/// A display fault puts every zone in its safe state (see [crate::zone::Zone::fail_safe()]) until it is retried,
+/// while a sensor fault only holds its own zone safe; see [crate::zone::Zone::hold_safe()].
+/// The relays, buzzer, and PWM outputs are on the RP2040's own pins, which cannot fail, so they have no variant
+///
+/// - **Display**: The [crate::rendering::Lcd] did not accept a command
+/// - **Sensor**: A zone's BME680 could not be read; see [SensorError]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::error::GemError;
+/// use gem_rs::sensors::SensorError;
+///
+/// assert_eq!(GemError::Display.code(), 10);
+/// assert_eq!(GemError::from(SensorError::Read).code(), 22);
+/// assert_eq!(GemError::from(SensorError::Absent).message(), "No Sensor");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GemError {
+    Display,
+    Sensor(SensorError),
+}
+
+impl GemError {
+    /// Gets the code shown on the LCD, so a fault can be told apart without a debug probe.
+    /// The tens digit is the part that failed: 1 = display, 2 = sensor
+    ///
+    /// returns the error code
+    pub fn code(self) -> u8 {
+        match self {
+            GemError::Display => 10,
+            GemError::Sensor(error) => match error {
+                SensorError::ModeSet => 21,
+                SensorError::Read => 22,
+                SensorError::Setup => 23,
+                SensorError::Absent => 24,
+                SensorError::NoAddress => 25,
+            },
+        }
+    }
+
+    /// Gets a short description of the fault for the LCD
+    ///
+    /// returns the message; Ex: `No Sensor`
+    pub fn message(self) -> &'static str {
+        match self {
+            GemError::Display => DISPLAY_ERROR,
+            GemError::Sensor(SensorError::Absent | SensorError::NoAddress) => NO_SENSOR,
+            GemError::Sensor(_) => SENSOR_ERROR,
+        }
+    }
+}
+
+impl From<SensorError> for GemError {
+    fn from(error: SensorError) -> Self {
+        GemError::Sensor(error)
+    }
+}
+
+impl<E> From<LcdError<E>> for GemError {
+    fn from(_: LcdError<E>) -> Self {
+        GemError::Display
+    }
+}
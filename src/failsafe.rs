@@ -0,0 +1,74 @@
+use core::ptr::write_volatile;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::relay::ActivePolarity;
+
+use panic_probe as _;
+
+// A panic halts the CPU with every output latched as it was. If the sprinklers or heater were on,
+// they would stay on: the watchdog only resets the board once it is running, and not at all while
+// a debugger holds it. panic-probe owns the panic handler, but it always ends in a HardFault, so the
+// firmware's HardFault handler calls de_energize() to switch the outputs off before halting.
+// That also covers faults that are not panics, like a bad memory access.
+//
+// The pins are stored as bit masks, one per [ActivePolarity], since the HAL's pin types are owned by the zones by then.
+// The outputs are switched off through the registers directly, so it works whatever state the HAL was left in.
+
+/// The SIO's register that drives the GPIOs set in it high; see the RP2040 datasheet's SIO registers
+const SIO_GPIO_OUT_SET: usize = 0xD000_0014;
+/// The SIO's register that drives the GPIOs set in it low
+const SIO_GPIO_OUT_CLR: usize = 0xD000_0018;
+/// The SIO's register that makes the GPIOs set in it outputs
+const SIO_GPIO_OE_SET: usize = 0xD000_0024;
+/// The first GPIO's control register, which picks its function; see the RP2040 datasheet's IO_BANK0 registers.
+/// Each GPIO's comes 8 bytes after the one before
+const IO_BANK0_GPIO0_CTRL: usize = 0x4001_4004;
+/// The GPIO function driven by the SIO, so a PWM pin stops pulsing and follows [SIO_GPIO_OUT_CLR]
+/// or [SIO_GPIO_OUT_SET]
+const FUNCSEL_SIO: u32 = 5;
+/// The GPIOs in bank 0
+const GPIO_COUNT: u8 = 30;
+
+/// The active-high GPIOs, which are driven low on a crash, one bit each
+static GUARDED_PINS: AtomicU32 = AtomicU32::new(0);
+/// The active-low GPIOs, which are driven high on a crash, one bit each
+static GUARDED_ACTIVE_LOW_PINS: AtomicU32 = AtomicU32::new(0);
+
+/// Adds an output to the ones switched off on a crash; see [de_energize()]
+///
+/// **NOTE:** This should only be called while setting up, before anything else could read the pins;
+/// the RP2040's cores have no atomic read-modify-write
+///
+/// - param gpio: The output's GPIO number; Ex: `sprinklers.id().num`
+/// - param polarity: Which level switches the output on, e.g. [crate::relay::SPRINKLER_POLARITY].
+///   PWM outputs are [ActivePolarity::High]
+pub fn guard(gpio: u8, polarity: ActivePolarity) {
+    let mask = match polarity {
+        ActivePolarity::High => &GUARDED_PINS,
+        ActivePolarity::Low => &GUARDED_ACTIVE_LOW_PINS,
+    };
+    if gpio < GPIO_COUNT {
+        let pins = mask.load(Ordering::Relaxed);
+        mask.store(pins | 1 << gpio, Ordering::Relaxed);
+    }
+}
+
+/// Drives every guarded output to its inactive level, so a crash leaves the sprinklers, heater, fan,
+/// and the rest off. A servo vent stops getting pulses, so it stays where it is; a relay vent closes
+///
+/// **NOTE:** This function is only for the HardFault handler; it takes the pins from under the HAL
+pub fn de_energize() {
+    let active_high = GUARDED_PINS.load(Ordering::Relaxed);
+    let active_low = GUARDED_ACTIVE_LOW_PINS.load(Ordering::Relaxed);
+    let pins = active_high | active_low;
+    // SAFETY: The registers are the RP2040's, and nothing else runs once the CPU has faulted
+    unsafe {
+        write_volatile(SIO_GPIO_OUT_CLR as *mut u32, active_high);
+        write_volatile(SIO_GPIO_OUT_SET as *mut u32, active_low);
+        write_volatile(SIO_GPIO_OE_SET as *mut u32, pins);
+        for gpio in (0..GPIO_COUNT).filter(|gpio| pins & 1 << gpio != 0) {
+            let ctrl = IO_BANK0_GPIO0_CTRL + gpio as usize * 8;
+            write_volatile(ctrl as *mut u32, FUNCSEL_SIO);
+        }
+    }
+}
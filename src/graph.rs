@@ -0,0 +1,232 @@
+use heapless::{Deque, String, Vec};
+use ufmt::uwrite;
+
+use crate::messages::{GRAPH_HUMIDITY, GRAPH_PRESSURE, GRAPH_TEMPERATURE};
+use crate::sensors::PressureUnit;
+
+use panic_probe as _;
+
+/// How many samples a [TrendBuffer] keeps: one per column of the [crate::rendering::Lcd]
+pub const GRAPH_SAMPLES: usize = 16;
+/// How many seconds of readings the graphs span
+pub const GRAPH_SPAN_S: u32 = 3600;
+/// How many seconds of readings are averaged into each sample, so the samples span [GRAPH_SPAN_S]
+pub const GRAPH_SAMPLE_INTERVAL: u32 = GRAPH_SPAN_S / GRAPH_SAMPLES as u32;
+
+/// The readings [crate::screen::Screen::Graph] can show, in the order SELECT cycles through them
+///
+/// - **Temperature**: The temperature in Fahrenheit
+/// - **Humidity**: The relative humidity percentage
+/// - **Pressure**: The barometric pressure, in millibars
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphSeries {
+    Temperature,
+    Humidity,
+    Pressure,
+}
+
+impl GraphSeries {
+    /// Gets the series shown after this one, wrapping to the first
+    ///
+    /// returns the next GraphSeries
+    pub fn next(self) -> GraphSeries {
+        match self {
+            GraphSeries::Temperature => GraphSeries::Humidity,
+            GraphSeries::Humidity => GraphSeries::Pressure,
+            GraphSeries::Pressure => GraphSeries::Temperature,
+        }
+    }
+
+    /// Formats the lowest and highest samples above the graph: `Temp 58-74F`, `RH 40-62%`, or `PRS 1008-1013`.
+    /// The degree glyph is not used, since the graph's bars take its place; see [crate::rendering::GlyphSet]
+    ///
+    /// - param range: The lowest and highest samples: Min, Max
+    /// - param unit: The [PressureUnit] pressure is shown in
+    ///
+    /// returns a [String] of length 16 containing the formatted range
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::graph::GraphSeries;
+    /// use gem_rs::sensors::PressureUnit;
+    ///
+    /// let unit = PressureUnit::InchesOfMercury;
+    /// assert_eq!(GraphSeries::Temperature.format_range((58, 74), unit).as_str(), "Temp 58-74F");
+    /// assert_eq!(GraphSeries::Humidity.format_range((40, 62), unit).as_str(), "RH 40-62%");
+    /// assert_eq!(GraphSeries::Pressure.format_range((1008, 1013), unit).as_str(), "PRS 29.77-29.91");
+    /// ```
+    pub fn format_range(self, range: (u16, u16), unit: PressureUnit) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self {
+            GraphSeries::Temperature => {
+                uwrite!(str, "{} {}-{}F", GRAPH_TEMPERATURE, range.0, range.1)
+            }
+            GraphSeries::Humidity => uwrite!(str, "{} {}-{}%", GRAPH_HUMIDITY, range.0, range.1),
+            GraphSeries::Pressure => uwrite!(
+                str,
+                "{} {}-{}",
+                GRAPH_PRESSURE,
+                unit.format(range.0).as_str(),
+                unit.format(range.1).as_str()
+            ),
+        }
+        .unwrap();
+        str
+    }
+}
+
+/// The last [GRAPH_SPAN_S] seconds of one reading, for drawing it as a graph.
+/// Every reading is recorded, and each [GRAPH_SAMPLE_INTERVAL] of them is averaged into one sample.
+/// Once full, the oldest sample is dropped. Time without readings, e.g. while the sensor was out, is not shown
+///
+/// - **samples**: The averaged samples, oldest first
+/// - **sum**, **count**: The readings being averaged into the next sample
+/// - **started**: When the next sample started in seconds since boot, or None before the first reading
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::graph::{TrendBuffer, GRAPH_SAMPLES, GRAPH_SAMPLE_INTERVAL};
+///
+/// let mut trend = TrendBuffer::new();
+/// assert_eq!(trend.range(), None);
+///
+/// // The sample being averaged is shown right away
+/// trend.record(70, 0);
+/// trend.record(72, 2);
+/// assert_eq!(trend.values().as_slice(), [71]);
+///
+/// trend.record(80, GRAPH_SAMPLE_INTERVAL);
+/// assert_eq!(trend.values().as_slice(), [71, 80]);
+/// assert_eq!(trend.range(), Some((71, 80)));
+///
+/// // Only the last hour is kept
+/// for sample in 2..40 {
+///     trend.record(60 + sample as u16, sample * GRAPH_SAMPLE_INTERVAL);
+/// }
+/// let values = trend.values();
+/// assert_eq!(values.len(), GRAPH_SAMPLES);
+/// assert_eq!(values.first(), Some(&84));
+/// assert_eq!(values.last(), Some(&99));
+/// ```
+pub struct TrendBuffer {
+    samples: Deque<u16, GRAPH_SAMPLES>,
+    sum: u32,
+    count: u32,
+    started: Option<u32>,
+}
+
+impl Default for TrendBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrendBuffer {
+    /// Creates a new, empty instance of TrendBuffer
+    ///
+    /// returns a new instance of TrendBuffer
+    pub fn new() -> TrendBuffer {
+        Self {
+            samples: Deque::new(),
+            sum: 0,
+            count: 0,
+            started: None,
+        }
+    }
+
+    /// Records a reading. Once [GRAPH_SAMPLE_INTERVAL] seconds of readings were recorded,
+    /// their average becomes a sample
+    ///
+    /// - param value: The reading
+    /// - param now: The current time in seconds since boot
+    pub fn record(&mut self, value: u16, now: u32) {
+        let started = *self.started.get_or_insert(now);
+        if self.count > 0 && now.wrapping_sub(started) >= GRAPH_SAMPLE_INTERVAL {
+            if self.samples.is_full() {
+                self.samples.pop_front();
+            }
+            self.samples.push_back((self.sum / self.count) as u16).ok();
+            self.sum = 0;
+            self.count = 0;
+            self.started = Some(now);
+        }
+        self.sum += value as u32;
+        self.count += 1;
+    }
+
+    /// Gets the samples to draw, with the one still being averaged as the newest
+    ///
+    /// returns up to [GRAPH_SAMPLES] samples, oldest first
+    pub fn values(&self) -> Vec<u16, GRAPH_SAMPLES> {
+        let averaging = self.count > 0;
+        let skip = (self.samples.len() + averaging as usize).saturating_sub(GRAPH_SAMPLES);
+        let mut values: Vec<u16, GRAPH_SAMPLES> = self.samples.iter().skip(skip).copied().collect();
+        if averaging {
+            values.push((self.sum / self.count) as u16).ok();
+        }
+        values
+    }
+
+    /// Gets the lowest and highest samples, which the graph is scaled to
+    ///
+    /// returns the range: Min, Max; or None before the first reading
+    pub fn range(&self) -> Option<(u16, u16)> {
+        let values = self.values();
+        let min = values.iter().min()?;
+        let max = values.iter().max()?;
+        Some((*min, *max))
+    }
+}
+
+/// A [TrendBuffer] for each [GraphSeries] of one zone
+///
+/// - **temperature**, **humidity**, **pressure**: The last hour of each reading
+pub struct TrendGraphs {
+    temperature: TrendBuffer,
+    humidity: TrendBuffer,
+    pressure: TrendBuffer,
+}
+
+impl Default for TrendGraphs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrendGraphs {
+    /// Creates a new instance of TrendGraphs, with no readings
+    ///
+    /// returns a new instance of TrendGraphs
+    pub fn new() -> TrendGraphs {
+        Self {
+            temperature: TrendBuffer::new(),
+            humidity: TrendBuffer::new(),
+            pressure: TrendBuffer::new(),
+        }
+    }
+
+    /// Records a reading of every series
+    ///
+    /// - param temp: The temperature in Fahrenheit
+    /// - param humidity: The relative humidity percentage
+    /// - param pressure: The pressure in millibars
+    /// - param now: The current time in seconds since boot
+    pub fn record(&mut self, temp: u8, humidity: u8, pressure: u16, now: u32) {
+        self.temperature.record(temp as u16, now);
+        self.humidity.record(humidity as u16, now);
+        self.pressure.record(pressure, now);
+    }
+
+    /// Gets the history of one series
+    ///
+    /// - param series: The [GraphSeries]
+    ///
+    /// returns the series' [TrendBuffer]
+    pub fn get(&self, series: GraphSeries) -> &TrendBuffer {
+        match series {
+            GraphSeries::Temperature => &self.temperature,
+            GraphSeries::Humidity => &self.humidity,
+            GraphSeries::Pressure => &self.pressure,
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use heapless::{Deque, String};
+use ufmt::uwrite;
+
+use crate::datetime::{pad_number, DateFormat, DateTime};
+use crate::messages::{
+    LAST_WATERED, MANUAL, NO_WATERING_YET, RUNNING, SCHEDULE, SOIL, WATERING_HUMIDITY,
+};
+use crate::timer::format_minutes_seconds;
+
+use panic_probe as _;
+
+/// How many watering events [WateringHistory] keeps
+pub const WATERING_HISTORY: usize = 8;
+
+/// Why the sprinklers started
+///
+/// - **Schedule**: A watering window started
+/// - **Humidity**: The air was too dry
+/// - **Soil**: The soil was too dry
+/// - **Manual**: Watering was started by hand, or the sprinklers were overridden on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WateringReason {
+    Schedule,
+    Humidity,
+    Soil,
+    Manual,
+}
+
+impl WateringReason {
+    /// Gets the reason's name for the LCD
+    ///
+    /// returns the name; Ex: `Schedule`
+    pub fn name(self) -> &'static str {
+        match self {
+            WateringReason::Schedule => SCHEDULE,
+            WateringReason::Humidity => WATERING_HUMIDITY,
+            WateringReason::Soil => SOIL,
+            WateringReason::Manual => MANUAL,
+        }
+    }
+}
+
+/// Works out why the sprinklers are running. Watering by hand wins over the schedule,
+/// and the schedule over the readings
+///
+/// - param manual: Whether watering was started by hand, or the sprinklers are overridden on
+/// - param scheduled: Whether it is watering time; see [crate::preferences::Preferences::is_watering_time()]
+/// - param soaking: Whether the soil is too dry
+///
+/// returns the [WateringReason]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::history::{watering_reason, WateringReason};
+///
+/// assert_eq!(watering_reason(true, true, true), WateringReason::Manual);
+/// assert_eq!(watering_reason(false, true, true), WateringReason::Schedule);
+/// assert_eq!(watering_reason(false, false, true), WateringReason::Soil);
+/// assert_eq!(watering_reason(false, false, false), WateringReason::Humidity);
+/// ```
+pub fn watering_reason(manual: bool, scheduled: bool, soaking: bool) -> WateringReason {
+    if manual {
+        WateringReason::Manual
+    } else if scheduled {
+        WateringReason::Schedule
+    } else if soaking {
+        WateringReason::Soil
+    } else {
+        WateringReason::Humidity
+    }
+}
+
+/// One time the sprinklers ran
+///
+/// - **start**: When the sprinklers turned on, in local time
+/// - **duration_s**: How long they ran in seconds, or None while they are still running
+/// - **reason**: The [WateringReason] they turned on for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WateringEvent {
+    pub start: DateTime,
+    pub duration_s: Option<u32>,
+    pub reason: WateringReason,
+}
+
+impl WateringEvent {
+    /// Formats when the event started, in 24-hour time: `31/12 06:00`.
+    /// The day and month are ordered by the [DateFormat]
+    ///
+    /// - param date_format: The [DateFormat]
+    ///
+    /// returns a [String] of length 16 containing the formatted start
+    pub fn format_start(&self, date_format: DateFormat) -> String<16> {
+        let day = pad_number(self.start.day);
+        let month = pad_number(self.start.month);
+        let (first, separator, second) = match date_format {
+            DateFormat::Dmy => (day.as_str(), "/", month.as_str()),
+            DateFormat::Mdy => (month.as_str(), "/", day.as_str()),
+            DateFormat::Iso => (month.as_str(), "-", day.as_str()),
+        };
+        let mut str: String<16> = String::new();
+        uwrite!(
+            str,
+            "{}{}{} {}:{}",
+            first,
+            separator,
+            second,
+            pad_number(self.start.hour).as_str(),
+            pad_number(self.start.minute).as_str()
+        )
+        .unwrap();
+        str
+    }
+
+    /// Formats why and for how long the sprinklers ran: `Schedule 10:00`, or `Manual running`
+    ///
+    /// returns a [String] of length 16 containing the formatted outcome
+    pub fn format_outcome(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.duration_s {
+            Some(seconds) => uwrite!(
+                str,
+                "{} {}",
+                self.reason.name(),
+                format_minutes_seconds(seconds.saturating_mul(1000)).as_str()
+            ),
+            None => uwrite!(str, "{} {}", self.reason.name(), RUNNING),
+        }
+        .unwrap();
+        str
+    }
+}
+
+/// The last [WATERING_HISTORY] times the sprinklers ran, so a grower can check they ran on schedule.
+/// Once it is full, the oldest event is overwritten
+///
+/// - **events**: The [WateringEvent]s, oldest first
+/// - **started_at**: When the running event started in seconds since boot, or None if the sprinklers are off
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::{DateFormat, DateTime};
+/// use gem_rs::history::{WateringHistory, WateringReason, WATERING_HISTORY};
+///
+/// let mut history = WateringHistory::new();
+/// let date = DateTime::new(2024, 5, 31, 6, 0, 0);
+/// history.update(true, WateringReason::Schedule, date, 100);
+/// history.update(true, WateringReason::Humidity, date, 160); // Still the same event
+///
+/// let event = history.get(0).unwrap();
+/// assert_eq!(event.reason, WateringReason::Schedule);
+/// assert_eq!(event.duration_s, None);
+/// assert_eq!(event.format_start(DateFormat::Dmy).as_str(), "31/05 06:00");
+/// assert_eq!(event.format_outcome().as_str(), "Schedule running");
+///
+/// history.update(false, WateringReason::Schedule, date, 700);
+/// assert_eq!(history.get(0).unwrap().duration_s, Some(600));
+/// assert_eq!(history.get(0).unwrap().format_outcome().as_str(), "Schedule 10:00");
+///
+/// // The newest event comes first
+/// history.update(true, WateringReason::Manual, date, 800);
+/// assert_eq!(history.get(0).unwrap().reason, WateringReason::Manual);
+/// assert_eq!(history.get(1).unwrap().reason, WateringReason::Schedule);
+///
+/// // Once full, the oldest event is overwritten
+/// for second in 0..WATERING_HISTORY as u32 - 1 {
+///     history.update(false, WateringReason::Humidity, date, 900 + second * 2);
+///     history.update(true, WateringReason::Humidity, date, 901 + second * 2);
+/// }
+/// assert_eq!(history.len(), WATERING_HISTORY);
+/// assert_eq!(history.get(WATERING_HISTORY - 1).unwrap().reason, WateringReason::Manual);
+/// assert!(history.get(WATERING_HISTORY).is_none());
+/// ```
+pub struct WateringHistory {
+    events: Deque<WateringEvent, WATERING_HISTORY>,
+    started_at: Option<u32>,
+}
+
+impl Default for WateringHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WateringHistory {
+    /// Creates a new, empty instance of WateringHistory
+    ///
+    /// returns a new instance of WateringHistory
+    pub fn new() -> WateringHistory {
+        Self {
+            events: Deque::new(),
+            started_at: None,
+        }
+    }
+
+    /// Records the sprinklers turning on or off. An event starts when they turn on,
+    /// and its duration is filled in when they turn off
+    ///
+    /// - param sprinkling: Whether the sprinklers are running
+    /// - param reason: Why they are running; only used when they turn on
+    /// - param date: The current local [DateTime]
+    /// - param now: The current time in seconds since boot
+    pub fn update(&mut self, sprinkling: bool, reason: WateringReason, date: DateTime, now: u32) {
+        match (sprinkling, self.started_at) {
+            (true, None) => {
+                if self.events.is_full() {
+                    self.events.pop_front();
+                }
+                let event = WateringEvent {
+                    start: date,
+                    duration_s: None,
+                    reason,
+                };
+                self.events.push_back(event).ok();
+                self.started_at = Some(now);
+            }
+            (false, Some(started_at)) => {
+                if let Some(event) = self.events.back_mut() {
+                    event.duration_s = Some(now.wrapping_sub(started_at));
+                }
+                self.started_at = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Gets how many events are kept
+    ///
+    /// returns the amount of events, up to [WATERING_HISTORY]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Checks if the sprinklers have not run yet
+    ///
+    /// returns true if there are no events
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Gets an event, counting back from the newest
+    ///
+    /// - param entry: How many events back to go; 0 is the newest
+    ///
+    /// returns the [WateringEvent], or None if there are not that many
+    pub fn get(&self, entry: usize) -> Option<&WateringEvent> {
+        self.events.iter().rev().nth(entry)
+    }
+
+    /// Formats when the sprinklers last started, whatever they ran for: `Last 31/12 06:00`, or `Last: None yet`
+    ///
+    /// - param date_format: The [DateFormat]
+    ///
+    /// returns a [String] of length 16 containing the formatted start
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{DateFormat, DateTime};
+    /// use gem_rs::history::{WateringHistory, WateringReason};
+    ///
+    /// let mut history = WateringHistory::new();
+    /// assert_eq!(history.format_last(DateFormat::Dmy).as_str(), "Last: None yet");
+    ///
+    /// history.update(true, WateringReason::Schedule, DateTime::new(2024, 5, 31, 6, 0, 0), 0);
+    /// history.update(false, WateringReason::Schedule, DateTime::new(2024, 5, 31, 6, 10, 0), 600);
+    /// assert_eq!(history.format_last(DateFormat::Dmy).as_str(), "Last 31/05 06:00");
+    /// ```
+    pub fn format_last(&self, date_format: DateFormat) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.get(0) {
+            Some(event) => uwrite!(
+                str,
+                "{} {}",
+                LAST_WATERED,
+                event.format_start(date_format).as_str()
+            ),
+            None => uwrite!(str, "{}: {}", LAST_WATERED, NO_WATERING_YET),
+        }
+        .unwrap();
+        str
+    }
+}
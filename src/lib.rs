@@ -6,16 +6,63 @@
 //!
 //! Features:
 //! - Temperature monitoring and safety range
+//! - Proportional roof vent servo
 //! - Humidity monitoring and safety range
 //! - Pressure monitoring
+//! - Two BME680s averaged, for greenhouses warmer in some spots than others
+//! - Airing out stale air by its gas resistance
 //! - Uptime tracker
+//! - Screens that cycle on their own for hands-free monitoring
 //! - Watering system scheduler
+//! - Watering history log
+//! - Last-hour graphs of the temperature, humidity, and pressure
+//! - Soil moisture sensor support
+//! - Ambient light sensing, to turn off the backlight at night and skip the grow light on bright days
+//! - Grow light scheduler
 //! - Smoke/fire detection support
+//! - Temperature and humidity alarms, separate from the control ranges
+//! - Distinct buzzer patterns for each alarm
+//! - Settings kept in an AT24C32 EEPROM
+//! - Outputs switched off if the firmware crashes
+//! - Active-high or active-low relay boards
+//! - Battery voltage monitoring and power saving
+//! - A second greenhouse zone
+//! - Stack usage diagnostics
 //!
 //! Links:
 //! [GitHub](https://github.com/QPCrummer/GEM-rs)
 
+pub mod air;
+pub mod alarm;
+pub mod battery;
+pub mod buttons;
+pub mod buzzer;
+pub mod datetime;
+pub mod edit;
+pub mod eeprom;
+pub mod error;
+pub mod failsafe;
+pub mod graph;
+pub mod history;
+pub mod light_sensor;
+pub mod logger;
+pub mod messages;
+#[cfg(any(test, feature = "mock-lcd"))]
+pub mod mock_lcd;
+pub mod observer;
+pub mod overrides;
 pub mod preferences;
+pub mod relay;
 pub mod rendering;
+pub mod rtc;
+pub mod screen;
+pub mod selftest;
 pub mod sensors;
+pub mod soil;
+#[cfg(feature = "stack-usage")]
+pub mod stack;
+pub mod stats;
+pub mod telemetry;
 pub mod timer;
+pub mod vent;
+pub mod zone;
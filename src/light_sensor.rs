@@ -0,0 +1,208 @@
+use heapless::String;
+use ufmt::uwrite;
+
+use crate::messages::{AMBIENT, AMBIENT_BRIGHT, AMBIENT_DARK, NO_READING, RAW_READING};
+use crate::preferences::{AmbientSettings, Hysteresis};
+use crate::soil::{moisture_percent, SoilPin};
+
+use panic_probe as _;
+
+/// The photoresistor's pin. Every other ADC input is taken, so it is wired in place of the soil sensor;
+/// see the `light-sensor` feature
+pub type LightSensorPin = SoilPin;
+
+/// Typical raw readings of a photoresistor wired from 3.3V to the pin, with a 10kOhm resistor to ground,
+/// covered and in daylight: Dark, Bright. Used until the sensor is calibrated from the ambient light screen
+pub const DEFAULT_LIGHT_CALIBRATION: (u16, u16) = (300, 3700);
+/// How many percent the brightness must move back past a threshold before it counts as crossed again,
+/// so the backlight and grow light do not flap at dusk
+pub const LIGHT_HYSTERESIS: u8 = 5;
+
+/// Estimates the brightness by interpolating between the calibration points,
+/// the same way [moisture_percent()] does for the soil sensor
+///
+/// - param raw: The raw reading from [crate::soil::read_raw()]
+/// - param calibration: The raw readings with the sensor covered and in daylight: Dark, Bright
+///
+/// returns the brightness percentage, clamped to 0 - 100
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::light_sensor::brightness_percent;
+///
+/// let calibration = (400, 3600); // Dark, Bright
+/// assert_eq!(brightness_percent(400, calibration), 0);
+/// assert_eq!(brightness_percent(2000, calibration), 50);
+/// assert_eq!(brightness_percent(4000, calibration), 100); // Brighter than the bright point
+/// assert_eq!(brightness_percent(100, calibration), 0);    // Darker than the dark point
+/// ```
+pub fn brightness_percent(raw: u16, calibration: (u16, u16)) -> u8 {
+    moisture_percent(raw, calibration)
+}
+
+/// Tracks the ambient light, and whether it is dark enough for the backlight to turn off
+/// or bright enough to skip the grow light. Each is off while its threshold is None
+///
+/// - **raw**: The last raw reading, or None before the first
+/// - **level**: The last brightness percentage, or None before the first
+/// - **dark**: On once the brightness drops below [AmbientSettings::backlight_dark],
+///   and off once it rises [LIGHT_HYSTERESIS] above it
+/// - **bright**: On once the brightness rises above [AmbientSettings::grow_cutoff],
+///   and off once it drops [LIGHT_HYSTERESIS] below it
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::light_sensor::AmbientLight;
+/// use gem_rs::preferences::AmbientSettings;
+///
+/// let settings = AmbientSettings {
+///     calibration: (0, 1000),
+///     backlight_dark: Some(20),
+///     grow_cutoff: Some(70),
+/// };
+/// let mut ambient = AmbientLight::new();
+/// assert_eq!(ambient.format_level().as_str(), "Ambient: --%");
+/// assert!(!ambient.keeps_backlight_on(&settings)); // Nothing read yet
+///
+/// // Daylight: the backlight stays on, and the grow light is skipped
+/// assert_eq!(ambient.update(800, &settings), 80);
+/// assert!(ambient.is_bright());
+/// assert!(ambient.keeps_backlight_on(&settings));
+/// assert_eq!(ambient.format_reading().as_str(), "Raw 800 Bright");
+///
+/// // A cloud passing within the margin changes nothing
+/// ambient.update(680, &settings);
+/// assert!(ambient.is_bright());
+/// ambient.update(650, &settings);
+/// assert!(!ambient.is_bright());
+///
+/// // Night: the backlight may turn off
+/// ambient.update(150, &settings);
+/// assert!(ambient.is_dark());
+/// assert!(!ambient.keeps_backlight_on(&settings));
+/// assert_eq!(ambient.format_level().as_str(), "Ambient: 15%");
+///
+/// // Turned off, neither is ever on
+/// let off = AmbientSettings { backlight_dark: None, grow_cutoff: None, ..settings };
+/// ambient.update(900, &off);
+/// assert!(!ambient.is_dark() && !ambient.is_bright());
+/// assert!(!ambient.keeps_backlight_on(&off));
+/// ```
+pub struct AmbientLight {
+    raw: Option<u16>,
+    level: Option<u8>,
+    dark: Hysteresis<u8>,
+    bright: Hysteresis<u8>,
+}
+
+impl Default for AmbientLight {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AmbientLight {
+    /// Creates a new instance of AmbientLight, with no reading yet
+    ///
+    /// returns a new instance of AmbientLight
+    pub fn new() -> AmbientLight {
+        Self {
+            raw: None,
+            level: None,
+            dark: Hysteresis::falling((0, 0)),
+            bright: Hysteresis::rising((0, 0)),
+        }
+    }
+
+    /// Feeds a new reading in
+    ///
+    /// - param raw: The raw reading from [crate::soil::read_raw()]
+    /// - param settings: The [AmbientSettings]
+    ///
+    /// returns the brightness percentage
+    pub fn update(&mut self, raw: u16, settings: &AmbientSettings) -> u8 {
+        let level = brightness_percent(raw, settings.calibration);
+        self.raw = Some(raw);
+        self.level = Some(level);
+
+        match settings.backlight_dark {
+            Some(threshold) => {
+                self.dark
+                    .set_points((threshold, threshold.saturating_add(LIGHT_HYSTERESIS)));
+                self.dark.update(level);
+            }
+            None => self.dark.reset(),
+        }
+        match settings.grow_cutoff {
+            Some(threshold) => {
+                self.bright
+                    .set_points((threshold.saturating_sub(LIGHT_HYSTERESIS), threshold));
+                self.bright.update(level);
+            }
+            None => self.bright.reset(),
+        }
+        level
+    }
+
+    /// Gets the brightness
+    ///
+    /// returns the last brightness percentage, or None before the first reading
+    pub fn level(&self) -> Option<u8> {
+        self.level
+    }
+
+    /// Checks if it is dark enough for the backlight to turn off while idle
+    ///
+    /// returns true from when it got dark until it got light again
+    pub fn is_dark(&self) -> bool {
+        self.dark.is_on()
+    }
+
+    /// Checks if it is bright enough that the grow light is skipped
+    ///
+    /// returns true from when it got bright until it dimmed again
+    pub fn is_bright(&self) -> bool {
+        self.bright.is_on()
+    }
+
+    /// Checks if the backlight should stay on while idle. With [AmbientSettings::backlight_dark] set,
+    /// it stays on while it is light, so the display can be read at a glance, and only turns off once it is dark.
+    /// Otherwise, and before the first reading, it turns off after the usual timeout
+    ///
+    /// - param settings: The [AmbientSettings]
+    ///
+    /// returns true if the backlight should not time out
+    pub fn keeps_backlight_on(&self, settings: &AmbientSettings) -> bool {
+        settings.backlight_dark.is_some() && self.level.is_some() && !self.is_dark()
+    }
+
+    /// Formats the brightness for the LCD: `Ambient: 72%`, or `Ambient: --%` before the first reading
+    ///
+    /// returns a [String] of length 16 containing the formatted brightness
+    pub fn format_level(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.level {
+            Some(level) => uwrite!(str, "{}: {}%", AMBIENT, level),
+            None => uwrite!(str, "{}: --%", AMBIENT),
+        }
+        .unwrap();
+        str
+    }
+
+    /// Formats the raw reading and what it is doing: `Raw 2100 Dark`, `Raw 2100 Bright`, or `Raw 2100`
+    ///
+    /// returns a [String] of length 16 containing the formatted reading
+    pub fn format_reading(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.raw {
+            Some(raw) => uwrite!(str, "{} {}", RAW_READING, raw).unwrap(),
+            None => uwrite!(str, "{} {}", RAW_READING, NO_READING).unwrap(),
+        }
+        if self.is_dark() {
+            uwrite!(str, " {}", AMBIENT_DARK).unwrap();
+        } else if self.is_bright() {
+            uwrite!(str, " {}", AMBIENT_BRIGHT).unwrap();
+        }
+        str
+    }
+}
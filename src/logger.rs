@@ -0,0 +1,170 @@
+use embedded_sdmmc::{
+    BlockDevice, Mode, RawFile, RawVolume, TimeSource, Timestamp, VolumeIdx, VolumeManager,
+};
+use heapless::String;
+use ufmt::uwrite;
+
+use crate::preferences::Preferences;
+use crate::sensors::SensorReading;
+
+use panic_probe as _;
+
+/// The file readings are logged to. FAT only supports 8.3 names, so `greenhouse.csv` is shortened
+pub const LOG_FILE: &str = "GREENHSE.CSV";
+/// The header written to a new log file
+const LOG_HEADER: &str =
+    "timestamp,temperature_f,humidity,pressure_mb,vent,sprinklers,heater,light\n";
+/// How many rows are written before the log file is flushed to the card
+pub const LOG_FLUSH_INTERVAL: u8 = 10;
+
+/// Whether each actuator is currently running
+///
+/// - **vent**: The roof vent is open
+/// - **sprinklers**: The sprinklers are on
+/// - **heater**: The heater is on
+/// - **light**: The grow light is on
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct ActuatorStates {
+    pub vent: bool,
+    pub sprinklers: bool,
+    pub heater: bool,
+    pub light: bool,
+}
+
+/// A [TimeSource] for file timestamps.
+/// The SD card does not share the [Preferences] clock, so every file is stamped `00:00:00 Jan 1 2000`
+pub struct LogClock;
+
+impl TimeSource for LogClock {
+    fn get_timestamp(&self) -> Timestamp {
+        Timestamp {
+            year_since_1970: 30,
+            zero_indexed_month: 0,
+            zero_indexed_day: 0,
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+        }
+    }
+}
+
+/// Logs sensor readings to an SD card as CSV rows
+///
+/// - **volume_mgr**: The SD card's [VolumeManager]
+/// - **volume**: The opened FAT volume
+/// - **file**: The opened log file, or None if logging is disabled
+/// - **unflushed**: Rows written since the last flush
+pub struct Logger<D: BlockDevice> {
+    volume_mgr: VolumeManager<D, LogClock>,
+    volume: Option<RawVolume>,
+    file: Option<RawFile>,
+    unflushed: u8,
+}
+
+impl<D: BlockDevice> Logger<D> {
+    /// Creates a new Logger, opening or creating [LOG_FILE] on the first volume.
+    /// A header is written if the file is new.
+    /// If the card is missing or unusable, logging is disabled
+    ///
+    /// - param block_device: The SD card
+    ///
+    /// returns a new instance of Logger
+    pub fn new(block_device: D) -> Logger<D> {
+        let mut logger = Self {
+            volume_mgr: VolumeManager::new(block_device, LogClock),
+            volume: None,
+            file: None,
+            unflushed: 0,
+        };
+        if logger.open().is_err() {
+            logger.disable();
+        }
+        logger
+    }
+
+    /// Opens the log file and writes the header if needed
+    ///
+    /// returns an error if the card could not be used
+    fn open(&mut self) -> Result<(), embedded_sdmmc::Error<D::Error>> {
+        let volume = self.volume_mgr.open_raw_volume(VolumeIdx(0))?;
+        self.volume = Some(volume);
+        let root = self.volume_mgr.open_root_dir(volume)?;
+        let file = self
+            .volume_mgr
+            .open_file_in_dir(root, LOG_FILE, Mode::ReadWriteCreateOrAppend);
+        self.volume_mgr.close_dir(root)?;
+        let file = file?;
+        self.file = Some(file);
+
+        if self.volume_mgr.file_length(file)? == 0 {
+            self.volume_mgr.write(file, LOG_HEADER.as_bytes())?;
+            self.volume_mgr.flush_file(file)?;
+        }
+        Ok(())
+    }
+
+    /// Checks if readings are being logged
+    ///
+    /// returns true if the log file is open
+    pub fn is_enabled(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// Appends a CSV row with the current time, readings, and actuator states.
+    /// Rows are flushed every [LOG_FLUSH_INTERVAL] writes to limit card wear.
+    /// If writing fails, e.g. because the card is full or removed, logging is disabled
+    ///
+    /// - param reading: The [SensorReading]
+    /// - param preferences: [Preferences] instance
+    /// - param actuators: [ActuatorStates] after this reading was acted on
+    pub fn log_reading(
+        &mut self,
+        reading: &SensorReading,
+        preferences: &Preferences,
+        actuators: &ActuatorStates,
+    ) {
+        let Some(file) = self.file else {
+            return;
+        };
+
+        // ISO 8601 whatever the display settings are, so the log is easy to parse
+        let timestamp = preferences.local_date().format_iso();
+        let mut row: String<64> = String::new();
+        uwrite!(
+            row,
+            "{},{},{},{},{},{},{},{}\n",
+            timestamp.as_str(),
+            reading.temperature,
+            reading.humidity,
+            reading.pressure,
+            actuators.vent as u8,
+            actuators.sprinklers as u8,
+            actuators.heater as u8,
+            actuators.light as u8,
+        )
+        .unwrap();
+
+        if self.volume_mgr.write(file, row.as_bytes()).is_err() {
+            self.disable();
+            return;
+        }
+
+        self.unflushed += 1;
+        if self.unflushed >= LOG_FLUSH_INTERVAL {
+            self.unflushed = 0;
+            if self.volume_mgr.flush_file(file).is_err() {
+                self.disable();
+            }
+        }
+    }
+
+    /// Stops logging and releases the card's handles
+    fn disable(&mut self) {
+        if let Some(file) = self.file.take() {
+            self.volume_mgr.close_file(file).ok();
+        }
+        if let Some(volume) = self.volume.take() {
+            self.volume_mgr.close_volume(volume).ok();
+        }
+    }
+}
@@ -1,16 +1,13 @@
 #![no_std]
 #![no_main]
 
-use bme680::{
-    Bme680, FieldData, I2CAddress, IIRFilterSize, OversamplingSetting, PowerMode, SettingsBuilder,
-};
 use bsp::entry;
-use core::time::Duration;
+use core::cell::RefCell;
+use cortex_m_rt::{exception, ExceptionFrame};
 use defmt_rtt as _;
 use embedded_hal::delay::DelayNs;
-use embedded_hal::digital::InputPin;
-use embedded_hal::digital::OutputPin;
-use embedded_hal::digital::StatefulOutputPin;
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::i2c::I2c;
 use panic_probe as _;
 use rp_pico::hal::Timer;
 
@@ -23,30 +20,87 @@ use bsp::hal::{
     pac,
     watchdog::Watchdog,
 };
-use gem_rs::preferences::{inclusive_iterator, Preferences};
+use embedded_hal_bus::i2c::RefCellDevice;
+use gem_rs::battery::BatteryMonitor;
+use gem_rs::buttons::{ButtonPad, ButtonPresses, Debouncer, SMOKE_SAMPLES};
+use gem_rs::buzzer::{
+    BuzzerPattern, BuzzerPlayer, FIRE_PATTERN, FROST_PATTERN, READING_ALARM_PATTERN,
+    SENSOR_FAULT_PATTERN, WATERING_CHIRP,
+};
+use gem_rs::datetime::ClockFormat;
+use gem_rs::edit::{DateField, EditState};
+use gem_rs::eeprom::Eeprom;
+use gem_rs::error::GemError;
+use gem_rs::failsafe;
+use gem_rs::graph::GraphSeries;
+use gem_rs::history::WateringHistory;
+use gem_rs::light_sensor::AmbientLight;
+use gem_rs::messages::{
+    on_off, yes_no, AIR, ALARM, ALARM_SILENCED, ALL_AUTOMATIC, ALL_FEATURES_OFF,
+    AMBIENT_BRIGHT_PROMPT, AMBIENT_DARK_PROMPT, BATTERY_LOW, BATTERY_OK, BUS_ERRORS, BUZZER,
+    BUZZER_LETTER, CLOCK_12H, CLOCK_24H, CLOCK_FORMAT, DAILY_HIGH, DAILY_LOW, DATE_FORMAT, DAY,
+    DEW_POINT, DST, DST_CUSTOM, DST_EU, DST_US, FEATURES_TITLE, FEELS_LIKE, FIRE, FROST, GAS,
+    HEATER, HEATER_LETTER, HOUR, HUMIDITY, HUMIDITY_SHORT, LAST_OK, LIGHT, LIGHT_OFF_HOUR,
+    LIGHT_OFF_MINUTE, LIGHT_ON_HOUR, LIGHT_ON_MINUTE, LOW_BATTERY, MARGINS_TITLE, MINUTE, MONTH,
+    NEVER, NO_DATA_TODAY, NO_READING, NO_READINGS_YET, NO_SOIL_SENSOR, NO_WATERING_YET, OFF,
+    OVERRIDDEN, OVERRIDES_TITLE, OVERRIDE_MARK, POLL_INTERVAL, PRESSURE, PRESSURE_UNIT,
+    RAW_READING, READS_OK, RESET, RESET_HINT, RESET_PROMPT, RESET_TITLE, RISING, SAVING_POWER,
+    SELF_TEST_TITLE, SENSOR_MISSING, SOIL, SOIL_DRY_PROMPT, SOIL_SENSOR, SOIL_WET_PROMPT,
+    SPRINKLERS, SPRINKLERS_LETTER, TEMPERATURE, UPTIME_TITLE, VENT, VENT_LETTER, VPD, WARMING_UP,
+    WATERING, WATERING_DAYS, WATERING_LOG_TITLE, WATERING_RUN, WATERING_SHORT, WATERING_WINDOW,
+    WHOLE_WINDOW, YEAR, ZONE,
+};
+use gem_rs::observer::{Actuator, ActuatorObserver};
+use gem_rs::overrides::{format_override, Overrides};
+use gem_rs::preferences::{vpd_status, DstRule, PreferenceStore, Preferences, SENSOR_INTERVALS};
+#[cfg(feature = "relay-vent")]
+use gem_rs::relay::VENT_RELAY_POLARITY;
+use gem_rs::relay::{
+    ActivePolarity, Relay, GROW_LIGHT_POLARITY, HEATER_POLARITY, SPRINKLER_POLARITY,
+};
 use gem_rs::rendering::{
-    render_date_edit_screen, render_edit_screen, render_screen, render_selector,
-    render_time_config_screen, render_watering_edit_screen, Lcd,
+    load_glyphs, render_date_edit_screen, render_days_edit_screen, render_edit_screen,
+    render_fault, render_gauge, render_screen, render_scrolling, render_sparkline, render_splash,
+    render_two_lines, render_watering_edit_screen, set_backlight, setup_display, Backlight,
+    GlyphSet, Lcd, LCD_INIT_RETRIES,
+};
+use gem_rs::rtc::{Rtc, RtcError};
+use gem_rs::screen::Screen;
+use gem_rs::selftest::{SelfTest, FAILED_RESULT_MS, RESULT_MS, SPLASH_MS};
+use gem_rs::sensors::{
+    averaged, format_hundredths, format_sensor_reading, SensorFilter, Trend, HEAT_INDEX_DIFFERENCE,
 };
-use gem_rs::sensors::{get_bme_data, get_humidity, get_pressure, get_temperature};
-use gem_rs::timer::{CountDownTimer, SCREEN_BUTTON_DELAY, SENSOR_DELAY, TICK_TIME_DELAY};
+#[cfg(not(feature = "light-sensor"))]
+use gem_rs::soil::moisture_percent;
+use gem_rs::soil::read_raw;
+use gem_rs::telemetry;
+use gem_rs::telemetry::actuator::ActuatorLog;
+use gem_rs::timer::{
+    CountDownTimer, EditTimer, SecondCounter, Uptime, ALARM_SILENCE_TIME, AUTO_CYCLE_PAUSE,
+    BACKLIGHT_TIMEOUT, FAULT_RETRY_DELAY, SCREEN_BUTTON_DELAY, SENSOR_DELAY, TICK_TIME_DELAY,
+};
+use gem_rs::zone::{share_clock, Zone, ZoneOutputs, ZONE_COUNT, ZONE_SENSORS};
 use hd44780_driver::bus::FourBitBusPins;
 use hd44780_driver::memory_map::MemoryMap1602;
 use hd44780_driver::setup::DisplayOptions4Bit;
-use hd44780_driver::{Cursor, CursorBlink, HD44780};
+use hd44780_driver::HD44780;
 use heapless::String;
 use i2c_pio::I2C;
 use rp_pico::hal;
-use rp_pico::hal::fugit::RateExtU32;
-use rp_pico::hal::gpio::bank0::{Gpio10, Gpio11, Gpio12};
-use rp_pico::hal::gpio::{FunctionSio, Pin, PullDown, SioInput};
+use rp_pico::hal::fugit::{ExtU32, RateExtU32};
+use rp_pico::hal::gpio::{FunctionI2C, Pin, PullUp};
 use rp_pico::hal::pio::PIOExt;
 use ufmt::uwrite;
 
-const FIRE: &str = "Fire Present";
+/// Seconds without a feed before the watchdog resets the board
+const WATCHDOG_TIMEOUT: u32 = 8;
 
 #[entry]
 fn main() -> ! {
+    // Mark the unused stack, before anything else uses it
+    #[cfg(feature = "stack-usage")]
+    gem_rs::stack::paint();
+
     // Grab our singleton objects
     let mut pac = pac::Peripherals::take().unwrap();
     let _core = pac::CorePeripherals::take().unwrap();
@@ -80,39 +134,69 @@ fn main() -> ! {
         &mut pac.RESETS,
     );
 
+    telemetry::boot::started();
+
     // Set up delays
     let mut delay = Timer::new(pac.TIMER, &mut pac.RESETS, &clocks);
     let mut button_countdown = CountDownTimer::new(0);
-    let mut sensor_countdown = CountDownTimer::new(0);
-    let mut time_countdown = CountDownTimer::new(0);
+    let mut sensor_countdown = CountDownTimer::new_repeating(SENSOR_DELAY);
+    sensor_countdown.set_time(0); // Read the sensors right away
+    let mut clock_seconds = SecondCounter::new(delay.get_counter().ticks());
+    let mut uptime = Uptime::new(delay.get_counter().ticks());
+    let mut backlight_countdown = CountDownTimer::new(BACKLIGHT_TIMEOUT);
+    // Linger on the first screen after boot, as after a button press
+    let mut cycle_countdown = CountDownTimer::new(AUTO_CYCLE_PAUSE);
 
     let (mut pio, sm0, _, _, _) = pac.PIO0.split(&mut pac.RESETS);
 
-    let i2c_pio = I2C::new(
+    let i2c_bus = RefCell::new(I2C::new(
         &mut pio,
         pins.gpio8,
         pins.gpio9,
         sm0,
         100.kHz(),
         clocks.system_clock.freq(),
-    );
+    ));
 
-    // Set up BME680
-    let mut bme = Bme680::init(i2c_pio, &mut delay, I2CAddress::Secondary).unwrap();
-    let settings = SettingsBuilder::new()
-        .with_humidity_oversampling(OversamplingSetting::OS2x)
-        .with_pressure_oversampling(OversamplingSetting::OS4x)
-        .with_temperature_oversampling(OversamplingSetting::OS8x)
-        .with_temperature_filter(IIRFilterSize::Size3)
-        .with_temperature_offset(-8.9)
-        .with_gas_measurement(Duration::from_millis(1500), 320, 25)
-        .with_run_gas(true)
-        .build();
-
-    bme.set_sensor_settings(&mut delay, settings).unwrap();
-
-    bme.set_sensor_mode(&mut delay, PowerMode::ForcedMode)
-        .unwrap();
+    // Set up SD card logging; the second zone uses its pins instead
+    #[cfg(not(feature = "two-zones"))]
+    let mut logger = {
+        use embedded_hal_bus::spi::ExclusiveDevice;
+        use embedded_sdmmc::SdCard;
+        use gem_rs::logger::Logger;
+        use rp_pico::hal::gpio::FunctionSpi;
+        use rp_pico::hal::spi::Spi;
+
+        let spi_sclk = pins.gpio18.into_function::<FunctionSpi>();
+        let spi_mosi = pins.gpio19.into_function::<FunctionSpi>();
+        let spi_miso = pins.gpio20.into_function::<FunctionSpi>();
+        let spi_cs = pins.gpio21.into_push_pull_output();
+        let spi = Spi::<_, _, _, 8>::new(pac.SPI0, (spi_mosi, spi_miso, spi_sclk)).init(
+            &mut pac.RESETS,
+            clocks.peripheral_clock.freq(),
+            400.kHz(),
+            embedded_hal::spi::MODE_0,
+        );
+        let sd_spi = ExclusiveDevice::new(spi, spi_cs, delay).unwrap();
+        Logger::new(SdCard::new(sd_spi, delay))
+    };
+
+    // Set up DS3231 RTC
+    let rtc_sda: Pin<_, FunctionI2C, PullUp> = pins.gpio26.reconfigure();
+    let rtc_scl: Pin<_, FunctionI2C, PullUp> = pins.gpio27.reconfigure();
+    let rtc_bus = RefCell::new(hal::I2C::i2c1(
+        pac.I2C1,
+        rtc_sda,
+        rtc_scl,
+        100.kHz(),
+        &mut pac.RESETS,
+        &clocks.system_clock,
+    ));
+    let mut rtc = Some(Rtc::new(RefCellDevice::new(&rtc_bus)));
+
+    // Set up the AT24C32 EEPROM on the RTC module, which keeps each zone's settings
+    let mut eeproms: [_; ZONE_COUNT] =
+        core::array::from_fn(|slot| Eeprom::new(RefCellDevice::new(&rtc_bus), delay, slot));
 
     // Set up LCD1602
     let rs = pins.gpio0.into_push_pull_output();
@@ -122,30 +206,51 @@ fn main() -> ! {
     let d6 = pins.gpio4.into_push_pull_output();
     let d7 = pins.gpio5.into_push_pull_output();
 
-    let lcd_result = HD44780::new(
-        DisplayOptions4Bit::new(MemoryMap1602::new()).with_pins(FourBitBusPins {
-            rs: rs.into_push_pull_output(), // Register Select pin,
-            en: en.into_push_pull_output(), // Enable pin,
-
-            d4: d4.into_push_pull_output(), // d4,
-            d5: d5.into_push_pull_output(), // d5,
-            d6: d6.into_push_pull_output(), // d6,
-            d7: d7.into_push_pull_output(), // d7,
-        }),
-        &mut delay,
-    );
+    let mut lcd_options = DisplayOptions4Bit::new(MemoryMap1602::new()).with_pins(FourBitBusPins {
+        rs: rs.into_push_pull_output(), // Register Select pin,
+        en: en.into_push_pull_output(), // Enable pin,
+
+        d4: d4.into_push_pull_output(), // d4,
+        d5: d5.into_push_pull_output(), // d5,
+        d6: d6.into_push_pull_output(), // d6,
+        d7: d7.into_push_pull_output(), // d7,
+    });
+
+    // The display may still be powering up, so give it a few tries.
+    // Without one the controller runs headless; the greenhouse doesn't need a screen to be kept safe
+    let mut lcd: Option<Lcd> = None;
+    for attempt in 1..=LCD_INIT_RETRIES {
+        match HD44780::new(lcd_options, &mut delay) {
+            Ok(display) => {
+                lcd = Some(display);
+                break;
+            }
+            Err((options, _)) => {
+                telemetry::display::init_failed(attempt);
+                lcd_options = options;
+                delay.delay_ms(200);
+            }
+        }
+    }
 
-    let mut lcd: Lcd = match lcd_result {
-        Ok(lcd) => lcd,
-        Err(_) => {
-            // Handle the error appropriately here
-            panic!("Failed to initialize the LCD");
+    // The glyphs in the display's CGRAM, once they are known
+    let mut glyphs: Option<GlyphSet> = None;
+    match lcd.as_mut() {
+        // A display that fails here is set up again once rendering to it fails
+        Some(lcd) => {
+            if setup_display(lcd, &mut delay).is_ok() {
+                glyphs = Some(GlyphSet::Text);
+                // Show which firmware is running
+                render_splash(lcd, &mut delay).ok();
+                delay.delay_ms(SPLASH_MS);
+            }
         }
-    };
+        None => telemetry::display::headless(),
+    }
 
-    lcd.set_cursor_visibility(Cursor::Invisible, &mut delay)
-        .unwrap();
-    lcd.set_cursor_blink(CursorBlink::Off, &mut delay).unwrap();
+    // Set up LCD backlight
+    let mut backlight = pins.gpio16.into_push_pull_output();
+    set_backlight(true, &mut backlight);
 
     // Set up button up
     let mut up_button = pins.gpio10.into_pull_down_input();
@@ -156,498 +261,1296 @@ fn main() -> ! {
     // Set up button select
     let mut select_button = pins.gpio12.into_pull_down_input();
 
+    // Set up button debouncing
+    let mut button_pad = ButtonPad::new();
+
     // Set up buzzer
     let mut buzzer = pins.gpio6.into_push_pull_output();
+    failsafe::guard(buzzer.id().num, ActivePolarity::High);
 
     // Set up smoke detector
     let mut smoke_detector = pins.gpio7.into_pull_down_input();
+    let mut smoke_debouncer = Debouncer::with_samples(SMOKE_SAMPLES);
+
+    // Set up sprinklers; the relays start off, whichever level that is
+    let mut sprinklers = pins
+        .gpio13
+        .into_push_pull_output_in_state(SPRINKLER_POLARITY.inactive_state());
+    failsafe::guard(sprinklers.id().num, SPRINKLER_POLARITY);
+
+    // Set up heater
+    let mut heater = pins
+        .gpio15
+        .into_push_pull_output_in_state(HEATER_POLARITY.inactive_state());
+    failsafe::guard(heater.id().num, HEATER_POLARITY);
+
+    // Set up grow light
+    let mut grow_light = pins
+        .gpio17
+        .into_push_pull_output_in_state(GROW_LIGHT_POLARITY.inactive_state());
+    failsafe::guard(grow_light.id().num, GROW_LIGHT_POLARITY);
+
+    // Set up cooling fan PWM
+    let pwm_slices = hal::pwm::Slices::new(pac.PWM, &mut pac.RESETS);
+    let mut fan_pwm = pwm_slices.pwm3;
+    fan_pwm.set_ph_correct();
+    fan_pwm.enable();
+    let mut fan = fan_pwm.channel_a;
+    failsafe::guard(fan.output_to(pins.gpio22).id().num, ActivePolarity::High);
+
+    // Set up roof vent servo: 125MHz / 125 counts 1us, so the period is 20ms (50Hz)
+    #[cfg(not(feature = "relay-vent"))]
+    let mut vent_output = {
+        let mut vent_pwm = pwm_slices.pwm7;
+        vent_pwm.set_div_int(125);
+        vent_pwm.set_top(gem_rs::vent::SERVO_PERIOD_US - 1);
+        vent_pwm.enable();
+        let mut channel = vent_pwm.channel_a;
+        failsafe::guard(
+            channel.output_to(pins.gpio14).id().num,
+            ActivePolarity::High,
+        );
+        channel
+    };
+    // Set up roof vent relay
+    #[cfg(feature = "relay-vent")]
+    let mut vent_pin = pins
+        .gpio14
+        .into_push_pull_output_in_state(VENT_RELAY_POLARITY.inactive_state());
+    #[cfg(feature = "relay-vent")]
+    failsafe::guard(vent_pin.id().num, VENT_RELAY_POLARITY);
+    #[cfg(feature = "relay-vent")]
+    let mut vent_output = Relay::new(&mut vent_pin, VENT_RELAY_POLARITY);
+
+    // Set up the second zone's outputs on the SD card's pins
+    #[cfg(feature = "two-zones")]
+    let (mut sprinklers_2, mut heater_2, mut fan_2) = {
+        let mut fan_pwm = pwm_slices.pwm1;
+        fan_pwm.set_ph_correct();
+        fan_pwm.enable();
+        let mut fan = fan_pwm.channel_a;
+        failsafe::guard(fan.output_to(pins.gpio18).id().num, ActivePolarity::High);
+        let sprinklers = pins
+            .gpio21
+            .into_push_pull_output_in_state(SPRINKLER_POLARITY.inactive_state());
+        failsafe::guard(sprinklers.id().num, SPRINKLER_POLARITY);
+        let heater = pins
+            .gpio19
+            .into_push_pull_output_in_state(HEATER_POLARITY.inactive_state());
+        failsafe::guard(heater.id().num, HEATER_POLARITY);
+        (sprinklers, heater, fan)
+    };
+    #[cfg(all(feature = "two-zones", not(feature = "relay-vent")))]
+    let mut vent_output_2 = {
+        let mut vent_pwm = pwm_slices.pwm2;
+        vent_pwm.set_div_int(125);
+        vent_pwm.set_top(gem_rs::vent::SERVO_PERIOD_US - 1);
+        vent_pwm.enable();
+        let mut channel = vent_pwm.channel_a;
+        failsafe::guard(
+            channel.output_to(pins.gpio20).id().num,
+            ActivePolarity::High,
+        );
+        channel
+    };
+    #[cfg(all(feature = "two-zones", feature = "relay-vent"))]
+    let mut vent_pin_2 = pins
+        .gpio20
+        .into_push_pull_output_in_state(VENT_RELAY_POLARITY.inactive_state());
+    #[cfg(all(feature = "two-zones", feature = "relay-vent"))]
+    failsafe::guard(vent_pin_2.id().num, VENT_RELAY_POLARITY);
+    #[cfg(all(feature = "two-zones", feature = "relay-vent"))]
+    let mut vent_output_2 = Relay::new(&mut vent_pin_2, VENT_RELAY_POLARITY);
 
-    // Set up sprinklers
-    let mut sprinklers = pins.gpio13.into_push_pull_output();
+    // Set up soil moisture sensor, or the light sensor in its place
+    let mut adc = hal::Adc::new(pac.ADC, &mut pac.RESETS);
+    #[cfg(not(feature = "light-sensor"))]
+    let mut soil_pin = hal::adc::AdcPin::new(pins.gpio28.into_floating_input()).unwrap();
+    #[cfg(feature = "light-sensor")]
+    let mut light_pin = hal::adc::AdcPin::new(pins.gpio28.into_floating_input()).unwrap();
+    // Without the light sensor it is never dark or bright, so the backlight and grow light carry on as usual
+    #[cfg_attr(not(feature = "light-sensor"), allow(unused_mut))]
+    let mut ambient = AmbientLight::new();
 
-    // Set up roof vent
-    let mut roof_vent = pins.gpio14.into_push_pull_output();
+    // Set up battery monitor, on the same ADC
+    let mut battery_pin =
+        hal::adc::AdcPin::new(pins.voltage_monitor.into_floating_input()).unwrap();
+    let mut battery = BatteryMonitor::new();
 
-    let mut current_screen_index: u8 = 0;
-    let mut data: FieldData = FieldData::default();
-    let mut preferences: Preferences = Preferences::default();
+    // The soil sensor and grow light belong to the first zone
+    let mut zones = [
+        Zone::new(
+            Preferences::default(),
+            ZONE_SENSORS[0],
+            ZoneOutputs::new(
+                &mut vent_output,
+                &mut fan,
+                Relay::new(&mut heater, HEATER_POLARITY),
+                Relay::new(&mut sprinklers, SPRINKLER_POLARITY),
+                Some(Relay::new(&mut grow_light, GROW_LIGHT_POLARITY)),
+            ),
+        ),
+        #[cfg(feature = "two-zones")]
+        Zone::new(
+            Preferences::default(),
+            ZONE_SENSORS[1],
+            ZoneOutputs::new(
+                &mut vent_output_2,
+                &mut fan_2,
+                Relay::new(&mut heater_2, HEATER_POLARITY),
+                Relay::new(&mut sprinklers_2, SPRINKLER_POLARITY),
+                None,
+            ),
+        ),
+    ];
+
+    // Use the saved settings; without an EEPROM or anything saved in it, the defaults are used
+    for (zone, eeprom) in zones.iter_mut().zip(eeproms.iter_mut()) {
+        if let Some(preferences) = eeprom.load() {
+            zone.preferences = preferences;
+            zone.refresh_points();
+        }
+    }
+
+    // Set up the BME680s; the display shows the problem if one is missing
+    for zone in zones.iter_mut() {
+        zone.connect_sensor(&i2c_bus, &mut delay).ok();
+    }
+
+    // Log every actuator turning on or off
+    let mut observer = ActuatorLog;
+
+    // Check every subsystem before the unit is left unattended
+    let mut self_test = SelfTest::new(lcd.is_some());
+    for (index, zone) in zones.iter_mut().enumerate() {
+        self_test.sensors[index] = zone.sensors.is_connected();
+        self_test.outputs[index] = zone.test_outputs(&mut delay);
+        if !self_test.sensors[index] {
+            // Nothing to act on, so hold the zone safe; it is taken up again once its sensor answers
+            zone.fail_safe(&mut observer);
+        }
+    }
+    for (line, passed) in self_test.results() {
+        telemetry::boot::self_test(&line, passed);
+        if let Some(lcd) = lcd.as_mut() {
+            render_screen(SELF_TEST_TITLE, true, lcd, &mut delay).ok();
+            render_screen(&line, false, lcd, &mut delay).ok();
+            delay.delay_ms(if passed { RESULT_MS } else { FAILED_RESULT_MS });
+        }
+    }
+
+    let mut current_screen = Screen::Temperature;
+    let mut current_zone: usize = 0;
+    let mut watering_index: usize = 0;
+    let mut scroll_offset: usize = 0;
+    let mut graph_series = GraphSeries::Temperature;
+    let mut alarm: Option<BuzzerPattern> = None;
+    let mut sensor_faulted: bool = false;
+    let mut buzzer_player = BuzzerPlayer::new();
+    let mut buzzer_sounding: bool = false;
+    let mut overrides = Overrides::default();
+    let mut edit: Option<EditState> = None;
+    let mut edit_timer = EditTimer::new();
+    let mut redraw_edit: bool = false;
+
+    // Use the RTC's clock if it responds, otherwise keep time in software.
+    // An RTC with an invalid date is kept so it can be set from the date screen
+    if let Some(clock) = rtc.as_mut() {
+        if zones[0].preferences.sync_from_rtc(clock) == Err(RtcError::Bus) {
+            rtc = None;
+        }
+    }
+    share_clock(&mut zones, 0);
+
+    // Reset if the main loop ever stalls, e.g. on a hung I2C read
+    watchdog.pause_on_debug(true);
+    watchdog.start(WATCHDOG_TIMEOUT.secs());
 
     loop {
         // Delay loop
         delay.delay_ms(1);
+        watchdog.feed();
+
+        // Handle a fire as soon as the smoke is confirmed, rather than on the next sensor poll
+        if smoke_debouncer.update(&mut smoke_detector) {
+            sensor_countdown.set_time(0);
+        }
+
+        // Play the current alarm's pattern; the fire alarm plays its own while it lasts
+        if buzzer_player.is_playing() {
+            let beep = buzzer_player.tick();
+            buzzer.set_state(overrides.buzzer_or(beep).into()).unwrap();
+        }
+        // Only whole patterns are changes, not the beeps within them
+        let sounding = overrides.buzzer_or(buzzer_player.is_playing());
+        if sounding != buzzer_sounding {
+            buzzer_sounding = sounding;
+            let date = zones[0].preferences.local_date();
+            observer.on_change(Actuator::Buzzer, sounding, &date);
+        }
 
-        let action = should_update(
+        let seconds = uptime.seconds();
+        let keep_backlight = ambient.keeps_backlight_on(&zones[0].preferences.ambient);
+        let mut action = should_update(
             &mut up_button,
             &mut down_button,
             &mut select_button,
-            &mut preferences,
+            &mut button_pad,
+            &mut zones[0].preferences,
             &mut button_countdown,
             &mut sensor_countdown,
-            &mut time_countdown,
+            &mut clock_seconds,
+            &mut uptime,
+            delay.get_counter().ticks(),
+            &mut rtc,
+            &mut backlight_countdown,
+            &mut backlight,
+            keep_backlight,
         );
+        // Every zone keeps time with the first zone's clock
+        share_clock(&mut zones, 0);
 
-        match action {
-            RefreshAction::Up => {
-                current_screen_index = next_screen(current_screen_index, true);
+        // Move on to the next screen on its own, unless a button was pressed a short while ago
+        cycle_countdown.tick();
+        if matches!(
+            action,
+            RefreshAction::Up
+                | RefreshAction::Down
+                | RefreshAction::Select
+                | RefreshAction::Chord(_)
+        ) {
+            cycle_countdown.set_time(AUTO_CYCLE_PAUSE);
+        } else if cycle_countdown.is_finished()
+            && edit.is_none()
+            && matches!(action, RefreshAction::None)
+        {
+            // Every zone shares the first zone's display settings
+            if let Some(seconds) = zones[0].preferences.auto_cycle_seconds {
+                cycle_countdown.set_time(seconds as u32 * 1000);
+                action = RefreshAction::Cycle;
             }
-            RefreshAction::Down => {
-                current_screen_index = next_screen(current_screen_index, false);
+        }
+
+        // Hand the sprinklers back to the schedule as soon as watering by hand is done
+        for zone in zones.iter_mut() {
+            if zone.manual_watering.tick() {
+                telemetry::watering::manual(false);
+                sensor_countdown.set_time(0);
             }
-            RefreshAction::Select => {
-                // Handle SELECT action
-                lcd.clear(&mut delay).unwrap();
-                let mut editing_lower: bool = true;
-                let mut update_date: bool = false;
-                let mut refresh: bool = true;
-                let mut info_str: String<11> = String::new();
-                match current_screen_index {
-                    0 => {
-                        // Temp
-                        for _ in 0..2 {
-                            loop {
-                                if refresh {
-                                    uwrite!(
-                                        &mut info_str,
-                                        "{} - {}",
-                                        preferences.temperature.0,
-                                        preferences.temperature.1
-                                    )
-                                    .unwrap();
-                                    render_edit_screen(
-                                        &info_str,
-                                        editing_lower,
-                                        &mut lcd,
-                                        &mut delay,
-                                    );
-                                    info_str.clear();
-                                    refresh = false;
-                                }
+            if zone.gas_warmup.tick() {
+                for slot in zone
+                    .sensors
+                    .slots()
+                    .iter()
+                    .filter(|slot| slot.sensor.is_some())
+                {
+                    telemetry::sensor::warmed_up(slot.address);
+                }
+            }
+        }
 
-                                delay.delay_ms(500);
+        if let Some(state) = edit.as_mut() {
+            // Holding UP or DOWN keeps changing the value
+            let held = button_pad.held();
+            if edit_timer.repeat(held.up != held.down && !held.select)
+                && matches!(action, RefreshAction::None)
+            {
+                backlight_countdown.set_time(BACKLIGHT_TIMEOUT);
+                action = if held.up {
+                    RefreshAction::Up
+                } else {
+                    RefreshAction::Down
+                };
+            }
 
-                                if update_date {
-                                    preferences.tick_time();
-                                }
-                                update_date = !update_date;
-
-                                if up_button.is_high().unwrap() {
-                                    if editing_lower {
-                                        if preferences.temperature.0 < 100 {
-                                            preferences.temperature.0 += 1;
-                                        }
-                                    } else if preferences.temperature.1 < 100 {
-                                        preferences.temperature.1 += 1;
-                                    }
-                                    refresh = true;
-                                } else if down_button.is_high().unwrap() {
-                                    if editing_lower {
-                                        if preferences.temperature.0 > 0 {
-                                            preferences.temperature.0 -= 1;
-                                        }
-                                    } else if preferences.temperature.1 > 0 {
-                                        preferences.temperature.1 -= 1;
-                                    }
-                                    refresh = true;
-                                } else if select_button.is_high().unwrap() {
-                                    editing_lower = false;
-                                    render_selector(false, 15, &mut lcd, &mut delay);
-
-                                    refresh = true;
-                                    break;
-                                }
-                            }
+            let ticked = uptime.seconds() != seconds;
+            let zone = &mut zones[current_zone];
+            match state {
+                // The date being edited keeps ticking too
+                EditState::Date { date, .. } => date.tick_by(uptime.seconds() - seconds),
+                // Take a fresh reading every second
+                EditState::TempOffset | EditState::HumidityOffset if ticked => {
+                    let offset = zone.preferences.humidity_offset;
+                    let readings = zone.sensors.read_all(&i2c_bus, &mut delay, offset);
+                    if let Some(reading) = averaged(&readings) {
+                        zone.data = reading;
+                        redraw_edit = true;
+                    }
+                }
+                #[cfg(not(feature = "light-sensor"))]
+                EditState::SoilPoint { .. } if ticked || redraw_edit => {
+                    if let Some(raw) = read_raw(&mut adc, &mut soil_pin) {
+                        state.set_soil_reading(raw);
+                    }
+                    redraw_edit = true;
+                }
+                #[cfg(feature = "light-sensor")]
+                EditState::AmbientPoint { .. } if ticked || redraw_edit => {
+                    if let Some(raw) = read_raw(&mut adc, &mut light_pin) {
+                        state.set_ambient_reading(raw);
+                    }
+                    redraw_edit = true;
+                }
+                _ => {}
+            }
+        }
+
+        let mut sensor_fault = None;
+        match action {
+            // While editing, the buttons change the setting instead of the screen
+            RefreshAction::Up
+            | RefreshAction::Down
+            | RefreshAction::Select
+            | RefreshAction::Chord(_)
+                if edit.is_some() =>
+            {
+                let Some(mut state) = edit else {
+                    continue;
+                };
+                let zone = &mut zones[current_zone];
+                edit = match action {
+                    RefreshAction::Up | RefreshAction::Down => {
+                        let up = matches!(action, RefreshAction::Up);
+                        state.adjust(up, &mut zone.preferences, &mut overrides);
+                        if state == EditState::TempOffset {
+                            // Apply it right away, so the live reading follows it
+                            zone.sensors
+                                .set_temperature_offset(zone.preferences.temp_offset, &mut delay)
+                                .ok();
+                        }
+                        Some(state)
+                    }
+                    RefreshAction::Select => {
+                        state.select(&mut zone.preferences, &mut watering_index)
+                    }
+                    // UP + DOWN removes the watering window being edited
+                    RefreshAction::Chord(presses)
+                        if presses.up && presses.down && !presses.select =>
+                    {
+                        state.remove(&mut zone.preferences, &mut watering_index)
+                    }
+                    _ => Some(state),
+                };
+                redraw_edit = true;
+                if edit.is_none() {
+                    // Apply what the edit changed
+                    match current_screen {
+                        Screen::Calibration => {
+                            // Don't let readings from before the change linger in the average
+                            zone.filter = SensorFilter::with_smoothing(zone.preferences.smoothing);
                         }
-                        // Check legality
-                        if preferences.temperature.0 > preferences.temperature.1 {
-                            core::mem::swap(
-                                &mut preferences.temperature.0,
-                                &mut preferences.temperature.1,
-                            );
+                        Screen::Soil | Screen::Features => zone.soil_control.reset(),
+                        Screen::Reset if state == EditState::Reset { confirmed: true } => {
+                            zone.sensors
+                                .set_temperature_offset(zone.preferences.temp_offset, &mut delay)
+                                .ok();
+                            zone.filter = SensorFilter::with_smoothing(zone.preferences.smoothing);
+                            zone.soil_control.reset();
                         }
+                        _ => {}
+                    }
+                    if matches!(
+                        current_screen,
+                        Screen::Overrides
+                            | Screen::Calibration
+                            | Screen::Margins
+                            | Screen::Battery
+                            | Screen::AirQuality
+                            | Screen::Soil
+                            | Screen::Light
+                            | Screen::Ambient
+                            | Screen::Features
+                            | Screen::Reset
+                    ) {
+                        // Act on the new settings right away
+                        sensor_countdown.set_time(0);
                     }
-                    1 => {
-                        // Humidity
-                        for _ in 0..2 {
-                            loop {
-                                if refresh {
-                                    uwrite!(
-                                        &mut info_str,
-                                        "{}% - {}%",
-                                        preferences.humidity.0,
-                                        preferences.humidity.1
-                                    )
-                                    .unwrap();
-                                    render_edit_screen(
-                                        &info_str,
-                                        editing_lower,
-                                        &mut lcd,
-                                        &mut delay,
-                                    );
-                                    info_str.clear();
-                                    refresh = false;
-                                }
-
-                                delay.delay_ms(500);
 
-                                if update_date {
-                                    preferences.tick_time();
-                                }
-                                update_date = !update_date;
-
-                                if up_button.is_high().unwrap() {
-                                    if editing_lower {
-                                        if preferences.humidity.0 < 100 {
-                                            preferences.humidity.0 += 1;
-                                        }
-                                    } else if preferences.humidity.1 < 100 {
-                                        preferences.humidity.1 += 1;
-                                    }
-                                    refresh = true;
-                                } else if down_button.is_high().unwrap() {
-                                    if editing_lower {
-                                        if preferences.humidity.0 > 0 {
-                                            preferences.humidity.0 -= 1;
-                                        }
-                                    } else if preferences.humidity.1 > 0 {
-                                        preferences.humidity.1 -= 1;
-                                    }
-                                    refresh = true;
-                                } else if select_button.is_high().unwrap() {
-                                    editing_lower = false;
-                                    render_selector(false, 15, &mut lcd, &mut delay);
-                                    refresh = true;
-                                    break;
-                                }
+                    // Fix anything the edit left out of range
+                    zone.preferences.normalize();
+                    zone.refresh_points();
+                    if current_screen == Screen::Date {
+                        if let Some(clock) = rtc.as_mut() {
+                            zone.preferences.sync_to_rtc(clock).ok();
+                        }
+                    }
+                    // The zone's clock was just set if the date was edited
+                    share_clock(&mut zones, current_zone);
+                    // Only save settings that changed
+                    for (index, (zone, eeprom)) in
+                        zones.iter_mut().zip(eeproms.iter_mut()).enumerate()
+                    {
+                        if zone.preferences.is_dirty() {
+                            telemetry::zone::start(index);
+                            telemetry::preferences::changed(&zone.preferences);
+                            if let Err(error) = eeprom.save(&zone.preferences) {
+                                telemetry::preferences::not_saved(error);
                             }
+                            zone.preferences.mark_clean();
+                        }
+                    }
+                    // Clear the selectors off the edit screen; a failing display is caught when the screen is drawn
+                    if let Some(lcd) = lcd.as_mut() {
+                        lcd.clear(&mut delay).ok();
+                    }
+                }
+            }
+            RefreshAction::Up => {
+                current_screen = current_screen.next_enabled(&zones[current_zone].preferences);
+                scroll_offset = 0;
+            }
+            RefreshAction::Down => {
+                current_screen = current_screen.prev_enabled(&zones[current_zone].preferences);
+                scroll_offset = 0;
+            }
+            RefreshAction::Cycle => {
+                current_screen = current_screen.next_cycled(&zones[current_zone].preferences);
+                scroll_offset = 0;
+            }
+            RefreshAction::Chord(presses) => {
+                if presses.up && presses.down && !presses.select {
+                    // UP + DOWN jumps back to the first screen
+                    current_screen = Screen::Temperature;
+                    scroll_offset = 0;
+                } else if presses.up && presses.select && !presses.down {
+                    // UP + SELECT waters the zone now, or stops watering early
+                    let zone = &mut zones[current_zone];
+                    if zone.manual_watering.is_active() {
+                        zone.manual_watering.stop();
+                    } else {
+                        zone.manual_watering
+                            .start(zone.preferences.manual_watering_ms());
+                    }
+                    telemetry::watering::manual(zone.manual_watering.is_active());
+                    // Act on it right away
+                    sensor_countdown.set_time(0);
+                } else {
+                    // No shortcut for this chord
+                    continue;
+                }
+            }
+            RefreshAction::Select => {
+                if let Some(lcd) = lcd.as_mut() {
+                    lcd.clear(&mut delay).ok();
+                }
+                if current_screen == Screen::Zone {
+                    // Show and edit the next zone
+                    current_zone = if current_zone + 1 < ZONE_COUNT {
+                        current_zone + 1
+                    } else {
+                        0
+                    };
+                } else if current_screen == Screen::Graph {
+                    // Graph the next reading
+                    graph_series = graph_series.next();
+                } else {
+                    // Edit the screen's settings, if it has any; the loop keeps running meanwhile
+                    edit = match current_screen {
+                        Screen::History => {
+                            EditState::history(zones[current_zone].watering_history.len())
                         }
-                        // Check legality
-                        if preferences.humidity.0 > preferences.humidity.1 {
-                            core::mem::swap(
-                                &mut preferences.humidity.0,
-                                &mut preferences.humidity.1,
-                            );
+                        _ => EditState::start(
+                            current_screen,
+                            &zones[current_zone].preferences,
+                            watering_index,
+                        ),
+                    };
+                    redraw_edit = true;
+                }
+            }
+            RefreshAction::Sensor => {
+                if smoke_debouncer.is_pressed() {
+                    // Panic!!!
+                    let roof_angles: [u8; ZONE_COUNT] =
+                        core::array::from_fn(|index| zones[index].outputs.vent.angle());
+                    // Make sure the alarm can be seen
+                    set_backlight(true, &mut backlight);
+                    backlight_countdown.set_time(BACKLIGHT_TIMEOUT);
+                    // The alarm carries on even if the display fails
+                    if let Some(lcd) = lcd.as_mut() {
+                        render_screen(FIRE, true, lcd, &mut delay).ok();
+                    }
+                    telemetry::actuator::changed("fire alarm", true);
+                    // The alarm takes over the sprinklers; watering by hand does not resume afterwards
+                    for zone in zones.iter_mut() {
+                        if zone.manual_watering.is_active() {
+                            zone.manual_watering.stop();
+                            telemetry::watering::manual(false);
                         }
                     }
-                    3 => {
-                        // Date
-
-                        preferences.date.1 = render_time_config_screen(
-                            "Minute",
-                            &mut info_str,
-                            0,
-                            59,
-                            preferences.date.1,
-                            &mut preferences,
-                            &mut lcd,
-                            &mut delay,
-                            &mut up_button,
-                            &mut down_button,
-                            &mut select_button,
-                        );
-                        info_str.clear();
-
-                        preferences.date.2 = render_time_config_screen(
-                            "Hour",
-                            &mut info_str,
-                            0,
-                            23,
-                            preferences.date.2,
-                            &mut preferences,
-                            &mut lcd,
-                            &mut delay,
-                            &mut up_button,
-                            &mut down_button,
-                            &mut select_button,
-                        );
-                        info_str.clear();
-
-                        preferences.date.3 = render_time_config_screen(
-                            "Day",
-                            &mut info_str,
-                            1,
-                            preferences.get_days_in_month(),
-                            preferences.date.3,
-                            &mut preferences,
-                            &mut lcd,
-                            &mut delay,
-                            &mut up_button,
-                            &mut down_button,
-                            &mut select_button,
-                        );
-                        info_str.clear();
-
-                        preferences.date.4 = render_time_config_screen(
-                            "Month",
-                            &mut info_str,
-                            1,
-                            12,
-                            preferences.date.4,
-                            &mut preferences,
-                            &mut lcd,
-                            &mut delay,
-                            &mut up_button,
-                            &mut down_button,
-                            &mut select_button,
-                        );
-                        info_str.clear();
-
-                        // Year
-                        loop {
-                            if refresh {
-                                uwrite!(&mut info_str, "Year: {}", preferences.date.5).unwrap();
-                                render_date_edit_screen(&info_str, &mut lcd, &mut delay);
-                                info_str.clear();
-                                refresh = false;
-                            }
-                            delay.delay_ms(500);
+                    // SELECT silences the buzzer for a while; the alarm itself stays active
+                    let mut silence_countdown = CountDownTimer::new(0);
+                    let mut alarm_time_countdown = CountDownTimer::new_repeating(TICK_TIME_DELAY);
+                    let mut silenced = false;
+                    let mut fire_buzzer = BuzzerPlayer::new();
+                    fire_buzzer.play(FIRE_PATTERN);
+                    while smoke_debouncer.is_pressed() {
+                        for zone in zones.iter_mut() {
+                            // Enable sprinklers, regardless of overrides
+                            zone.outputs.sprinklers.activate();
+                            // Ensure windows are closed
+                            zone.outputs.vent.set_angle(0);
+                            // Ensure heater is off
+                            zone.outputs.heater.deactivate();
+                            // Don't fan the flames
+                            zone.outputs.fan.set_duty_cycle_percent(0).unwrap();
+                            zone.sync_actuators(&mut observer);
+                        }
 
-                            if update_date {
-                                preferences.tick_time();
-                            }
-                            update_date = !update_date;
-
-                            if up_button.is_high().unwrap() {
-                                // Assuming the integer limit cannot be reached
-                                preferences.date.5 += 1;
-                                refresh = true;
-                            } else if down_button.is_high().unwrap() {
-                                if preferences.date.5 != 0 {
-                                    preferences.date.5 -= 1;
+                        if button_pad
+                            .update(&mut up_button, &mut down_button, &mut select_button)
+                            .is_some_and(|presses| presses.select)
+                        {
+                            silence_countdown.set_time(ALARM_SILENCE_TIME);
+                        }
+                        if silenced == silence_countdown.is_finished() {
+                            silenced = !silenced;
+                            if let Some(lcd) = lcd.as_mut() {
+                                render_screen(FIRE, true, lcd, &mut delay).ok();
+                                if silenced {
+                                    render_screen(ALARM_SILENCED, false, lcd, &mut delay).ok();
                                 }
-                                refresh = true;
-                            } else if select_button.is_high().unwrap() {
-                                break;
                             }
                         }
+                        // Sound alarm, unless it was silenced here or for maintenance
+                        let beep = fire_buzzer.tick();
+                        buzzer
+                            .set_state(overrides.buzzer_or(beep && !silenced).into())
+                            .unwrap();
+                        let sounding = overrides.buzzer_or(!silenced);
+                        if sounding != buzzer_sounding {
+                            buzzer_sounding = sounding;
+                            let date = zones[0].preferences.local_date();
+                            observer.on_change(Actuator::Buzzer, sounding, &date);
+                        }
 
-                        // Validate day
-                        if preferences.date.3 > preferences.get_days_in_month() {
-                            preferences.date.3 = preferences.get_days_in_month();
+                        delay.delay_ms(1);
+                        // The alarm ends once the smoke has cleared for a while
+                        smoke_debouncer.update(&mut smoke_detector);
+                        silence_countdown.tick();
+                        // Still keep track of time though
+                        alarm_time_countdown.tick();
+                        if alarm_time_countdown.expired() {
+                            zones[0].preferences.date.tick();
                         }
+                        // The alarm is being handled, so don't let the watchdog reset
+                        watchdog.feed();
+                    }
+                    // Safe; Disable sprinklers and open vents if they were open before
+                    telemetry::actuator::changed("fire alarm", false);
+                    buzzer.set_state(overrides.buzzer_or(false).into()).unwrap();
+                    for (zone, angle) in zones.iter_mut().zip(roof_angles) {
+                        zone.outputs.sprinklers.deactivate();
+                        zone.outputs.vent.set_angle(angle);
+                        zone.sync_actuators(&mut observer);
+                    }
+                    share_clock(&mut zones, 0);
+                    // The alarm loop kept the clock ticking itself
+                    clock_seconds.reset(delay.get_counter().ticks());
+                    // Put the edit screen back up, if something was being edited
+                    redraw_edit = true;
+                }
+
+                // The buzzer only sounds outside of an alarm when forced on
+                if !buzzer_player.is_playing() {
+                    buzzer.set_state(overrides.buzzer_or(false).into()).unwrap();
+                }
 
-                        render_selector(false, 7, &mut lcd, &mut delay);
+                // Check the battery first, so a low one saves power right away
+                let was_low = battery.is_low();
+                let low = battery.update(
+                    read_raw(&mut adc, &mut battery_pin),
+                    &zones[0].preferences.battery,
+                );
+                if low != was_low {
+                    telemetry::power::battery_low(low, battery.volts);
+                    if low {
+                        // Make sure the warning can be seen
+                        set_backlight(true, &mut backlight);
+                        backlight_countdown.set_time(BACKLIGHT_TIMEOUT);
                     }
-                    4 => {
-                        let mut remove: bool = false;
-                        for index in 0..4 {
-                            loop {
-                                if refresh {
-                                    render_watering_edit_screen(
-                                        &preferences.format_watering_time(),
-                                        index,
-                                        &mut lcd,
-                                        &mut delay,
-                                    );
-                                    refresh = false;
-                                }
+                }
+                // The sprinklers, vent, and heater keep running, so the greenhouse stays safe
+                let saving_power = low && zones[0].preferences.battery.power_saving;
+                for zone in zones.iter_mut() {
+                    zone.saving_power = saving_power;
+                }
 
-                                delay.delay_ms(500);
+                // Every zone is under the same sky, so the one light sensor follows the first zone's settings
+                #[cfg(feature = "light-sensor")]
+                if let Some(raw) = read_raw(&mut adc, &mut light_pin) {
+                    ambient.update(raw, &zones[0].preferences.ambient);
+                }
+                for zone in zones.iter_mut() {
+                    zone.daylight = ambient.is_bright();
+                }
 
-                                if update_date {
-                                    preferences.tick_time();
-                                }
-                                update_date = !update_date;
+                let now = (delay.get_counter().ticks() / 1_000_000) as u32;
+                for (index, zone) in zones.iter_mut().enumerate() {
+                    zone.update_light();
 
-                                if up_button.is_high().unwrap() && down_button.is_high().unwrap() {
-                                    remove = true;
-                                    break;
-                                }
+                    // Set the sensor up again once it reconnects
+                    zone.connect_sensor(&i2c_bus, &mut delay).ok();
 
-                                if up_button.is_high().unwrap() {
-                                    if preferences.watering.is_none() {
-                                        preferences.set_default_watering_time();
-                                    } else if let Some((
-                                        ref mut min_low,
-                                        ref mut hr_low,
-                                        ref mut min_high,
-                                        ref mut hr_high,
-                                    )) = preferences.watering
-                                    {
-                                        match index {
-                                            0 => *hr_low = inclusive_iterator(*hr_low, 0, 23, true),
-                                            1 => {
-                                                *min_low = inclusive_iterator(*min_low, 0, 59, true)
-                                            }
-                                            2 => {
-                                                *hr_high = inclusive_iterator(*hr_high, 0, 23, true)
-                                            }
-                                            3 => {
-                                                *min_high =
-                                                    inclusive_iterator(*min_high, 0, 59, true)
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                    refresh = true;
-                                } else if down_button.is_high().unwrap() {
-                                    if preferences.watering.is_none() {
-                                        preferences.set_default_watering_time();
-                                    } else if let Some((
-                                        ref mut min_low,
-                                        ref mut hr_low,
-                                        ref mut min_high,
-                                        ref mut hr_high,
-                                    )) = preferences.watering
-                                    {
-                                        match index {
-                                            0 => {
-                                                *hr_low = inclusive_iterator(*hr_low, 0, 23, false)
-                                            }
-                                            1 => {
-                                                *min_low =
-                                                    inclusive_iterator(*min_low, 0, 59, false)
-                                            }
-                                            2 => {
-                                                *hr_high =
-                                                    inclusive_iterator(*hr_high, 0, 23, false)
-                                            }
-                                            3 => {
-                                                *min_high =
-                                                    inclusive_iterator(*min_high, 0, 59, false)
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                    refresh = true;
-                                } else if select_button.is_high().unwrap() {
-                                    remove = preferences.watering.is_none();
-                                    refresh = true;
-                                    break;
-                                }
+                    // One sensor failing only drops it from the average
+                    let offset = zone.preferences.humidity_offset;
+                    let readings = zone.sensors.read_all(&i2c_bus, &mut delay, offset);
+                    let reading = averaged(&readings).ok_or(zone.sensors.error());
+                    zone.sensor_stats.record(&reading, now);
+                    let data = match reading {
+                        Ok(data) => data,
+                        Err(error) => {
+                            // Don't act on bad data; hold the actuators in a safe state
+                            zone.hold_safe(&overrides, &mut observer);
+                            if index == current_zone {
+                                sensor_fault = Some(GemError::Sensor(error));
                             }
-                            if remove {
-                                break;
-                            }
-                        }
-                        // Check legality
-                        if remove {
-                            preferences.watering = None;
-                        } else if (preferences.watering.unwrap().1 > preferences.watering.unwrap().3) || // Hours are incorrect
-                                    (preferences.watering.unwrap().1 == preferences.watering.unwrap().3 && // Minutes are incorrect assuming hours are equal
-                                        preferences.watering.unwrap().0 > preferences.watering.unwrap().2)
-                        {
-                            preferences.watering = Some((
-                                preferences.watering.unwrap().2,
-                                preferences.watering.unwrap().3,
-                                preferences.watering.unwrap().0,
-                                preferences.watering.unwrap().1,
-                            ));
+                            continue;
                         }
-                    }
-                    _ => {
-                        // Pressure has no configuration
-                    }
-                }
-            }
-            RefreshAction::Sensor => {
-                if smoke_detector.is_high().unwrap() {
-                    // Panic!!!
-                    let roof_open = &roof_vent.is_set_high().unwrap();
-                    render_screen(FIRE, true, &mut lcd, &mut delay);
-                    while smoke_detector.is_high().unwrap() {
-                        // Enable sprinklers
-                        sprinklers.set_high().unwrap();
-                        // Ensure windows are closed
-                        roof_vent.set_low().unwrap();
-                        // Sound alarm
-                        buzzer.set_high().unwrap();
-                        delay.delay_ms(1000);
-                        // Still keep track of time though
-                        preferences.tick_time();
-                    }
-                    // Safe; Disable sprinklers and open vent if it was open before
-                    buzzer.set_low().unwrap();
-                    sprinklers.set_low().unwrap();
-                    if *roof_open {
-                        roof_vent.set_high().unwrap();
-                    }
-                }
+                    };
 
-                data = get_bme_data(&mut bme, &mut delay, &mut buzzer);
-
-                // Check if temperature is valid
-                let temp = get_temperature(&data);
-                if temp > preferences.temperature.1 {
-                    // open vent
-                    roof_vent.set_high().unwrap();
-                } else {
-                    roof_vent.set_low().unwrap();
+                    // Only the first zone has a soil sensor, unless the light sensor took its pin
+                    #[cfg(feature = "light-sensor")]
+                    let soil_moisture = None;
+                    #[cfg(not(feature = "light-sensor"))]
+                    let soil_moisture = match index {
+                        0 => zone.preferences.soil_calibration.and_then(|calibration| {
+                            read_raw(&mut adc, &mut soil_pin)
+                                .map(|raw| moisture_percent(raw, calibration))
+                        }),
+                        _ => None,
+                    };
+                    telemetry::zone::start(index);
+                    let was_sprinkling = zone.actuators.sprinklers;
+                    zone.regulate(data, now, soil_moisture, &overrides, &mut observer);
+                    // Chirp when the sprinklers start, unless an alarm is already sounding
+                    if zone.actuators.sprinklers && !was_sprinkling && !buzzer_player.is_playing() {
+                        buzzer_player.play(WATERING_CHIRP);
+                    }
+                    #[cfg(not(feature = "two-zones"))]
+                    logger.log_reading(&zone.data, &zone.preferences, &zone.actuators);
                 }
 
-                // Check if humidity is valid
-                let humidity = get_humidity(&data);
-                if humidity < preferences.humidity.0 || humidity > preferences.humidity.1 {
-                    // enable sprinklers
-                    sprinklers.set_high().unwrap();
+                // Warn if any zone is freezing, or a reading is past its alarm bounds
+                let was_alarm = alarm;
+                alarm = if zones.iter().any(|zone| zone.frost) {
+                    Some(FROST_PATTERN)
+                } else if zones.iter().any(|zone| zone.alarms.active().is_some()) {
+                    Some(READING_ALARM_PATTERN)
                 } else {
-                    sprinklers.set_low().unwrap();
+                    None
+                };
+                if alarm != was_alarm {
+                    match alarm {
+                        Some(pattern) => {
+                            // Make sure the warning can be seen
+                            set_backlight(true, &mut backlight);
+                            backlight_countdown.set_time(BACKLIGHT_TIMEOUT);
+                            buzzer_player.play(pattern);
+                        }
+                        None => {
+                            buzzer_player.stop();
+                            buzzer.set_state(overrides.buzzer_or(false).into()).unwrap();
+                        }
+                    }
                 }
 
-                // Check if it is watering time
-                if preferences.is_watering_time() {
-                    sprinklers.set_high().unwrap();
-                } else {
-                    sprinklers.set_low().unwrap();
+                // Beep when the sensor first fails, unless an alarm is already sounding
+                if sensor_fault.is_some() != sensor_faulted {
+                    sensor_faulted = !sensor_faulted;
+                    if sensor_faulted && alarm.is_none() {
+                        buzzer_player.play(SENSOR_FAULT_PATTERN);
+                    }
                 }
             }
+            RefreshAction::None if edit.is_some() && redraw_edit => {
+                // A live value on the edit screen changed
+            }
             _ => {
                 // Nothing is needed to do, so just continue
                 continue;
             }
         }
 
-        let mut data_str: String<12> = String::new();
-        match current_screen_index {
-            0 => {
-                // Temp
-                uwrite!(&mut data_str, "Temp: {}F", get_temperature(&data)).unwrap();
-                render_screen(&data_str, true, &mut lcd, &mut delay);
-                data_str.clear();
+        let Some(lcd) = lcd.as_mut() else {
+            // Running headless; the outputs were already driven, so there is nothing left to do
+            redraw_edit = false;
+            continue;
+        };
+        let shown = match edit {
+            // Keep the edit screen up while the sensors are read in the background
+            Some(state) => {
+                if !redraw_edit {
+                    continue;
+                }
+                redraw_edit = false;
+                load_glyphs(GlyphSet::Text, &mut glyphs, lcd, &mut delay).and_then(|()| {
+                    render_edit(&state, &zones[current_zone], &overrides, lcd, &mut delay)
+                })
+            }
+            // The edit screen stays up; a sensor problem shows once the edit is done
+            None => match sensor_fault {
+                Some(error) => load_glyphs(GlyphSet::Text, &mut glyphs, lcd, &mut delay)
+                    .and_then(|()| render_fault(error, lcd, &mut delay)),
+                None => render_status(
+                    current_screen,
+                    graph_series,
+                    &zones,
+                    current_zone,
+                    &overrides,
+                    &uptime,
+                    &battery,
+                    &ambient,
+                    watering_index,
+                    &mut scroll_offset,
+                    &mut glyphs,
+                    lcd,
+                    &mut delay,
+                ),
+            },
+        };
+
+        if let Err(error) = shown {
+            // Don't leave anything running while the controller can't be seen
+            for zone in zones.iter_mut() {
+                zone.fail_safe(&mut observer);
+            }
+            buzzer_player.stop();
+            buzzer.set_low().unwrap();
+            telemetry::fault::occurred(error);
+            // Bring the display back to show what happened; if it is still out, the next refresh tries again
+            glyphs = None;
+            if setup_display(lcd, &mut delay).is_ok() {
+                glyphs = Some(GlyphSet::Text);
+                render_fault(error, lcd, &mut delay).ok();
+            }
+            // Hold the safe state for a while before the sensors are read and the outputs driven again
+            sensor_countdown.set_time(FAULT_RETRY_DELAY);
+            redraw_edit = true;
+        }
+    }
+}
+
+/// Renders the current screen, or a warning while any zone is freezing or has a reading past its alarm bounds
+///
+/// - param current_screen: The [Screen] being shown
+/// - param graph_series: The [GraphSeries] shown on [Screen::Graph]
+/// - param zones: Every [Zone]
+/// - param current_zone: The index of the zone being shown
+/// - param overrides: The manual [Overrides]
+/// - param uptime: [Uptime] since boot
+/// - param battery: The [BatteryMonitor]
+/// - param ambient: The [AmbientLight]
+/// - param watering_index: The watering window being shown
+/// - param scroll_offset: The scroll position of a line too long for the [Lcd]
+/// - param glyphs: The [GlyphSet] the [Lcd] holds, or None if it is unknown
+/// - param lcd: [Lcd] instance
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the screen could not be written
+#[allow(clippy::too_many_arguments)]
+fn render_status(
+    current_screen: Screen,
+    graph_series: GraphSeries,
+    zones: &[Zone],
+    current_zone: usize,
+    overrides: &Overrides,
+    uptime: &Uptime,
+    battery: &BatteryMonitor,
+    ambient: &AmbientLight,
+    watering_index: usize,
+    scroll_offset: &mut usize,
+    glyphs: &mut Option<GlyphSet>,
+    lcd: &mut Lcd,
+    delay: &mut Timer,
+) -> Result<(), GemError> {
+    // The graph's bars take the other glyphs' place while it is up, rather than a warning
+    let warning = zones
+        .iter()
+        .any(|zone| zone.frost || zone.alarms.active().is_some());
+    let glyph_set = if current_screen == Screen::Graph && !warning {
+        GlyphSet::Sparkline
+    } else {
+        GlyphSet::Text
+    };
+    load_glyphs(glyph_set, glyphs, lcd, delay)?;
+
+    let mut data_str: String<16> = String::new();
+    if let Some(zone) = zones.iter().find(|zone| zone.frost) {
+        // Keep the warning up until it warms up
+        render_screen(FROST, true, lcd, delay)?;
+        uwrite!(
+            &mut data_str,
+            "{}: {}°F",
+            TEMPERATURE,
+            zone.filter.temperature()
+        )
+        .unwrap();
+        render_screen(&data_str, false, lcd, delay)?;
+        return Ok(());
+    }
+    if let Some((zone, alarm)) = zones
+        .iter()
+        .find_map(|zone| zone.alarms.active().map(|alarm| (zone, alarm)))
+    {
+        // Keep the warning up until the reading is back within its bounds
+        render_screen(alarm.name(), true, lcd, delay)?;
+        if alarm.is_temperature() {
+            uwrite!(
+                &mut data_str,
+                "{}: {}°F",
+                TEMPERATURE,
+                zone.filter.temperature()
+            )
+            .unwrap();
+        } else {
+            uwrite!(&mut data_str, "{}: {}%", HUMIDITY, zone.filter.humidity()).unwrap();
+        }
+        render_screen(&data_str, false, lcd, delay)?;
+        return Ok(());
+    }
+    let Zone {
+        preferences,
+        data,
+        filter,
+        pressure_trend,
+        temperature_rate,
+        rising_fast,
+        sensors,
+        sensor_stats,
+        daily_stats,
+        trends,
+        soil_moisture,
+        manual_watering,
+        watering_history,
+        gas_warmup,
+        air_quality,
+        actuators,
+        ..
+    } = &zones[current_zone];
+    match current_screen {
+        Screen::Temperature => {
+            // Temp
+            uwrite!(&mut data_str, "{}: {}°F", TEMPERATURE, filter.temperature()).unwrap();
+            if overrides.vent.is_some() || overrides.heater.is_some() {
+                // The vent or heater is under manual control
+                uwrite!(&mut data_str, " {}", OVERRIDE_MARK).unwrap();
+            }
+            let feels_like = data.heat_index;
+            let mut line: String<24> = String::new();
+            if battery.is_low() {
+                // Warn that the battery is running out
                 uwrite!(
-                    &mut data_str,
-                    "({}, {})",
+                    &mut line,
+                    "{} {}V",
+                    LOW_BATTERY,
+                    format_hundredths(battery.volts).as_str()
+                )
+                .unwrap();
+                render_two_lines(&data_str, &line, lcd, delay)?;
+            } else if *rising_fast {
+                // Warn that it is heating up fast
+                let rate = temperature_rate.temperature_rate_per_min();
+                uwrite!(&mut line, "{} {}.{}°F/min", RISING, rate / 10, rate % 10).unwrap();
+                render_two_lines(&data_str, &line, lcd, delay)?;
+            } else if feels_like.abs_diff(data.temperature) > HEAT_INDEX_DIFFERENCE {
+                // Show how hot it feels instead of the range
+                uwrite!(&mut line, "{}: {}°F", FEELS_LIKE, feels_like).unwrap();
+                render_two_lines(&data_str, &line, lcd, delay)?;
+            } else {
+                // Show where the temperature is within the range
+                render_screen(&data_str, true, lcd, delay)?;
+                render_gauge(
+                    filter.temperature(),
                     preferences.temperature.0,
-                    preferences.temperature.1
+                    preferences.temperature.1,
+                    lcd,
+                    delay,
+                )?;
+            }
+        }
+        Screen::Humidity => {
+            // Humidity
+            uwrite!(&mut data_str, "{}: {}%", HUMIDITY_SHORT, filter.humidity()).unwrap();
+            if overrides.sprinklers.is_some() {
+                // The sprinklers are under manual control
+                uwrite!(&mut data_str, " {}", OVERRIDE_MARK).unwrap();
+            }
+            if manual_watering.is_active() {
+                // Show how much longer the sprinklers run
+                let mut line: String<24> = String::new();
+                uwrite!(
+                    &mut line,
+                    "{} {}",
+                    WATERING_SHORT,
+                    manual_watering.format_remaining().as_str()
+                )
+                .unwrap();
+                render_two_lines(&data_str, &line, lcd, delay)?;
+            } else {
+                // Show where the humidity is within the range
+                render_screen(&data_str, true, lcd, delay)?;
+                render_gauge(
+                    filter.humidity(),
+                    preferences.humidity.0,
+                    preferences.humidity.1,
+                    lcd,
+                    delay,
+                )?;
+            }
+        }
+        Screen::Pressure => {
+            // Pressure
+            let indicator = match pressure_trend.trend() {
+                Trend::Rising => "↑",
+                Trend::Steady => "-",
+                Trend::Falling => "↓",
+            };
+            let unit = preferences.pressure_unit;
+            let mut line: String<24> = String::new();
+            uwrite!(
+                &mut line,
+                "{}: {} {} {}",
+                PRESSURE,
+                unit.format(filter.pressure()).as_str(),
+                unit.suffix(),
+                indicator
+            )
+            .unwrap();
+            render_screen(&line, true, lcd, delay)?;
+
+            // Gas resistance, once the heater has settled
+            line.clear();
+            if gas_warmup.is_warmed_up() {
+                uwrite!(&mut line, "{}: {}kOhm", GAS, data.gas_resistance / 1000).unwrap();
+            } else {
+                uwrite!(
+                    &mut line,
+                    "{} {}",
+                    WARMING_UP,
+                    gas_warmup.format_remaining().as_str()
                 )
                 .unwrap();
-                render_screen(&data_str, false, &mut lcd, &mut delay);
             }
-            1 => {
-                // Humidity
-                uwrite!(&mut data_str, "RH: {}%", get_humidity(&data)).unwrap();
-                render_screen(&data_str, true, &mut lcd, &mut delay);
-                data_str.clear();
+            render_screen(&line, false, lcd, delay)?;
+        }
+        Screen::AirQuality => {
+            // The gas resistance against its baseline, once the heater has settled
+            if gas_warmup.is_warmed_up() {
+                let now = (delay.get_counter().ticks() / 1_000_000) as u32;
                 uwrite!(
                     &mut data_str,
-                    "({}%, {}%)",
-                    preferences.humidity.0,
-                    preferences.humidity.1
+                    "{}",
+                    air_quality.format_baseline(now).as_str()
+                )
+                .unwrap();
+            } else {
+                uwrite!(
+                    &mut data_str,
+                    "{} {}",
+                    WARMING_UP,
+                    gas_warmup.format_remaining().as_str()
+                )
+                .unwrap();
+            }
+            let reading = air_quality.format_reading(data.gas_resistance);
+            render_two_lines(&data_str, &reading, lcd, delay)?;
+        }
+        Screen::Date => {
+            // Date
+            let (time, date) = preferences.get_date_formatted();
+            uwrite!(
+                &mut data_str,
+                "{} {}",
+                date.as_str(),
+                preferences.local_date().weekday().name()
+            )
+            .unwrap();
+            render_two_lines(&time, &data_str, lcd, delay)?;
+        }
+        Screen::Watering => {
+            // Water Schedule
+            render_screen(
+                &preferences.format_watering_time(watering_index),
+                true,
+                lcd,
+                delay,
+            )?;
+            if manual_watering.is_active() {
+                // Show how much longer the sprinklers run
+                uwrite!(
+                    &mut data_str,
+                    "{} {}",
+                    WATERING_SHORT,
+                    manual_watering.format_remaining().as_str()
+                )
+                .unwrap();
+                render_screen(&data_str, false, lcd, delay)?;
+            } else {
+                // The window's days, then when the next window starts and when the sprinklers last ran
+                let mut schedule: String<48> = String::new();
+                if let Some(window) = preferences.watering.get(watering_index) {
+                    uwrite!(
+                        &mut schedule,
+                        "{}/{} {}  ",
+                        watering_index + 1,
+                        preferences.watering.len(),
+                        window.format_days().as_str()
+                    )
+                    .unwrap();
+                }
+                uwrite!(
+                    &mut schedule,
+                    "{}  {}",
+                    preferences.format_next_watering().as_str(),
+                    watering_history
+                        .format_last(preferences.date_format)
+                        .as_str()
                 )
                 .unwrap();
-                render_screen(&data_str, false, &mut lcd, &mut delay);
+                render_scrolling(&schedule, false, scroll_offset, lcd, delay)?;
             }
-            2 => {
-                // Pressure
-                uwrite!(&mut data_str, "PRS: {} mb", get_pressure(&data)).unwrap();
-                render_screen(&data_str, true, &mut lcd, &mut delay);
+        }
+        Screen::Soil => {
+            // Soil Moisture
+            match soil_moisture {
+                Some(moisture) => uwrite!(&mut data_str, "{}: {}%", SOIL, moisture).unwrap(),
+                None if preferences.soil_calibration.is_some() => {
+                    uwrite!(&mut data_str, "{}: {}%", SOIL, NO_READING).unwrap()
+                }
+                None => uwrite!(&mut data_str, "{}", NO_SOIL_SENSOR).unwrap(),
             }
-            3 => {
-                // Date
-                let (time, date) = preferences.get_date_formatted();
-                render_screen(&time, true, &mut lcd, &mut delay);
-                render_screen(&date, false, &mut lcd, &mut delay);
+            let mut range: String<16> = String::new();
+            uwrite!(
+                &mut range,
+                "({}%, {}%)",
+                preferences.soil_moisture.0,
+                preferences.soil_moisture.1
+            )
+            .unwrap();
+            render_two_lines(&data_str, &range, lcd, delay)?;
+        }
+        Screen::Light => {
+            // Grow Light Schedule
+            render_screen(&preferences.format_light_time(), true, lcd, delay)?;
+            if preferences.light.is_some() {
+                let state = on_off(actuators.light);
+                uwrite!(&mut data_str, "{}: {}", LIGHT, state).unwrap();
+                render_screen(&data_str, false, lcd, delay)?;
             }
-            _ => {
-                // Water Schedule
-                render_screen(
-                    &preferences.format_watering_time(),
-                    true,
-                    &mut lcd,
-                    &mut delay,
-                );
+        }
+        Screen::Ambient => {
+            // Ambient Light
+            render_two_lines(
+                &ambient.format_level(),
+                &ambient.format_reading(),
+                lcd,
+                delay,
+            )?;
+        }
+        Screen::DewPoint => {
+            // Dew Point
+            uwrite!(&mut data_str, "{}: {}°F", DEW_POINT, data.dew_point).unwrap();
+            let mut air: String<16> = String::new();
+            uwrite!(&mut air, "{}: {}°F", AIR, filter.temperature()).unwrap();
+            if data.is_condensation_risk() {
+                // Warn that condensation may form
+                uwrite!(&mut air, " !").unwrap();
+            }
+            render_two_lines(&data_str, &air, lcd, delay)?;
+        }
+        Screen::Vpd => {
+            // Vapor Pressure Deficit
+            uwrite!(
+                &mut data_str,
+                "{}: {} kPa",
+                VPD,
+                format_hundredths(data.vpd).as_str()
+            )
+            .unwrap();
+            let mut status: String<24> = String::new();
+            uwrite!(
+                &mut status,
+                "{} ({}-{})",
+                vpd_status(data.vpd, preferences).name(),
+                format_hundredths(preferences.vpd.0).as_str(),
+                format_hundredths(preferences.vpd.1).as_str()
+            )
+            .unwrap();
+            render_two_lines(&data_str, &status, lcd, delay)?;
+        }
+        Screen::Sensors => {
+            // Each sensor's own reading, before they are averaged
+            let mut lines = sensors
+                .slots()
+                .iter()
+                .map(|slot| format_sensor_reading(slot.address, slot.reading.as_ref()));
+            let top = lines.next().unwrap_or_default();
+            let bottom = lines.next().unwrap_or_default();
+            render_two_lines(&top, &bottom, lcd, delay)?;
+        }
+        Screen::Stats => {
+            // Today's highs and lows
+            match daily_stats.temperature() {
+                Some((low, high)) => uwrite!(
+                    &mut data_str,
+                    "{} {} {} {}°F",
+                    DAILY_HIGH,
+                    high,
+                    DAILY_LOW,
+                    low
+                )
+                .unwrap(),
+                None => uwrite!(&mut data_str, "{}", NO_DATA_TODAY).unwrap(),
+            }
+            render_screen(&data_str, true, lcd, delay)?;
+            data_str.clear();
+            if let Some((low, high)) = daily_stats.humidity() {
+                uwrite!(
+                    &mut data_str,
+                    "{} {}% {} {}%",
+                    DAILY_HIGH,
+                    high,
+                    DAILY_LOW,
+                    low
+                )
+                .unwrap();
+                render_screen(&data_str, false, lcd, delay)?;
+            }
+        }
+        Screen::Calibration => {
+            // Temperature offset
+            uwrite!(&mut data_str, "{}: {}°F", TEMPERATURE, filter.temperature()).unwrap();
+            render_two_lines(&data_str, &preferences.format_temp_offset(), lcd, delay)?;
+        }
+        Screen::Features => {
+            // Optional features
+            for (name, enabled) in [
+                (WATERING_SHORT, !preferences.watering.is_empty()),
+                (SOIL, preferences.soil_calibration.is_some()),
+                (LIGHT, preferences.light.is_some()),
+            ] {
+                if enabled {
+                    if !data_str.is_empty() {
+                        uwrite!(&mut data_str, " ").unwrap();
+                    }
+                    uwrite!(&mut data_str, "{}", name).unwrap();
+                }
+            }
+            if data_str.is_empty() {
+                uwrite!(&mut data_str, "{}", ALL_FEATURES_OFF).unwrap();
+            }
+            render_two_lines(FEATURES_TITLE, &data_str, lcd, delay)?;
+        }
+        Screen::Reset => {
+            // Reset to defaults
+            render_two_lines(RESET_TITLE, RESET_HINT, lcd, delay)?;
+        }
+        Screen::Zone => {
+            // Which zone the other screens show
+            uwrite!(
+                &mut data_str,
+                "{} {}/{}",
+                ZONE,
+                current_zone + 1,
+                ZONE_COUNT
+            )
+            .unwrap();
+            let mut temp: String<16> = String::new();
+            uwrite!(&mut temp, "{}: {}°F", TEMPERATURE, filter.temperature()).unwrap();
+            render_two_lines(&data_str, &temp, lcd, delay)?;
+        }
+        Screen::Uptime => {
+            // Time since boot
+            render_two_lines(UPTIME_TITLE, &uptime.format(), lcd, delay)?;
+        }
+        Screen::Memory => {
+            // The stack's high-water mark since boot
+            #[cfg(feature = "stack-usage")]
+            {
+                let usage = gem_rs::stack::measure();
+                render_two_lines(&usage.format_peak(), &usage.format_free(), lcd, delay)?;
+            }
+        }
+        Screen::Diagnostics => {
+            // Sensor read counts
+            let mut counts: String<48> = String::new();
+            uwrite!(
+                &mut counts,
+                "{} {} {} {} {} {}",
+                READS_OK,
+                sensor_stats.successes(),
+                BUS_ERRORS,
+                sensor_stats.bus_errors(),
+                SENSOR_MISSING,
+                sensor_stats.absences()
+            )
+            .unwrap();
+            render_scrolling(&counts, true, scroll_offset, lcd, delay)?;
+
+            let now = (delay.get_counter().ticks() / 1_000_000) as u32;
+            let mut last_read: String<24> = String::new();
+            match sensor_stats.since_success(now) {
+                Some(seconds) => uwrite!(&mut last_read, "{}: {}s", LAST_OK, seconds).unwrap(),
+                None => uwrite!(&mut last_read, "{}: {}", LAST_OK, NEVER).unwrap(),
+            }
+            render_screen(&last_read, false, lcd, delay)?;
+        }
+        Screen::History => {
+            // The last time the sprinklers ran; SELECT pages back through the rest
+            render_history(watering_history, 0, preferences, lcd, delay)?;
+        }
+        Screen::Graph => {
+            // The last hour of one reading; SELECT switches to the next
+            let trend = trends.get(graph_series);
+            match trend.range() {
+                Some(range) => {
+                    let line = graph_series.format_range(range, preferences.pressure_unit);
+                    render_screen(&line, true, lcd, delay)?;
+                    render_sparkline(&trend.values(), 1, lcd, delay)?;
+                }
+                None => {
+                    render_screen(NO_READINGS_YET, true, lcd, delay)?;
+                }
             }
         }
+        Screen::Battery => {
+            // Battery voltage
+            if !battery.is_low() {
+                uwrite!(&mut data_str, "{}", BATTERY_OK).unwrap();
+            } else if preferences.battery.power_saving {
+                uwrite!(&mut data_str, "{}, {}", BATTERY_LOW, SAVING_POWER).unwrap();
+            } else {
+                uwrite!(&mut data_str, "{}", BATTERY_LOW).unwrap();
+            }
+            render_two_lines(&battery.format(), &data_str, lcd, delay)?;
+        }
+        Screen::Margins => {
+            // Hysteresis margins
+            render_two_lines(MARGINS_TITLE, &preferences.format_margins(), lcd, delay)?;
+        }
+        Screen::Overrides => {
+            // Overrides
+            render_screen(OVERRIDES_TITLE, true, lcd, delay)?;
+            if overrides.is_active() {
+                uwrite!(&mut data_str, "{}:", OVERRIDDEN).unwrap();
+                for (label, state) in [
+                    (VENT_LETTER, overrides.vent),
+                    (SPRINKLERS_LETTER, overrides.sprinklers),
+                    (HEATER_LETTER, overrides.heater),
+                    (BUZZER_LETTER, overrides.buzzer),
+                ] {
+                    if state.is_some() {
+                        uwrite!(&mut data_str, " {}", label).unwrap();
+                    }
+                }
+            } else {
+                uwrite!(&mut data_str, "{}", ALL_AUTOMATIC).unwrap();
+            }
+            render_screen(&data_str, false, lcd, delay)?;
+        }
     }
+    Ok(())
 }
 
 /// What type of update is required when refreshing the screen
@@ -655,12 +1558,16 @@ fn main() -> ! {
 /// - **Up**: The Up button was pressed
 /// - **Down**: The Down button was pressed
 /// - **Select**: The Select button was pressed
+/// - **Chord**: Several buttons were pressed together
+/// - **Cycle**: The next screen is due while the screens cycle on their own
 /// - **Sensor**: The sensors need to be refreshed
 /// - **None**: Ignore the refresh
 enum RefreshAction {
     Up,
     Down,
     Select,
+    Chord(ButtonPresses),
+    Cycle,
     Sensor,
     None,
 }
@@ -670,48 +1577,86 @@ enum RefreshAction {
 /// - param up: Up Button
 /// - param down: Down Button
 /// - param select: Selection Button
+/// - param button_pad: [ButtonPad] debouncing the buttons
 /// - param preferences: [Preferences] instance
 /// - param button_cd: button countdown
 /// - param sensor_cd: sensor countdown
-/// - param time_cd: uptime countdown
+/// - param clock: [SecondCounter] keeping the software clock in step with the hardware timer
+/// - param uptime: [Uptime] since boot
+/// - param now_us: The hardware timer's reading in microseconds
+/// - param rtc: [Rtc] instance, or None to keep time in software
+/// - param backlight_cd: backlight idle countdown
+/// - param backlight: [Backlight] pin
+/// - param keep_backlight: Whether the backlight stays on while idle; see [AmbientLight::keeps_backlight_on()]
 ///
 /// returns: if the LCD needs an update
+#[allow(clippy::too_many_arguments)]
 fn should_update(
-    up: &mut Pin<Gpio10, FunctionSio<SioInput>, PullDown>,
-    down: &mut Pin<Gpio11, FunctionSio<SioInput>, PullDown>,
-    select: &mut Pin<Gpio12, FunctionSio<SioInput>, PullDown>,
+    up: &mut impl InputPin,
+    down: &mut impl InputPin,
+    select: &mut impl InputPin,
+    button_pad: &mut ButtonPad,
     preferences: &mut Preferences,
     button_cd: &mut CountDownTimer,
     sensor_cd: &mut CountDownTimer,
-    time_cd: &mut CountDownTimer,
+    clock: &mut SecondCounter,
+    uptime: &mut Uptime,
+    now_us: u64,
+    rtc: &mut Option<Rtc<impl I2c>>,
+    backlight_cd: &mut CountDownTimer,
+    backlight: &mut Backlight,
+    keep_backlight: bool,
 ) -> RefreshAction {
-    // Tick
-    time_cd.tick();
-    if time_cd.is_finished() {
-        preferences.tick_time();
-        time_cd.set_time(TICK_TIME_DELAY);
+    // Uptime follows the hardware timer, whatever the clock is set to
+    uptime.update(now_us);
+
+    // Tick, catching up on any seconds a stall held up
+    let seconds = clock.elapsed(now_us);
+    if seconds > 0 {
+        // Prefer the RTC's clock, but keep ticking in software if it can't be read
+        let synced = match rtc {
+            Some(rtc) => preferences.sync_from_rtc(rtc).is_ok(),
+            None => false,
+        };
+        if !synced {
+            preferences.date.tick_by(seconds);
+        }
     }
 
     button_cd.tick();
     sensor_cd.tick();
 
+    // Turn the backlight off when idle, unless it is kept on while it is light
+    backlight_cd.tick();
+    if backlight_cd.is_finished() && !keep_backlight {
+        set_backlight(false, backlight);
+    }
+
+    // Sample the buttons every tick so presses are debounced
+    let presses = button_pad.update(up, down, select);
+
     // Only tick buttons if they aren't on delay
-    if button_cd.is_finished() {
-        if up.is_high().unwrap() {
-            button_cd.set_time(SCREEN_BUTTON_DELAY);
+    if let (Some(presses), true) = (presses, button_cd.is_finished()) {
+        // Wake the backlight before handling the press
+        set_backlight(true, backlight);
+        backlight_cd.set_time(BACKLIGHT_TIMEOUT);
+        button_cd.set_time(SCREEN_BUTTON_DELAY);
+
+        if presses.is_chord() {
+            return RefreshAction::Chord(presses);
+        } else if presses.up {
             return RefreshAction::Up;
-        } else if down.is_high().unwrap() {
-            button_cd.set_time(SCREEN_BUTTON_DELAY);
+        } else if presses.down {
             return RefreshAction::Down;
-        } else if select.is_high().unwrap() {
-            button_cd.set_time(SCREEN_BUTTON_DELAY);
+        } else {
             return RefreshAction::Select;
         }
     }
 
     // Only tick sensors if they aren't on delay
-    if sensor_cd.is_finished() {
-        sensor_cd.set_time(SENSOR_DELAY);
+    if sensor_cd.expired() {
+        // Picks up interval changes from the next countdown on
+        sensor_cd.set_period(preferences.sensor_interval_ms);
         return RefreshAction::Sensor;
     }
 
@@ -719,12 +1664,325 @@ fn should_update(
     RefreshAction::None
 }
 
-/// Iterates forwards or backwards through Screens
+/// Renders the setting being edited, with a `^` under the selected field
+///
+/// - param state: The [EditState] being edited
+/// - param zone: The [Zone] being edited
+/// - param overrides: The manual [Overrides]
+/// - param lcd: [Lcd] instance
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the screen could not be written
+fn render_edit(
+    state: &EditState,
+    zone: &Zone,
+    overrides: &Overrides,
+    lcd: &mut Lcd,
+    delay: &mut Timer,
+) -> Result<(), GemError> {
+    let preferences = &zone.preferences;
+    let mut line: String<16> = String::new();
+    match *state {
+        EditState::Temperature { lower } => {
+            uwrite!(
+                &mut line,
+                "{} - {}",
+                preferences.temperature.0,
+                preferences.temperature.1
+            )
+            .unwrap();
+            render_edit_screen(&line, lower, lcd, delay)?;
+        }
+        EditState::Humidity { lower } => {
+            uwrite!(
+                &mut line,
+                "{}% - {}%",
+                preferences.humidity.0,
+                preferences.humidity.1
+            )
+            .unwrap();
+            render_edit_screen(&line, lower, lcd, delay)?;
+        }
+        EditState::TemperatureAlarm { lower } => {
+            let (low, high) = preferences.temperature_alarm;
+            uwrite!(&mut line, "{} {} - {}", ALARM, low, high).unwrap();
+            render_edit_screen(&line, lower, lcd, delay)?;
+        }
+        EditState::HumidityAlarm { lower } => {
+            let (low, high) = preferences.humidity_alarm;
+            uwrite!(&mut line, "{} {}%-{}%", ALARM, low, high).unwrap();
+            render_edit_screen(&line, lower, lcd, delay)?;
+        }
+        EditState::SoilRange { lower } => {
+            uwrite!(
+                &mut line,
+                "{}% - {}%",
+                preferences.soil_moisture.0,
+                preferences.soil_moisture.1
+            )
+            .unwrap();
+            render_edit_screen(&line, lower, lcd, delay)?;
+        }
+        EditState::VpdRange { lower } => {
+            uwrite!(
+                &mut line,
+                "{} - {}",
+                format_hundredths(preferences.vpd.0).as_str(),
+                format_hundredths(preferences.vpd.1).as_str()
+            )
+            .unwrap();
+            render_edit_screen(&line, lower, lcd, delay)?;
+        }
+        EditState::Date { field, date } => {
+            match field {
+                DateField::Minute => uwrite!(&mut line, "{}: {}", MINUTE, date.minute),
+                DateField::Hour => uwrite!(&mut line, "{}: {}", HOUR, date.hour),
+                DateField::Day => uwrite!(&mut line, "{}: {}", DAY, date.day),
+                DateField::Month => uwrite!(&mut line, "{}: {}", MONTH, date.month),
+                DateField::Year => uwrite!(&mut line, "{}: {}", YEAR, date.year),
+                DateField::ClockFormat => match preferences.clock_format {
+                    ClockFormat::TwentyFourHour => {
+                        uwrite!(&mut line, "{}: {}", CLOCK_FORMAT, CLOCK_24H)
+                    }
+                    ClockFormat::TwelveHour => {
+                        uwrite!(&mut line, "{}: {}", CLOCK_FORMAT, CLOCK_12H)
+                    }
+                },
+                DateField::DateFormat => {
+                    uwrite!(
+                        &mut line,
+                        "{}: {}",
+                        DATE_FORMAT,
+                        preferences.date_format.name()
+                    )
+                }
+                DateField::Dst => {
+                    let rule = if !preferences.dst_enabled {
+                        OFF
+                    } else if preferences.dst_rule == DstRule::US {
+                        DST_US
+                    } else if preferences.dst_rule == DstRule::EU {
+                        DST_EU
+                    } else {
+                        DST_CUSTOM
+                    };
+                    uwrite!(&mut line, "{}: {}", DST, rule)
+                }
+            }
+            .unwrap();
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::WateringPick { index, last } => {
+            render_screen(
+                &preferences.format_watering_time(index as usize),
+                true,
+                lcd,
+                delay,
+            )?;
+            uwrite!(&mut line, "{} {}/{}", WATERING_WINDOW, index + 1, last + 1).unwrap();
+            render_screen(&line, false, lcd, delay)?;
+        }
+        EditState::WateringTime { window, field } => {
+            render_watering_edit_screen(
+                &preferences.format_watering_time(window),
+                field as i32,
+                lcd,
+                delay,
+            )?;
+        }
+        EditState::WateringDays { window, day } => {
+            if let Some(watering) = preferences.watering.get(window) {
+                uwrite!(
+                    &mut line,
+                    "{}: {}",
+                    WATERING_DAYS,
+                    watering.format_days().as_str()
+                )
+                .unwrap();
+            }
+            render_days_edit_screen(&line, day, lcd, delay)?;
+        }
+        EditState::WateringDuration { .. } => {
+            if preferences.watering_duration_minutes == 0 {
+                uwrite!(&mut line, "{}: {}", WATERING_RUN, WHOLE_WINDOW).unwrap();
+            } else {
+                uwrite!(
+                    &mut line,
+                    "{}: {}min",
+                    WATERING_RUN,
+                    preferences.watering_duration_minutes
+                )
+                .unwrap();
+            }
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::PollInterval { index } => {
+            uwrite!(
+                &mut line,
+                "{}: {}s",
+                POLL_INTERVAL,
+                SENSOR_INTERVALS[index as usize] / 1000
+            )
+            .unwrap();
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::PressureUnit => {
+            uwrite!(
+                &mut line,
+                "{}: {}",
+                PRESSURE_UNIT,
+                preferences.pressure_unit.suffix()
+            )
+            .unwrap();
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::Override { field } => {
+            let (name, state) = match field {
+                0 => (VENT, overrides.vent),
+                1 => (SPRINKLERS, overrides.sprinklers),
+                2 => (HEATER, overrides.heater),
+                _ => (BUZZER, overrides.buzzer),
+            };
+            render_date_edit_screen(&format_override(name, state), lcd, delay)?;
+        }
+        EditState::Margin { field } => {
+            render_date_edit_screen(&preferences.format_margin(field), lcd, delay)?;
+        }
+        EditState::Battery { field } => {
+            render_date_edit_screen(&preferences.format_battery_setting(field), lcd, delay)?;
+        }
+        EditState::AirVenting => {
+            render_date_edit_screen(&preferences.format_air_venting(), lcd, delay)?;
+        }
+        EditState::AmbientThreshold { field } => {
+            render_date_edit_screen(&preferences.format_ambient_setting(field), lcd, delay)?;
+        }
+        EditState::AmbientPoint { lit, calibration } => {
+            // Calibrate with the sensor covered, then in daylight
+            let (prompt, point) = if lit {
+                (AMBIENT_BRIGHT_PROMPT, calibration.1)
+            } else {
+                (AMBIENT_DARK_PROMPT, calibration.0)
+            };
+            render_screen(prompt, true, lcd, delay)?;
+            uwrite!(&mut line, "{}: {}", RAW_READING, point).unwrap();
+            render_screen(&line, false, lcd, delay)?;
+        }
+        EditState::TempOffset => {
+            // Nudge the offset while watching the live temperature
+            uwrite!(&mut line, "{}: {}°F", TEMPERATURE, zone.data.temperature).unwrap();
+            render_screen(&line, true, lcd, delay)?;
+            render_screen(&preferences.format_temp_offset(), false, lcd, delay)?;
+        }
+        EditState::HumidityOffset => {
+            // Nudge the offset while watching the live humidity
+            uwrite!(&mut line, "{}: {}%", HUMIDITY_SHORT, zone.data.humidity).unwrap();
+            render_screen(&line, true, lcd, delay)?;
+            render_screen(&preferences.format_humidity_offset(), false, lcd, delay)?;
+        }
+        EditState::Smoothing => {
+            render_date_edit_screen(&preferences.format_smoothing(), lcd, delay)?;
+        }
+        EditState::GasWarmup => {
+            render_date_edit_screen(&preferences.format_gas_warmup(), lcd, delay)?;
+        }
+        EditState::SoilSensor { enabled } => {
+            uwrite!(&mut line, "{}: {}", SOIL_SENSOR, on_off(enabled)).unwrap();
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::SoilPoint { wet, calibration } => {
+            // Calibrate with the sensor in dry air, then in water
+            let (prompt, point) = if wet {
+                (SOIL_WET_PROMPT, calibration.1)
+            } else {
+                (SOIL_DRY_PROMPT, calibration.0)
+            };
+            render_screen(prompt, true, lcd, delay)?;
+            uwrite!(&mut line, "{}: {}", RAW_READING, point).unwrap();
+            render_screen(&line, false, lcd, delay)?;
+        }
+        EditState::LightSchedule { enabled } => {
+            uwrite!(&mut line, "{}: {}", LIGHT, on_off(enabled)).unwrap();
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::LightTime { field, window } => {
+            let (unit, value) = match field {
+                0 => (LIGHT_ON_HOUR, window.start.1),
+                1 => (LIGHT_ON_MINUTE, window.start.0),
+                2 => (LIGHT_OFF_HOUR, window.end.1),
+                _ => (LIGHT_OFF_MINUTE, window.end.0),
+            };
+            uwrite!(&mut line, "{}: {}", unit, value).unwrap();
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::Feature { field } => {
+            // Optional features' screens are hidden while they are off
+            let (name, enabled) = match field {
+                0 => (WATERING, !preferences.watering.is_empty()),
+                1 => (SOIL, preferences.soil_calibration.is_some()),
+                _ => (LIGHT, preferences.light.is_some()),
+            };
+            uwrite!(&mut line, "{}: {}", name, on_off(enabled)).unwrap();
+            render_date_edit_screen(&line, lcd, delay)?;
+        }
+        EditState::AutoCycle => {
+            render_date_edit_screen(&preferences.format_auto_cycle(), lcd, delay)?;
+        }
+        EditState::Reset { confirmed } => {
+            // Ask first, so the settings are not wiped by accident
+            render_screen(RESET_PROMPT, true, lcd, delay)?;
+            uwrite!(&mut line, "{}: {}", RESET, yes_no(confirmed)).unwrap();
+            render_screen(&line, false, lcd, delay)?;
+        }
+        EditState::History { entry, .. } => {
+            render_history(&zone.watering_history, entry, preferences, lcd, delay)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders one event of a zone's watering history: `2/8 31/05 06:00`, then why and how long it ran
 ///
-/// - param current_screen_index: The current screen being displayed
-/// - param next: Whether to iterate forward; If false, iterate backwards
+/// - param history: The zone's [WateringHistory]
+/// - param entry: Which event to show; 0 is the newest
+/// - param preferences: [Preferences] instance
+/// - param lcd: [Lcd] instance
+/// - param delay: Delay instance
 ///
-/// returns: The next Screen
-fn next_screen(current_screen_index: u8, next: bool) -> u8 {
-    (current_screen_index + if next { 1 } else { 4 }) % 5
+/// returns [GemError::Display] if the screen could not be written
+fn render_history(
+    history: &WateringHistory,
+    entry: u8,
+    preferences: &Preferences,
+    lcd: &mut Lcd,
+    delay: &mut Timer,
+) -> Result<(), GemError> {
+    let Some(event) = history.get(entry as usize) else {
+        render_screen(WATERING_LOG_TITLE, true, lcd, delay)?;
+        render_screen(NO_WATERING_YET, false, lcd, delay)?;
+        return Ok(());
+    };
+    let mut line: String<24> = String::new();
+    uwrite!(
+        &mut line,
+        "{}/{} {}",
+        entry + 1,
+        history.len(),
+        event.format_start(preferences.date_format).as_str()
+    )
+    .unwrap();
+    render_screen(&line, true, lcd, delay)?;
+    render_screen(&event.format_outcome(), false, lcd, delay)?;
+    Ok(())
+}
+
+/// Switches the outputs off when the firmware crashes, then halts until the watchdog resets the board.
+/// Every panic ends up here too, since panic-probe raises a HardFault once it has logged the panic;
+/// see [failsafe::de_energize()]
+#[exception]
+unsafe fn HardFault(_frame: &ExceptionFrame) -> ! {
+    failsafe::de_energize();
+    loop {
+        core::hint::spin_loop();
+    }
 }
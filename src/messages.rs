@@ -0,0 +1,273 @@
+use panic_probe as _;
+
+// Every piece of text the LCD shows on its own lives here, so the UI can be reworded or
+// translated without touching the control logic. Text with numbers in it stays a ufmt format
+// string next to its value, since ufmt needs the format string to be a literal; its words are
+// written in from here with `{}`.
+// The LCD is 16 characters wide; the edit screens fit 11 characters after their label
+
+/// Shown while the smoke detector is tripped
+pub const FIRE: &str = "Fire Present";
+/// Shown under [FIRE] once the alarm was silenced
+pub const ALARM_SILENCED: &str = "Silenced";
+/// Shown while the frost alarm is active
+pub const FROST: &str = "FROST";
+/// Shown while a reading is outside of its alarm bounds; see [crate::alarm::Alarm]
+pub const TOO_COLD: &str = "TOO COLD";
+pub const TOO_HOT: &str = "TOO HOT";
+pub const TOO_DRY: &str = "TOO DRY";
+pub const TOO_HUMID: &str = "TOO HUMID";
+/// Shown before the alarm bounds while they are edited
+pub const ALARM: &str = "Alarm";
+/// Shown when the BME680 is not connected
+pub const NO_SENSOR: &str = "No Sensor";
+/// Shown when the BME680 failed to read
+pub const SENSOR_ERROR: &str = "Sensor Err";
+/// Shown when the LCD stopped responding, once it is back
+pub const DISPLAY_ERROR: &str = "Display Err";
+
+/// Shown for a schedule slot with nothing in it
+pub const NONE: &str = "None";
+/// A feature or output that is on
+pub const ON: &str = "On";
+/// A feature or output that is off
+pub const OFF: &str = "Off";
+/// An output following its automatic control
+pub const AUTO: &str = "Auto";
+/// A confirmed choice
+pub const YES: &str = "Yes";
+/// A declined choice
+pub const NO: &str = "No";
+
+/// The date fields, in the order they are edited
+pub const MINUTE: &str = "Minute";
+pub const HOUR: &str = "Hour";
+pub const DAY: &str = "Day";
+pub const MONTH: &str = "Month";
+pub const YEAR: &str = "Year";
+
+/// The days of the week, starting on Sunday; see [crate::datetime::Weekday]
+pub const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+/// The days of the week as one letter each, starting on Sunday
+pub const WEEKDAY_LETTERS: [char; 7] = ['S', 'M', 'T', 'W', 'T', 'F', 'S'];
+/// Stands in for a day a watering window skips
+pub const SKIPPED_DAY: char = '-';
+
+/// The pressure units; see [crate::sensors::PressureUnit]
+pub const MILLIBARS: &str = "mb";
+pub const INCHES_OF_MERCURY: &str = "in";
+
+/// The date formats; see [crate::datetime::DateFormat]
+pub const DATE_DMY: &str = "D/M/Y";
+pub const DATE_MDY: &str = "M/D/Y";
+pub const DATE_ISO: &str = "ISO";
+
+/// The vapor pressure deficit classifications; see [crate::preferences::VpdStatus]
+pub const VPD_LOW: &str = "Low";
+pub const VPD_OK: &str = "OK";
+pub const VPD_HIGH: &str = "High";
+
+/// The daylight saving time rules; see [crate::preferences::DstRule]
+pub const DST_US: &str = "US";
+pub const DST_EU: &str = "EU";
+pub const DST_CUSTOM: &str = "Custom";
+
+/// The outputs that can be overridden, in the order they are edited
+pub const VENT: &str = "Vent";
+pub const SPRINKLERS: &str = "Sprinklers";
+pub const HEATER: &str = "Heater";
+pub const BUZZER: &str = "Buzzer";
+/// The sprinklers, where [SPRINKLERS] does not fit
+pub const SPRINKLER: &str = "Sprinkler";
+
+/// The optional features, as edited and as listed on [crate::screen::Screen::Features]
+pub const WATERING: &str = "Watering";
+pub const WATERING_SHORT: &str = "Water";
+pub const SOIL: &str = "Soil";
+pub const LIGHT: &str = "Light";
+
+/// Why the sprinklers started, as listed on [crate::screen::Screen::History];
+/// see [crate::history::WateringReason]. The soil uses [SOIL]
+pub const SCHEDULE: &str = "Schedule";
+pub const WATERING_HUMIDITY: &str = "Humidity";
+pub const MANUAL: &str = "Manual";
+/// Shown instead of how long the sprinklers ran while they are still running
+pub const RUNNING: &str = "running";
+
+/// When the next watering window starts and when the sprinklers last ran, on [crate::screen::Screen::Watering]
+pub const NEXT_WATERING: &str = "Next";
+pub const WATERING_NOW: &str = "Watering now";
+pub const LAST_WATERED: &str = "Last";
+/// The watering duration setting, and its value while the sprinklers run for the whole window
+pub const WATERING_RUN: &str = "Run";
+pub const WHOLE_WINDOW: &str = "Window";
+
+/// The readings' labels on their screens
+pub const TEMPERATURE: &str = "Temp";
+pub const HUMIDITY: &str = "Humidity";
+pub const HUMIDITY_SHORT: &str = "RH";
+pub const FEELS_LIKE: &str = "Feels";
+pub const DEW_POINT: &str = "Dew Pt";
+pub const AIR: &str = "Air";
+pub const PRESSURE: &str = "PRS";
+pub const VPD: &str = "VPD";
+pub const GAS: &str = "Gas";
+/// The gas resistance right now, next to its [GAS_BASELINE]
+pub const GAS_NOW: &str = "Now";
+pub const BATTERY: &str = "Battery";
+/// Leads how fast the temperature is rising, once it rises faster than the rate limit
+pub const RISING: &str = "Rise";
+/// Marks a reading whose output is overridden by hand
+pub const OVERRIDE_MARK: &str = "[M]";
+/// Stands in for a reading that is not available yet, e.g. the soil moisture
+pub const NO_READING: &str = "--";
+/// Today's highs and lows on [crate::screen::Screen::Stats]
+pub const DAILY_HIGH: &str = "Hi";
+pub const DAILY_LOW: &str = "Lo";
+/// Which zone is shown on [crate::screen::Screen::Zone]
+pub const ZONE: &str = "Zone";
+/// The sensor read counts on [crate::screen::Screen::Diagnostics]: read, failed on the bus, and missing
+pub const READS_OK: &str = "OK";
+pub const BUS_ERRORS: &str = "Bus err";
+pub const SENSOR_MISSING: &str = "No sensor";
+/// How long ago the sensor was last read on [crate::screen::Screen::Diagnostics], or that it never was
+pub const LAST_OK: &str = "Last OK";
+pub const NEVER: &str = "never";
+/// Leads the overridden outputs on [crate::screen::Screen::Overrides]
+pub const OVERRIDDEN: &str = "Manual";
+/// The outputs as one letter each, where their names do not fit: Vent, Sprinklers, Heater, Buzzer
+pub const VENT_LETTER: &str = "V";
+pub const SPRINKLERS_LETTER: &str = "S";
+pub const HEATER_LETTER: &str = "H";
+pub const BUZZER_LETTER: &str = "B";
+/// Leads a fault's code; see [crate::error::GemError::code()]
+pub const FAULT_CODE: &str = "Code";
+
+/// The sensor poll interval and pressure unit settings, and the reset confirmation
+pub const POLL_INTERVAL: &str = "Poll";
+pub const PRESSURE_UNIT: &str = "Unit";
+pub const RESET: &str = "Reset";
+/// The clock and date settings: the clock format and its choices, the date format, and the DST rule
+pub const CLOCK_FORMAT: &str = "Clock";
+pub const CLOCK_24H: &str = "24h";
+pub const CLOCK_12H: &str = "12h";
+pub const DATE_FORMAT: &str = "Date";
+pub const DST: &str = "DST";
+/// The watering window being picked, and its days
+pub const WATERING_WINDOW: &str = "Window";
+pub const WATERING_DAYS: &str = "Days";
+/// The sensor calibration settings; see [crate::preferences::Preferences::temp_offset]
+pub const TEMP_OFFSET: &str = "Offset";
+pub const SMOOTHING: &str = "Smooth";
+pub const GAS_WARMUP: &str = "Warm-up";
+/// Whether the soil sensor is used
+pub const SOIL_SENSOR: &str = "Sensor";
+/// Leads a raw sensor reading while calibrating
+pub const RAW_READING: &str = "Raw";
+
+/// The soil sensor calibration prompts
+pub const SOIL_DRY_PROMPT: &str = "Hold in air";
+pub const SOIL_WET_PROMPT: &str = "Dip in water";
+
+/// The grow light schedule fields, in the order they are edited
+pub const LIGHT_ON_HOUR: &str = "On Hr";
+pub const LIGHT_ON_MINUTE: &str = "On Min";
+pub const LIGHT_OFF_HOUR: &str = "Off Hr";
+pub const LIGHT_OFF_MINUTE: &str = "Off Min";
+
+/// Screen titles and hints
+pub const OVERRIDES_TITLE: &str = "Overrides";
+pub const FEATURES_TITLE: &str = "Features";
+pub const RESET_TITLE: &str = "Reset Settings";
+pub const RESET_HINT: &str = "Select to start";
+pub const RESET_PROMPT: &str = "Reset settings?";
+pub const UPTIME_TITLE: &str = "Uptime";
+pub const MARGINS_TITLE: &str = "Hysteresis";
+pub const WATERING_LOG_TITLE: &str = "Watering log";
+
+/// Shown when a screen has nothing to report
+pub const NO_SOIL_SENSOR: &str = "No soil sensor";
+pub const NO_DATA_TODAY: &str = "No data today";
+pub const ALL_FEATURES_OFF: &str = "All off";
+pub const ALL_AUTOMATIC: &str = "All automatic";
+pub const NO_WATERING_YET: &str = "None yet";
+pub const NO_READINGS_YET: &str = "No readings yet";
+/// The readings on [crate::screen::Screen::Graph], each followed by its range; see [crate::graph::GraphSeries]
+pub const GRAPH_TEMPERATURE: &str = "Temp";
+pub const GRAPH_HUMIDITY: &str = "RH";
+pub const GRAPH_PRESSURE: &str = "PRS";
+/// Shown instead of the gas resistance until the BME680 has warmed up
+pub const WARMING_UP: &str = "Warm-up";
+
+/// The air quality on [crate::screen::Screen::AirQuality]: its baseline, while the baseline is learned,
+/// and while the vent is open for stale air; see [crate::air::AirQuality]
+pub const GAS_BASELINE: &str = "Base";
+pub const LEARNING: &str = "Learning";
+pub const AIR_VENTING: &str = "Venting";
+/// The air venting setting; see [crate::preferences::Preferences::air_venting]
+pub const AIR_VENT: &str = "Air vent";
+
+/// The screen auto-cycle setting; see [crate::preferences::Preferences::auto_cycle_seconds]
+pub const AUTO_CYCLE: &str = "Cycle";
+
+/// The ambient light on [crate::screen::Screen::Ambient], and what it is doing; see [crate::light_sensor::AmbientLight]
+pub const AMBIENT: &str = "Ambient";
+pub const AMBIENT_DARK: &str = "Dark";
+pub const AMBIENT_BRIGHT: &str = "Bright";
+/// The ambient light settings: how dark it gets before the backlight may turn off,
+/// and how bright before the grow light is skipped; see [crate::preferences::AmbientSettings]
+pub const BACKLIGHT_DARK: &str = "Dark";
+pub const GROW_CUTOFF: &str = "Sunny";
+/// The light sensor calibration prompts
+pub const AMBIENT_DARK_PROMPT: &str = "Cover sensor";
+pub const AMBIENT_BRIGHT_PROMPT: &str = "Shine light";
+
+/// The battery's state on [crate::screen::Screen::Battery], and the warning shown while it is low
+pub const BATTERY_OK: &str = "OK";
+pub const BATTERY_LOW: &str = "LOW";
+pub const LOW_BATTERY: &str = "Low batt";
+/// Shown after [BATTERY_LOW] while the grow light and fan are off to save power
+pub const SAVING_POWER: &str = "saving";
+
+/// The battery settings, in the order they are edited; see [crate::preferences::BatterySettings]
+pub const LOW_BATTERY_THRESHOLD: &str = "Low";
+pub const BATTERY_DIVIDER: &str = "Divider";
+pub const POWER_SAVER: &str = "Saver";
+
+/// The stack's high-water mark and how much was never used on [crate::screen::Screen::Memory]
+pub const STACK_PEAK: &str = "Stack peak";
+pub const STACK_FREE: &str = "Free";
+
+/// The boot self-test's title, results, and the subsystems it checks; see [crate::selftest::SelfTest]
+pub const SELF_TEST_TITLE: &str = "Self-test";
+pub const PASS: &str = "PASS";
+pub const FAIL: &str = "FAIL";
+pub const TEST_DISPLAY: &str = "LCD";
+pub const TEST_SENSOR: &str = "BME680";
+pub const TEST_OUTPUTS: &str = "Outputs";
+
+/// Picks [ON] or [OFF]
+///
+/// - param on: Whether the feature or output is on
+///
+/// returns the message for the state
+pub fn on_off(on: bool) -> &'static str {
+    if on {
+        ON
+    } else {
+        OFF
+    }
+}
+
+/// Picks [YES] or [NO]
+///
+/// - param yes: Whether the choice was confirmed
+///
+/// returns the message for the choice
+pub fn yes_no(yes: bool) -> &'static str {
+    if yes {
+        YES
+    } else {
+        NO
+    }
+}
@@ -0,0 +1,183 @@
+use embedded_hal::delay::DelayNs;
+use heapless::{String, Vec};
+
+use crate::error::GemError;
+use crate::rendering::{DisplaySurface, DEGREE_GLYPH, DOWN_ARROW_GLYPH, UP_ARROW_GLYPH};
+
+use panic_probe as _;
+
+/// The most columns a [MockLcd] can have, as on a 20x4 panel
+pub const MOCK_MAX_COLS: usize = 20;
+/// The most rows a [MockLcd] can have, as on a 20x4 panel
+pub const MOCK_MAX_ROWS: usize = 4;
+/// How many [LcdOperation]s a [MockLcd] records; later ones are still drawn, but not recorded
+pub const MOCK_OPERATIONS: usize = 128;
+
+/// One thing a [MockLcd] was told to do
+///
+/// - **Clear**: The screen was cleared
+/// - **SetCursor**: The cursor moved to a column and row: X, Y
+/// - **Write**: A character code was written
+/// - **CustomChar**: A glyph was uploaded into a CGRAM slot
+/// - **HideCursor**: The cursor was hidden
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LcdOperation {
+    Clear,
+    SetCursor(u8, u8),
+    Write(u8),
+    CustomChar(u8),
+    HideCursor,
+}
+
+/// A `COLS`x`ROWS` stand-in for a [crate::rendering::Lcd] that draws into memory, so layouts can be
+/// checked without a display. It is 16x2 unless specified, and at most [MOCK_MAX_COLS]x[MOCK_MAX_ROWS]
+///
+/// - **operations**: Every [LcdOperation], in order
+/// - **screen**: The character codes on the screen, one row per line
+/// - **cursor**: Where the next character is written: X, Y
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{LcdOperation, MockDelay, MockLcd};
+/// use gem_rs::rendering::render_screen;
+///
+/// let mut lcd: MockLcd = MockLcd::new();
+/// render_screen("Temp: 72°F", true, &mut lcd, &mut MockDelay).unwrap();
+/// render_screen("RH: 65%", false, &mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(lcd.row(0).as_str(), "Temp: 72°F      ");
+/// assert_eq!(lcd.row(1).as_str(), "RH: 65%         ");
+/// assert_eq!(lcd.operations[..2], [LcdOperation::Clear, LcdOperation::SetCursor(0, 0)]);
+///
+/// // Text past the edge is cut off
+/// render_screen("0123456789ABCDEFGH", true, &mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(lcd.row(0).as_str(), "0123456789ABCDEF");
+/// ```
+pub struct MockLcd<const COLS: u8 = 16, const ROWS: u8 = 2> {
+    pub operations: Vec<LcdOperation, MOCK_OPERATIONS>,
+    screen: [[u8; MOCK_MAX_COLS]; MOCK_MAX_ROWS],
+    cursor: (u8, u8),
+}
+
+impl<const COLS: u8, const ROWS: u8> Default for MockLcd<COLS, ROWS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const COLS: u8, const ROWS: u8> MockLcd<COLS, ROWS> {
+    /// Creates a new, blank MockLcd
+    ///
+    /// returns a new instance of MockLcd
+    pub fn new() -> MockLcd<COLS, ROWS> {
+        assert!(COLS as usize <= MOCK_MAX_COLS && ROWS as usize <= MOCK_MAX_ROWS);
+        Self {
+            operations: Vec::new(),
+            screen: [[b' '; MOCK_MAX_COLS]; MOCK_MAX_ROWS],
+            cursor: (0, 0),
+        }
+    }
+
+    /// Gets the character codes on a row
+    ///
+    /// - param row: The row, starting at 0
+    ///
+    /// returns the row's `COLS` character codes
+    pub fn row_bytes(&self, row: u8) -> &[u8] {
+        &self.screen[row as usize][..COLS as usize]
+    }
+
+    /// Gets a row as text. Glyph slots are shown as the characters that stand for them, like `°`,
+    /// and any other character code outside of ASCII as `?`
+    ///
+    /// - param row: The row, starting at 0
+    ///
+    /// returns the row's `COLS` characters
+    pub fn row(&self, row: u8) -> String<64> {
+        let mut text: String<64> = String::new();
+        for byte in self.row_bytes(row) {
+            let c = match *byte {
+                DEGREE_GLYPH => '°',
+                UP_ARROW_GLYPH => '↑',
+                DOWN_ARROW_GLYPH => '↓',
+                b' '..=b'~' => *byte as char,
+                _ => '?',
+            };
+            text.push(c).unwrap();
+        }
+        text
+    }
+
+    /// Gets where the cursor is
+    ///
+    /// returns the cursor's column and row: X, Y
+    pub fn cursor(&self) -> (u8, u8) {
+        self.cursor
+    }
+
+    /// Records an operation, if there is room left
+    ///
+    /// - param operation: The [LcdOperation]
+    fn record(&mut self, operation: LcdOperation) {
+        self.operations.push(operation).ok();
+    }
+}
+
+impl<const COLS: u8, const ROWS: u8> DisplaySurface for MockLcd<COLS, ROWS> {
+    const COLS: u8 = COLS;
+    const ROWS: u8 = ROWS;
+
+    fn clear(&mut self, _: &mut impl DelayNs) -> Result<(), GemError> {
+        self.record(LcdOperation::Clear);
+        self.screen = [[b' '; MOCK_MAX_COLS]; MOCK_MAX_ROWS];
+        self.cursor = (0, 0);
+        Ok(())
+    }
+
+    fn set_cursor(&mut self, position: (u8, u8), _: &mut impl DelayNs) -> Result<(), GemError> {
+        self.record(LcdOperation::SetCursor(position.0, position.1));
+        self.cursor = position;
+        Ok(())
+    }
+
+    fn write_str(&mut self, text: &str, delay: &mut impl DelayNs) -> Result<(), GemError> {
+        // Like the real display's charset, anything outside of ASCII is left blank
+        for c in text.chars() {
+            let byte = if c.is_ascii() { c as u8 } else { b' ' };
+            self.write_byte(byte, delay)?;
+        }
+        Ok(())
+    }
+
+    fn write_byte(&mut self, byte: u8, _: &mut impl DelayNs) -> Result<(), GemError> {
+        self.record(LcdOperation::Write(byte));
+        let (column, row) = self.cursor;
+        // Characters past the edge land off screen
+        if column < COLS && row < ROWS {
+            self.screen[row as usize][column as usize] = byte;
+        }
+        self.cursor.0 = column.saturating_add(1);
+        Ok(())
+    }
+
+    fn set_custom_char(
+        &mut self,
+        slot: u8,
+        _: &[u8; 8],
+        _: &mut impl DelayNs,
+    ) -> Result<(), GemError> {
+        self.record(LcdOperation::CustomChar(slot));
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self, _: &mut impl DelayNs) -> Result<(), GemError> {
+        self.record(LcdOperation::HideCursor);
+        Ok(())
+    }
+}
+
+/// A delay that returns at once, for drawing on a [MockLcd]
+pub struct MockDelay;
+
+impl DelayNs for MockDelay {
+    fn delay_ns(&mut self, _: u32) {}
+}
@@ -0,0 +1,118 @@
+use crate::datetime::DateTime;
+use crate::logger::ActuatorStates;
+use crate::messages::{BUZZER, HEATER, LIGHT, SPRINKLERS, VENT};
+
+use panic_probe as _;
+
+/// An output that an [ActuatorObserver] is told about
+///
+/// - **Vent**: The roof vent; on while it is open
+/// - **Sprinklers**: The sprinklers
+/// - **Heater**: The heater
+/// - **Light**: The grow light
+/// - **Buzzer**: The buzzer; on while an alarm or chirp is playing, or it is forced on.
+///   The beeps within a pattern are not changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Actuator {
+    Vent,
+    Sprinklers,
+    Heater,
+    Light,
+    Buzzer,
+}
+
+impl Actuator {
+    /// Gets the actuator's name for the LCD and logs
+    ///
+    /// returns the name; Ex: `Vent`
+    pub fn name(self) -> &'static str {
+        match self {
+            Actuator::Vent => VENT,
+            Actuator::Sprinklers => SPRINKLERS,
+            Actuator::Heater => HEATER,
+            Actuator::Light => LIGHT,
+            Actuator::Buzzer => BUZZER,
+        }
+    }
+}
+
+/// Something that wants to know when an actuator turns on or off, like a log or an external relay board.
+/// It is only told about actual changes, not every time the outputs are driven.
+///
+/// Observers are passed by generic reference, so there is no heap or dynamic dispatch.
+/// Two observers can be combined into one as a tuple: `(first, second)`
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::DateTime;
+/// use gem_rs::logger::ActuatorStates;
+/// use gem_rs::observer::{notify_changes, Actuator, ActuatorObserver, NoObserver};
+///
+/// struct Counter(u8);
+///
+/// impl ActuatorObserver for Counter {
+///     fn on_change(&mut self, actuator: Actuator, new_state: bool, _: &DateTime) {
+///         if actuator == Actuator::Heater && new_state {
+///             self.0 += 1;
+///         }
+///     }
+/// }
+///
+/// let date = DateTime::new(2024, 5, 31, 6, 0, 0);
+/// let off = ActuatorStates::default();
+/// let heating = ActuatorStates { heater: true, ..off };
+/// let mut observers = (Counter(0), NoObserver);
+///
+/// notify_changes(&off, &heating, &date, &mut observers);
+/// notify_changes(&heating, &heating, &date, &mut observers); // Nothing changed
+/// assert_eq!(observers.0 .0, 1);
+/// ```
+pub trait ActuatorObserver {
+    /// Called when an actuator turns on or off. Does nothing unless implemented
+    ///
+    /// - param actuator: The [Actuator] that changed
+    /// - param new_state: Whether it is now on
+    /// - param date: The local [DateTime] it changed at
+    #[allow(unused_variables)]
+    fn on_change(&mut self, actuator: Actuator, new_state: bool, date: &DateTime) {}
+}
+
+/// An [ActuatorObserver] that does nothing, for when no one needs to be told
+pub struct NoObserver;
+
+impl ActuatorObserver for NoObserver {}
+
+impl<A: ActuatorObserver, B: ActuatorObserver> ActuatorObserver for (A, B) {
+    fn on_change(&mut self, actuator: Actuator, new_state: bool, date: &DateTime) {
+        self.0.on_change(actuator, new_state, date);
+        self.1.on_change(actuator, new_state, date);
+    }
+}
+
+/// Tells an observer about every actuator that changed state
+///
+/// - param previous: The [ActuatorStates] before
+/// - param current: The [ActuatorStates] now
+/// - param date: The current local [DateTime]
+/// - param observer: The [ActuatorObserver] to tell
+pub fn notify_changes(
+    previous: &ActuatorStates,
+    current: &ActuatorStates,
+    date: &DateTime,
+    observer: &mut impl ActuatorObserver,
+) {
+    for (actuator, before, now) in [
+        (Actuator::Vent, previous.vent, current.vent),
+        (
+            Actuator::Sprinklers,
+            previous.sprinklers,
+            current.sprinklers,
+        ),
+        (Actuator::Heater, previous.heater, current.heater),
+        (Actuator::Light, previous.light, current.light),
+    ] {
+        if before != now {
+            observer.on_change(actuator, now, date);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use heapless::String;
+use ufmt::uwrite;
+
+use crate::messages::{AUTO, OFF, ON};
+use crate::preferences::MAX_VENT_ANGLE;
+
+use panic_probe as _;
+
+/// Manual states for each actuator, used during maintenance.
+/// Some(state) forces the actuator to that state; None leaves it under automatic control.
+///
+/// The smoke/fire alarm still takes over the vent, sprinklers, and heater;
+/// only the buzzer can be silenced during an alarm
+///
+/// - **vent**: The roof vent
+/// - **sprinklers**: The sprinklers
+/// - **heater**: The heater
+/// - **buzzer**: The alarm buzzer
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::overrides::Overrides;
+///
+/// let mut overrides = Overrides::default();
+/// overrides.vent = Some(true); // Force the vent open
+///
+/// assert!(overrides.vent_or(false)); // Open, even though it should be closed
+/// assert!(!overrides.heater_or(false)); // Still automatic
+/// assert!(overrides.is_active());
+/// ```
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct Overrides {
+    pub vent: Option<bool>,
+    pub sprinklers: Option<bool>,
+    pub heater: Option<bool>,
+    pub buzzer: Option<bool>,
+}
+
+impl Overrides {
+    /// Checks if any actuator is under manual control
+    ///
+    /// returns true if at least one override is engaged
+    pub fn is_active(&self) -> bool {
+        self.vent.is_some()
+            || self.sprinklers.is_some()
+            || self.heater.is_some()
+            || self.buzzer.is_some()
+    }
+
+    /// Gets the vent state to apply
+    ///
+    /// - param automatic: The state the automatic logic decided on
+    ///
+    /// returns the overridden state if set, otherwise the automatic state
+    pub fn vent_or(&self, automatic: bool) -> bool {
+        self.vent.unwrap_or(automatic)
+    }
+
+    /// Gets the vent angle to apply
+    ///
+    /// - param automatic: The angle the automatic logic decided on
+    ///
+    /// returns fully open or closed if overridden, otherwise the automatic angle
+    pub fn vent_angle_or(&self, automatic: u8) -> u8 {
+        match self.vent {
+            Some(true) => MAX_VENT_ANGLE,
+            Some(false) => 0,
+            None => automatic,
+        }
+    }
+
+    /// Gets the sprinkler state to apply
+    ///
+    /// - param automatic: The state the automatic logic decided on
+    ///
+    /// returns the overridden state if set, otherwise the automatic state
+    pub fn sprinklers_or(&self, automatic: bool) -> bool {
+        self.sprinklers.unwrap_or(automatic)
+    }
+
+    /// Gets the heater state to apply
+    ///
+    /// - param automatic: The state the automatic logic decided on
+    ///
+    /// returns the overridden state if set, otherwise the automatic state
+    pub fn heater_or(&self, automatic: bool) -> bool {
+        self.heater.unwrap_or(automatic)
+    }
+
+    /// Gets the buzzer state to apply
+    ///
+    /// - param automatic: The state the automatic logic decided on
+    ///
+    /// returns the overridden state if set, otherwise the automatic state
+    pub fn buzzer_or(&self, automatic: bool) -> bool {
+        self.buzzer.unwrap_or(automatic)
+    }
+}
+
+/// Cycles an override through Auto, On, and Off
+///
+/// - param state: The current override
+/// - param forward: Whether to cycle forwards; If false, cycle backwards
+///
+/// returns the next override
+pub fn cycle_override(state: Option<bool>, forward: bool) -> Option<bool> {
+    match (state, forward) {
+        (None, true) | (Some(true), false) => Some(false),
+        (Some(false), true) | (None, false) => Some(true),
+        _ => None,
+    }
+}
+
+/// Formats an override for the LCD
+///
+/// - param name: The actuator's name; Ex: Vent
+/// - param state: The override
+///
+/// returns the formatted override; Ex: `Vent: Auto`
+pub fn format_override(name: &str, state: Option<bool>) -> String<16> {
+    let mut str: String<16> = String::new();
+    let state = match state {
+        None => AUTO,
+        Some(true) => ON,
+        Some(false) => OFF,
+    };
+    uwrite!(str, "{}: {}", name, state).unwrap();
+    str
+}
@@ -1,214 +1,2668 @@
-use heapless::String;
-use ufmt::uwrite;
+use core::ops::Range;
+use embedded_hal::i2c::I2c;
+use heapless::{String, Vec};
+use ufmt::{uDisplay, uWrite, uwrite, Formatter};
+
+use crate::battery::PICO_VSYS_DIVIDER;
+use crate::datetime::{
+    nth_sunday, pad_number, time_to_minutes, ClockFormat, DateFormat, DateTime, Weekday,
+    MINUTES_PER_DAY,
+};
+use crate::light_sensor::DEFAULT_LIGHT_CALIBRATION;
+use crate::messages::{
+    on_off, AIR_VENT, AUTO_CYCLE, BACKLIGHT_DARK, BATTERY_DIVIDER, GAS_WARMUP, GROW_CUTOFF, HEATER,
+    HEATER_LETTER, LOW_BATTERY_THRESHOLD, NEXT_WATERING, NONE, OFF, POWER_SAVER, SKIPPED_DAY,
+    SMOOTHING, SPRINKLER, SPRINKLERS_LETTER, TEMP_OFFSET, VENT, VENT_LETTER, VPD_HIGH, VPD_LOW,
+    VPD_OK, WATERING_NOW, WEEKDAY_LETTERS,
+};
+use crate::rtc::{Rtc, RtcError};
+use crate::sensors::{format_hundredths, PressureUnit};
+use crate::timer::SENSOR_DELAY;
 
 use panic_probe as _;
 
-/// Preferences defines the consumer-selected range of acceptable values for each category.
-///
-/// - **temperature**: The acceptable temperature range in Fahrenheit
-/// - **humidity**: The acceptable relative humidity percentage range
-/// - **date**: The current date and time: Sec, Min, Hour, Day, Month, Year
-/// - **watering**: The minute and hour range for when watering should occur
-pub struct Preferences {
-    pub temperature: (u8, u8),
-    pub humidity: (u8, u8),
-    pub date: (u8, u8, u8, u8, u8, u16), // Sec, Min, Hour, Day, Month, Year
-    pub watering: Option<(u8, u8, u8, u8)>, // Start (Min, Hour), End (Min, Hour)
-}
+/// The size of a buffer that fits the longest one-line summary of [Preferences] from its [uDisplay] implementation
+pub const PREFERENCES_SUMMARY_BYTES: usize = 390;
+
+/// How many degrees Fahrenheit above the lower temperature bound the heater keeps running by default;
+/// see [HysteresisMargins::heater]
+pub const HEATER_DEADBAND: u8 = 2;
+
+/// How many degrees Fahrenheit above the upper temperature bound the cooling fan reaches full speed
+pub const FAN_SPAN: u8 = 10;
+
+/// How many degrees Fahrenheit above the upper temperature bound the roof vent is fully open
+pub const VENT_SPAN: u8 = 10;
+
+/// The roof vent's fully open angle in degrees
+pub const MAX_VENT_ANGLE: u8 = 180;
+
+/// How far in degrees the roof vent opens early while the temperature rises too fast;
+/// see [Preferences::temperature_rate_limit]
+pub const RATE_VENT_ANGLE: u8 = MAX_VENT_ANGLE / 2;
+
+/// How many percent of relative humidity the sprinklers overshoot the lower humidity bound by by default;
+/// see [HysteresisMargins::sprinklers]
+pub const HUMIDITY_HYSTERESIS: u8 = 3;
+
+/// How many degrees Fahrenheit below the upper temperature bound the roof vent stays open by default;
+/// see [HysteresisMargins::vent]
+pub const VENT_HYSTERESIS: u8 = 2;
+
+/// How far in degrees the roof vent stays open while it cools down through its margin:
+/// as far as it opens for one degree over the upper temperature bound
+pub const VENT_HOLD_ANGLE: u8 = MAX_VENT_ANGLE / VENT_SPAN;
+
+/// The widest hysteresis margin that can be set; see [HysteresisMargins]
+pub const MAX_HYSTERESIS_MARGIN: u8 = 10;
+
+/// The smallest battery voltage divider ratio in hundredths: 1.00, for a battery wired straight to the pin
+pub const MIN_BATTERY_DIVIDER: u16 = 100;
+/// The largest battery voltage divider ratio in hundredths: 10.00
+pub const MAX_BATTERY_DIVIDER: u16 = 1000;
+/// The highest low battery threshold in hundredths of a volt: 30.00V
+pub const MAX_LOW_BATTERY: u16 = 3000;
+
+/// The smallest drop in gas resistance below its baseline, in percent, that can open the vent
+pub const MIN_AIR_DROP: u8 = 5;
+/// The largest drop in gas resistance below its baseline, in percent, that can open the vent
+pub const MAX_AIR_DROP: u8 = 50;
+
+/// The shortest time in seconds each screen is shown for while the screens cycle on their own
+pub const MIN_AUTO_CYCLE: u8 = 2;
+/// The longest time in seconds each screen is shown for while the screens cycle on their own
+pub const MAX_AUTO_CYCLE: u8 = 60;
+
+/// The lowest brightness threshold in percent for the backlight or grow light; see [AmbientSettings]
+pub const MIN_AMBIENT_THRESHOLD: u8 = 5;
+/// The highest brightness threshold in percent for the backlight or grow light; see [AmbientSettings]
+pub const MAX_AMBIENT_THRESHOLD: u8 = 95;
+
+/// The largest temperature offset in tenths of a degree Celsius, in either direction
+pub const MAX_TEMP_OFFSET: i16 = 200;
+
+/// The largest humidity offset in percent, in either direction
+pub const MAX_HUMIDITY_OFFSET: i8 = 20;
+
+/// The longest the BME680 can be given to warm up, in minutes
+pub const MAX_GAS_WARMUP_MINUTES: u8 = 30;
+
+/// The full weight of a new reading in [Preferences::smoothing], which turns smoothing off
+pub const MAX_SMOOTHING: u8 = 16;
+
+/// The highest vapor pressure deficit target in hundredths of a kPa; plants close up well before it
+pub const MAX_VPD: u16 = 300;
+
+/// How many degrees Fahrenheit above the frost threshold it must warm up before the frost alarm clears
+pub const FROST_MARGIN: u8 = 2;
+
+/// The maximum amount of watering windows that can be scheduled per day
+pub const MAX_WATERING_WINDOWS: usize = 4;
+
+/// Every day of the week in [WateringWindow::days]
+pub const EVERY_DAY: u8 = 0b111_1111;
+
+/// The amount of bytes one [WateringWindow] takes up in serialized [Preferences]
+const WATERING_WINDOW_BYTES: usize = 5;
+
+/// A period of time during which watering should occur, on some or all days of the week
+///
+/// - **start**: The time watering starts: Min, Hour
+/// - **end**: The time watering ends: Min, Hour
+/// - **days**: The days of the week the window runs on, one bit per day; see [Weekday::mask()]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::Weekday;
+/// use gem_rs::preferences::WateringWindow;
+///
+/// let weekends = WateringWindow {
+///     days: Weekday::Saturday.mask() | Weekday::Sunday.mask(),
+///     ..WateringWindow::default()
+/// };
+/// assert!(weekends.runs_on(Weekday::Sunday));
+/// assert!(!weekends.runs_on(Weekday::Monday));
+/// assert_eq!(weekends.format_days().as_str(), "S-----S");
+/// ```
+#[derive(Clone, Copy, PartialEq)]
+pub struct WateringWindow {
+    pub start: (u8, u8),
+    pub end: (u8, u8),
+    pub days: u8,
+}
+
+impl Default for WateringWindow {
+    fn default() -> Self {
+        WateringWindow {
+            start: (0, 0), // 00:00
+            end: (0, 1),   // 01:00
+            days: EVERY_DAY,
+        }
+    }
+}
+
+impl WateringWindow {
+    /// Gets the start time of the window as minutes since midnight
+    ///
+    /// returns the start time in minutes
+    pub fn start_minutes(&self) -> u16 {
+        time_to_minutes(self.start)
+    }
+
+    /// Gets the end time of the window as minutes since midnight
+    ///
+    /// returns the end time in minutes
+    pub fn end_minutes(&self) -> u16 {
+        time_to_minutes(self.end)
+    }
+
+    /// Checks if a time falls within the window (inclusive)
+    ///
+    /// - param minutes: The time to check as minutes since midnight
+    ///
+    /// returns if the time is within the window
+    pub fn contains(&self, minutes: u16) -> bool {
+        minutes >= self.start_minutes() && minutes <= self.end_minutes()
+    }
+
+    /// Checks if the window runs on a day of the week
+    ///
+    /// - param weekday: The [Weekday] to check
+    ///
+    /// returns true if the day is in [WateringWindow::days]
+    pub fn runs_on(&self, weekday: Weekday) -> bool {
+        self.days & weekday.mask() != 0
+    }
+
+    /// Turns a day of the week on or off
+    ///
+    /// - param weekday: The [Weekday] to change
+    pub fn toggle_day(&mut self, weekday: Weekday) {
+        self.days ^= weekday.mask();
+    }
+
+    /// Formats the days the window runs on: one letter per day starting on Sunday,
+    /// or `-` for a skipped day. Ex: `-MTWTF-` for weekdays
+    ///
+    /// returns a [String] of length 7 containing the formatted days
+    pub fn format_days(&self) -> String<7> {
+        let mut str: String<7> = String::new();
+        for (weekday, letter) in Weekday::ALL.into_iter().zip(WEEKDAY_LETTERS) {
+            let letter = if self.runs_on(weekday) {
+                letter
+            } else {
+                SKIPPED_DAY
+            };
+            str.push(letter).unwrap();
+        }
+        str
+    }
+}
+
+/// How far past their threshold the outputs keep running, so a reading hovering around the
+/// threshold does not make them chatter; see [Hysteresis]. A margin of 0 switches right at the
+/// threshold, like a plain on/off thermostat
+///
+/// - **vent**: Degrees Fahrenheit below the upper temperature bound the roof vent stays open
+/// - **sprinklers**: Percent of relative humidity the sprinklers turn on below, and off above, the lower humidity bound
+/// - **heater**: Degrees Fahrenheit above the lower temperature bound the heater keeps running
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::preferences::{should_heat, HysteresisMargins, Preferences};
+///
+/// let mut preferences = Preferences::default(); // Lower bound is 60F
+/// assert_eq!(preferences.heater_points(), (60, 62));
+///
+/// // No margin switches right at the bound
+/// preferences.margins = HysteresisMargins { vent: 0, sprinklers: 0, heater: 0 };
+/// assert_eq!(preferences.heater_points(), (60, 60));
+/// assert_eq!(preferences.humidity_points(), (60, 60));
+/// assert_eq!(preferences.vent_points(), (80, 80));
+/// assert!(should_heat(59, false, &preferences));
+/// assert!(!should_heat(60, true, &preferences));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HysteresisMargins {
+    pub vent: u8,
+    pub sprinklers: u8,
+    pub heater: u8,
+}
+
+impl Default for HysteresisMargins {
+    fn default() -> Self {
+        HysteresisMargins {
+            vent: VENT_HYSTERESIS,
+            sprinklers: HUMIDITY_HYSTERESIS,
+            heater: HEATER_DEADBAND,
+        }
+    }
+}
+
+/// How the battery is measured, and what is done when it runs low; see [crate::battery::BatteryMonitor]
+///
+/// - **divider**: The voltage divider's ratio in hundredths, between [MIN_BATTERY_DIVIDER] and [MAX_BATTERY_DIVIDER]
+/// - **low_voltage**: The battery is low below this voltage, in hundredths of a volt
+/// - **power_saving**: Whether the grow light and fan are turned off while the battery is low.
+///   The sprinklers, vent, and heater keep running, so the greenhouse stays safe
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::preferences::{BatterySettings, Preferences, PrefError};
+///
+/// let mut preferences = Preferences::default();
+/// assert_eq!(preferences.battery, BatterySettings { divider: 300, low_voltage: 340, power_saving: true });
+///
+/// preferences.battery.divider = 50;
+/// assert_eq!(preferences.validate(), Err(PrefError::Battery));
+/// preferences.normalize();
+/// assert_eq!(preferences.battery.divider, 100);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatterySettings {
+    pub divider: u16,
+    pub low_voltage: u16,
+    pub power_saving: bool,
+}
+
+impl Default for BatterySettings {
+    fn default() -> Self {
+        BatterySettings {
+            divider: PICO_VSYS_DIVIDER,
+            low_voltage: 340, // A single lithium cell is nearly flat at 3.4V
+            power_saving: true,
+        }
+    }
+}
+
+/// How the ambient light is measured, and what it is used for; see [crate::light_sensor::AmbientLight].
+/// Each use can be turned off on its own. The sensor needs the `light-sensor` feature
+///
+/// - **calibration**: The photoresistor's raw readings covered and in daylight: Dark, Bright
+/// - **backlight_dark**: The brightness percentage below which it is dark. While it is light, the backlight
+///   stays on rather than timing out; once it is dark, it turns off when idle. None always times out
+/// - **grow_cutoff**: The brightness percentage above which it is bright enough that the grow light is skipped,
+///   even during its schedule. None follows the schedule alone
+///
+/// Both thresholds are between [MIN_AMBIENT_THRESHOLD] and [MAX_AMBIENT_THRESHOLD]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::preferences::{AmbientSettings, Preferences, PrefError};
+///
+/// let mut preferences = Preferences::default();
+/// assert_eq!(preferences.ambient.backlight_dark, None);
+/// assert_eq!(preferences.ambient.grow_cutoff, None);
+///
+/// preferences.ambient.grow_cutoff = Some(100);
+/// assert_eq!(preferences.validate(), Err(PrefError::Ambient));
+/// preferences.normalize();
+/// assert_eq!(preferences.ambient.grow_cutoff, Some(95));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientSettings {
+    pub calibration: (u16, u16),
+    pub backlight_dark: Option<u8>,
+    pub grow_cutoff: Option<u8>,
+}
+
+impl Default for AmbientSettings {
+    fn default() -> Self {
+        AmbientSettings {
+            calibration: DEFAULT_LIGHT_CALIBRATION,
+            backlight_dark: None,
+            grow_cutoff: None,
+        }
+    }
+}
+
+/// A daily period of time during which the grow light is on.
+/// Unlike a [WateringWindow], it may wrap past midnight
+///
+/// - **start**: The time the light turns on: Min, Hour
+/// - **end**: The time the light turns off: Min, Hour
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::preferences::LightWindow;
+///
+/// let window = LightWindow {
+///     start: (0, 20), // 20:00
+///     end: (0, 4),    // 04:00
+/// };
+/// assert!(window.contains(22 * 60)); // 22:00
+/// assert!(window.contains(60));      // 01:00
+/// assert!(!window.contains(4 * 60)); // 04:00, the light turns off
+/// assert!(!window.contains(12 * 60)); // 12:00
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightWindow {
+    pub start: (u8, u8),
+    pub end: (u8, u8),
+}
+
+impl Default for LightWindow {
+    fn default() -> Self {
+        LightWindow {
+            start: (0, 6), // 06:00
+            end: (0, 20),  // 20:00
+        }
+    }
+}
+
+impl LightWindow {
+    /// Checks if a time falls within the window. The light turns off at the end time
+    ///
+    /// - param minutes: The time to check as minutes since midnight
+    ///
+    /// returns if the time is within the window
+    pub fn contains(&self, minutes: u16) -> bool {
+        let start = time_to_minutes(self.start);
+        let end = time_to_minutes(self.end);
+        if start <= end {
+            minutes >= start && minutes < end
+        } else {
+            // Wraps past midnight
+            minutes >= start || minutes < end
+        }
+    }
+}
+
+/// The longest watering duration in minutes that can be set
+pub const MAX_WATERING_DURATION: u8 = 120;
+/// How long in minutes watering started by hand lasts when [Preferences::watering_duration_minutes]
+/// is 0, since there is no window for it to fill
+pub const MANUAL_WATERING_MINUTES: u8 = 10;
+
+/// The sensor poll intervals in milliseconds that can be picked on the pressure screen
+pub const SENSOR_INTERVALS: [u32; 5] = [1000, 2000, 5000, 10_000, 60_000];
+
+/// When daylight saving time (DST) starts and ends each year.
+/// Transitions happen on a Sunday at a given local (wall clock) hour
+///
+/// - **start_month**: The month DST starts (1-12)
+/// - **start_week**: Which Sunday of the month DST starts: 1-4, or 5 for the last Sunday
+/// - **start_hour**: The local hour DST starts; clocks jump forward an hour
+/// - **end_month**: The month DST ends (1-12)
+/// - **end_week**: Which Sunday of the month DST ends: 1-4, or 5 for the last Sunday
+/// - **end_hour**: The local hour DST ends; clocks fall back an hour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DstRule {
+    pub start_month: u8,
+    pub start_week: u8,
+    pub start_hour: u8,
+    pub end_month: u8,
+    pub end_week: u8,
+    pub end_hour: u8,
+}
+
+impl DstRule {
+    /// United States: the second Sunday of March to the first Sunday of November at 02:00
+    pub const US: DstRule = DstRule {
+        start_month: 3,
+        start_week: 2,
+        start_hour: 2,
+        end_month: 11,
+        end_week: 1,
+        end_hour: 2,
+    };
+
+    /// European Union (Central European Time): the last Sunday of March at 02:00
+    /// to the last Sunday of October at 03:00
+    pub const EU: DstRule = DstRule {
+        start_month: 3,
+        start_week: 5,
+        start_hour: 2,
+        end_month: 10,
+        end_week: 5,
+        end_hour: 3,
+    };
+}
+
+/// The version of the serialized [Preferences] layout. It is bumped whenever the layout changes,
+/// so settings saved by older firmware are rejected instead of misread
+pub const PREFERENCES_VERSION: u8 = 1;
+/// The amount of bytes [Preferences] takes up when serialized
+pub const PREFERENCES_BYTES: usize = 13 + MAX_WATERING_WINDOWS * WATERING_WINDOW_BYTES + 58;
+/// Where the format version is in serialized [Preferences]
+const VERSION_BYTE: usize = 0;
+/// Where the date is in serialized [Preferences]
+const DATE_BYTES: Range<usize> = 5..12;
+/// Where the clock format starts in serialized [Preferences]
+const CLOCK_FORMAT_BYTE: usize = 13 + MAX_WATERING_WINDOWS * WATERING_WINDOW_BYTES;
+/// Where the DST settings start in serialized [Preferences]
+const DST_BYTE: usize = CLOCK_FORMAT_BYTE + 6;
+/// Where the soil moisture settings start in serialized [Preferences]
+const SOIL_BYTE: usize = DST_BYTE + 10;
+/// Where the grow light schedule starts in serialized [Preferences]
+const LIGHT_BYTE: usize = SOIL_BYTE + 7;
+/// Where the smoothing weight is in serialized [Preferences]
+const SMOOTHING_BYTE: usize = LIGHT_BYTE + 5;
+/// Where the pressure unit is in serialized [Preferences]
+const PRESSURE_UNIT_BYTE: usize = SMOOTHING_BYTE + 1;
+/// Where the date format is in serialized [Preferences]
+const DATE_FORMAT_BYTE: usize = PRESSURE_UNIT_BYTE + 1;
+/// Where the VPD range starts in serialized [Preferences]
+const VPD_BYTE: usize = DATE_FORMAT_BYTE + 1;
+/// Where the temperature rate limit is in serialized [Preferences]
+const RATE_LIMIT_BYTE: usize = VPD_BYTE + 4;
+/// Where the humidity offset is in serialized [Preferences]
+const HUMIDITY_OFFSET_BYTE: usize = RATE_LIMIT_BYTE + 1;
+/// Where the gas sensor warm-up time is in serialized [Preferences]
+const GAS_WARMUP_BYTE: usize = HUMIDITY_OFFSET_BYTE + 1;
+/// Where the hysteresis margins start in serialized [Preferences]
+const MARGINS_BYTE: usize = GAS_WARMUP_BYTE + 1;
+/// Where the battery settings start in serialized [Preferences]
+const BATTERY_BYTE: usize = MARGINS_BYTE + 3;
+/// Where the air venting threshold is in serialized [Preferences]
+const AIR_VENT_BYTE: usize = BATTERY_BYTE + 5;
+/// Where the alarm bounds start in serialized [Preferences]
+const ALARM_BYTE: usize = AIR_VENT_BYTE + 1;
+/// Where the screen auto-cycle time is in serialized [Preferences]
+const AUTO_CYCLE_BYTE: usize = ALARM_BYTE + 4;
+/// Where the ambient light settings start in serialized [Preferences]
+const AMBIENT_BYTE: usize = AUTO_CYCLE_BYTE + 1;
+
+/// Errors that can occur while deserializing [Preferences]
+///
+/// - **Length**: There are not enough bytes
+/// - **Version**: The bytes were made by another version of the layout; see [PREFERENCES_VERSION]
+/// - **InvalidValue**: A byte holds a value that cannot be represented
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseError {
+    Length,
+    Version,
+    InvalidValue,
+}
+
+/// Problems [Preferences::validate()] can find
+///
+/// - **TemperatureRange**: The temperature bounds are reversed
+/// - **HumidityRange**: The humidity bounds are reversed or above 100%
+/// - **Date**: The date or time does not exist
+/// - **Watering**: A watering window has an invalid time or ends before it starts
+/// - **SensorInterval**: The sensor poll interval is not one of the [SENSOR_INTERVALS]
+/// - **WateringDuration**: The watering duration is above [MAX_WATERING_DURATION]
+/// - **TemperatureOffset**: The temperature offset is beyond [MAX_TEMP_OFFSET]
+/// - **SoilMoisture**: The soil moisture bounds are reversed or above 100%
+/// - **Light**: The grow light schedule has an invalid time
+/// - **Smoothing**: The smoothing weight is 0 or above [MAX_SMOOTHING]
+/// - **Vpd**: The VPD bounds are reversed or above [MAX_VPD]
+/// - **HumidityOffset**: The humidity offset is beyond [MAX_HUMIDITY_OFFSET]
+/// - **GasWarmup**: The gas sensor warm-up time is above [MAX_GAS_WARMUP_MINUTES]
+/// - **Margins**: A hysteresis margin is above [MAX_HYSTERESIS_MARGIN]
+/// - **Battery**: The battery divider is outside of [MIN_BATTERY_DIVIDER] - [MAX_BATTERY_DIVIDER],
+///   or the low battery threshold is above [MAX_LOW_BATTERY]
+/// - **AirVenting**: The air venting threshold is outside of [MIN_AIR_DROP] - [MAX_AIR_DROP]
+/// - **TemperatureAlarm**: The temperature alarm bounds are reversed
+/// - **HumidityAlarm**: The humidity alarm bounds are reversed or above 100%
+/// - **AutoCycle**: The screen auto-cycle time is outside of [MIN_AUTO_CYCLE] - [MAX_AUTO_CYCLE]
+/// - **Ambient**: A brightness threshold is outside of [MIN_AMBIENT_THRESHOLD] - [MAX_AMBIENT_THRESHOLD]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrefError {
+    TemperatureRange,
+    HumidityRange,
+    Date,
+    Watering,
+    SensorInterval,
+    WateringDuration,
+    TemperatureOffset,
+    SoilMoisture,
+    Light,
+    Smoothing,
+    Vpd,
+    HumidityOffset,
+    GasWarmup,
+    Margins,
+    Battery,
+    AirVenting,
+    TemperatureAlarm,
+    HumidityAlarm,
+    AutoCycle,
+    Ambient,
+}
+
+/// Preferences defines the consumer-selected range of acceptable values for each category.
+///
+/// - **temperature**: The acceptable temperature range in Fahrenheit
+/// - **humidity**: The acceptable relative humidity percentage range
+/// - **date**: The current [DateTime] in standard time.
+///   See [Preferences::local_date()] for the time including DST
+/// - **watering**: The [WateringWindow]s for when watering should occur
+/// - **clock_format**: The [ClockFormat] used to display the time
+/// - **sensor_interval_ms**: The delay in milliseconds between querying sensors
+/// - **watering_duration_minutes**: How long to water from the start of each [WateringWindow];
+///   0 waters for the whole window
+/// - **dst_enabled**: Whether the clock follows daylight saving time
+/// - **dst_rule**: The [DstRule] used when DST is enabled
+/// - **frost_threshold**: The temperature in Fahrenheit below which the frost alarm sounds
+/// - **temp_offset**: The BME680 temperature correction in tenths of a degree Celsius,
+///   compensating for the sensor heating itself
+/// - **soil_calibration**: The soil sensor's raw readings in dry air and in water: Dry, Wet.
+///   None if there is no soil sensor, so watering only follows the [WateringWindow]s
+/// - **soil_moisture**: The acceptable soil moisture percentage range. Scheduled watering is
+///   skipped at or above the upper bound, and the soil is watered off-schedule below the
+///   lower bound until it reaches the upper bound. A lower bound of 0 never waters off-schedule
+/// - **light**: The [LightWindow] for when the grow light is on, or None to leave it off
+/// - **smoothing**: How much each new temperature and humidity reading counts, in sixteenths, from 1 to
+///   [MAX_SMOOTHING]. Lower values smooth out more noise but follow real changes slower;
+///   [MAX_SMOOTHING] uses every reading as is. See [crate::sensors::Ema]
+/// - **pressure_unit**: The [PressureUnit] pressure is shown in
+/// - **date_format**: The [DateFormat] used to display the date
+/// - **vpd**: The target vapor pressure deficit range in hundredths of a kPa; see [vpd_status()]
+/// - **temperature_rate_limit**: How fast the temperature may rise, in tenths of a degree Fahrenheit per
+///   minute, before the vent opens early and a warning shows; see [is_rising_fast()]. 0 turns this off
+/// - **humidity_offset**: The BME680 humidity correction in percent, compensating for the sensor drifting;
+///   see [crate::sensors::offset_humidity()]
+/// - **gas_warmup_minutes**: How long the BME680's gas heater is given to settle after the sensor is set up,
+///   before anything is worked out from the gas resistance; see [crate::timer::SensorWarmup]. 0 trusts it right away
+/// - **margins**: The [HysteresisMargins] for the vent, sprinklers, and heater
+/// - **battery**: The [BatterySettings]. There is one battery, so every zone shares the first zone's
+/// - **air_venting**: How many percent the gas resistance may drop below its learned baseline before the
+///   vent opens to air out stale air, from [MIN_AIR_DROP] to [MAX_AIR_DROP]; see [crate::air::AirQuality].
+///   None leaves the vent to the temperature alone
+/// - **temperature_alarm**: The temperature range in Fahrenheit outside of which the alarm sounds, e.g. because
+///   the vent or heater failed. It is separate from **temperature**, which only drives the outputs;
+///   see [crate::alarm::ReadingAlarms]
+/// - **humidity_alarm**: The relative humidity percentage range outside of which the alarm sounds,
+///   separate from **humidity** like **temperature_alarm**
+/// - **auto_cycle_seconds**: How long in seconds each screen is shown for before the next one comes up
+///   on its own, from [MIN_AUTO_CYCLE] to [MAX_AUTO_CYCLE], for reading the display without touching it.
+///   None leaves the screens to the buttons; see [crate::screen::Screen::next_cycled()]
+/// - **ambient**: The [AmbientSettings]. There is one light sensor, so every zone shares the first zone's
+/// - **saved**: A checksum of the settings when they were last saved; see [Preferences::is_dirty()]
+#[derive(Clone)]
+pub struct Preferences {
+    pub temperature: (u8, u8),
+    pub humidity: (u8, u8),
+    pub date: DateTime,
+    pub watering: Vec<WateringWindow, MAX_WATERING_WINDOWS>,
+    pub clock_format: ClockFormat,
+    pub sensor_interval_ms: u32,
+    pub watering_duration_minutes: u8,
+    pub dst_enabled: bool,
+    pub dst_rule: DstRule,
+    pub frost_threshold: u8,
+    pub temp_offset: i16,
+    pub soil_calibration: Option<(u16, u16)>,
+    pub soil_moisture: (u8, u8),
+    pub light: Option<LightWindow>,
+    pub smoothing: u8,
+    pub pressure_unit: PressureUnit,
+    pub date_format: DateFormat,
+    pub vpd: (u16, u16),
+    pub temperature_rate_limit: u8,
+    pub humidity_offset: i8,
+    pub gas_warmup_minutes: u8,
+    pub margins: HysteresisMargins,
+    pub battery: BatterySettings,
+    pub air_venting: Option<u8>,
+    pub temperature_alarm: (u8, u8),
+    pub humidity_alarm: (u8, u8),
+    pub auto_cycle_seconds: Option<u8>,
+    pub ambient: AmbientSettings,
+    saved: u32,
+}
+
+// Every setting is serialized, so comparing the bytes compares them all, but not the change tracking
+impl PartialEq for Preferences {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+/// A one-line summary of the settings, for logs and the serial port. The format is kept stable
+/// so tools can parse it: space separated `key=value` pairs, always in this order
+///
+/// - **temp**, **humidity**: The temperature and humidity ranges: `60-80F`, `60-70%`
+/// - **frost**: The frost alarm threshold: `36F`
+/// - **soil**: The soil moisture range: `30-60%`
+/// - **vpd**: The vapor pressure deficit range: `0.80-1.20kPa`
+/// - **rate**: The temperature rate limit: `2.0F/min`
+/// - **windows**, **duration**: How many watering windows there are, and how long each waters: `1`, `10min`
+/// - **light**: The grow light schedule, `06:00-20:00`, or `off`
+/// - **interval**: The sensor poll interval: `2000ms`
+/// - **offset**: The temperature correction: `-8.9C`
+/// - **rh_offset**: The humidity correction: `+0%`
+/// - **smoothing**: The reading smoothing weight: `16/16`
+/// - **warmup**: The gas sensor warm-up time: `5min`
+/// - **margins**: The vent, sprinkler, and heater hysteresis margins: `2/3/2`
+/// - **battery**, **divider**, **saver**: The low battery threshold, the battery divider ratio,
+///   and whether power is saved while it is low: `3.40V`, `3.00`, `on`
+/// - **air_vent**: How far the gas resistance drops before the vent opens: `20%`, or `off`
+/// - **temp_alarm**, **rh_alarm**: The temperature and humidity alarm bounds: `32-95F`, `20-95%`
+/// - **cycle**: How long each screen is shown for while they cycle on their own: `10s`, or `off`
+/// - **backlight_dark**, **grow_cutoff**: The brightness the backlight may turn off below,
+///   and the grow light is skipped above: `20%`, or `off`
+///
+/// The buffer should hold [PREFERENCES_SUMMARY_BYTES]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::preferences::{Preferences, PREFERENCES_SUMMARY_BYTES};
+/// use heapless::String;
+/// use ufmt::uwrite;
+///
+/// let preferences = Preferences::default();
+/// let mut line: String<PREFERENCES_SUMMARY_BYTES> = String::new();
+/// uwrite!(line, "{}", preferences).unwrap();
+/// assert_eq!(
+///     line.as_str(),
+///     "temp=60-80F humidity=60-70% frost=36F soil=30-60% vpd=0.80-1.20kPa rate=2.0F/min \
+///      windows=0 duration=0min light=off interval=2000ms offset=-8.9C rh_offset=+0% \
+///      smoothing=16/16 warmup=5min margins=2/3/2 battery=3.40V divider=3.00 saver=on air_vent=off \
+///      temp_alarm=32-95F rh_alarm=20-95% cycle=off backlight_dark=off grow_cutoff=off"
+/// );
+/// ```
+impl uDisplay for Preferences {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        uwrite!(
+            f,
+            "temp={}-{}F humidity={}-{}% frost={}F soil={}-{}% vpd={}-{}kPa rate={}.{}F/min ",
+            self.temperature.0,
+            self.temperature.1,
+            self.humidity.0,
+            self.humidity.1,
+            self.frost_threshold,
+            self.soil_moisture.0,
+            self.soil_moisture.1,
+            format_hundredths(self.vpd.0).as_str(),
+            format_hundredths(self.vpd.1).as_str(),
+            self.temperature_rate_limit / 10,
+            self.temperature_rate_limit % 10,
+        )?;
+        uwrite!(
+            f,
+            "windows={} duration={}min light=",
+            self.watering.len(),
+            self.watering_duration_minutes,
+        )?;
+        match self.light {
+            Some(window) => uwrite!(
+                f,
+                "{}:{}-{}:{}",
+                pad_number(window.start.1).as_str(),
+                pad_number(window.start.0).as_str(),
+                pad_number(window.end.1).as_str(),
+                pad_number(window.end.0).as_str(),
+            )?,
+            None => uwrite!(f, "off")?,
+        }
+        let sign = if self.temp_offset < 0 { "-" } else { "" };
+        let tenths = self.temp_offset.unsigned_abs();
+        let humidity_sign = if self.humidity_offset < 0 { "-" } else { "+" };
+        uwrite!(
+            f,
+            " interval={}ms offset={}{}.{}C rh_offset={}{}% smoothing={}/{} warmup={}min margins={}/{}/{}",
+            self.sensor_interval_ms,
+            sign,
+            tenths / 10,
+            tenths % 10,
+            humidity_sign,
+            self.humidity_offset.unsigned_abs(),
+            self.smoothing,
+            MAX_SMOOTHING,
+            self.gas_warmup_minutes,
+            self.margins.vent,
+            self.margins.sprinklers,
+            self.margins.heater,
+        )?;
+        uwrite!(
+            f,
+            " battery={}V divider={} saver={}",
+            format_hundredths(self.battery.low_voltage).as_str(),
+            format_hundredths(self.battery.divider).as_str(),
+            if self.battery.power_saving {
+                "on"
+            } else {
+                "off"
+            },
+        )?;
+        match self.air_venting {
+            Some(percent) => uwrite!(f, " air_vent={}%", percent)?,
+            None => uwrite!(f, " air_vent=off")?,
+        }
+        uwrite!(
+            f,
+            " temp_alarm={}-{}F rh_alarm={}-{}%",
+            self.temperature_alarm.0,
+            self.temperature_alarm.1,
+            self.humidity_alarm.0,
+            self.humidity_alarm.1,
+        )?;
+        match self.auto_cycle_seconds {
+            Some(seconds) => uwrite!(f, " cycle={}s", seconds)?,
+            None => uwrite!(f, " cycle=off")?,
+        }
+        match self.ambient.backlight_dark {
+            Some(percent) => uwrite!(f, " backlight_dark={}%", percent)?,
+            None => uwrite!(f, " backlight_dark=off")?,
+        }
+        match self.ambient.grow_cutoff {
+            Some(percent) => uwrite!(f, " grow_cutoff={}%", percent),
+            None => uwrite!(f, " grow_cutoff=off"),
+        }
+    }
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        let mut preferences = Preferences {
+            temperature: (60, 80),     // Ideal range is 60F - 80F
+            humidity: (60, 70),        // Ideal range is 60% - 70%
+            date: DateTime::default(), // Date: 00:00:00 Jan 1 2000
+            watering: Vec::new(),      // No default watering times set
+            clock_format: ClockFormat::TwentyFourHour,
+            sensor_interval_ms: SENSOR_DELAY,
+            watering_duration_minutes: 0, // Water for the whole window
+            dst_enabled: false,
+            dst_rule: DstRule::US,
+            frost_threshold: 36, // About 2C
+            temp_offset: -89,    // -8.9C
+            soil_calibration: None,
+            soil_moisture: (30, 60),  // Ideal range is 30% - 60%
+            light: None,              // No grow light schedule set
+            smoothing: MAX_SMOOTHING, // No extra smoothing
+            pressure_unit: PressureUnit::Hectopascals,
+            date_format: DateFormat::Dmy,
+            vpd: (80, 120),             // Ideal range is 0.8 - 1.2 kPa
+            temperature_rate_limit: 20, // 10F in 5 minutes
+            humidity_offset: 0,
+            gas_warmup_minutes: 5,
+            margins: HysteresisMargins::default(),
+            battery: BatterySettings::default(),
+            air_venting: None,           // Not everyone has a vent to air out with
+            temperature_alarm: (32, 95), // Freezing, or the vent failed
+            humidity_alarm: (20, 95),
+            auto_cycle_seconds: None, // Screens only change with the buttons
+            ambient: AmbientSettings::default(),
+            saved: 0,
+        };
+        // There is nothing to save until a setting changes
+        preferences.mark_clean();
+        preferences
+    }
+}
+
+impl Preferences {
+    /// Sets the date from the [Rtc]
+    ///
+    /// - param rtc: [Rtc] instance
+    ///
+    /// returns an [RtcError] if the RTC could not be read; the date is then left unchanged
+    pub fn sync_from_rtc<I2C: I2c>(&mut self, rtc: &mut Rtc<I2C>) -> Result<(), RtcError> {
+        self.date = rtc.read_date()?;
+        Ok(())
+    }
+
+    /// Sets the [Rtc]'s date to this date
+    ///
+    /// - param rtc: [Rtc] instance
+    ///
+    /// returns an [RtcError] if the RTC could not be written
+    pub fn sync_to_rtc<I2C: I2c>(&self, rtc: &mut Rtc<I2C>) -> Result<(), RtcError> {
+        rtc.write_date(&self.date)
+    }
+
+    /// Copies the date, the clock settings, the sensor poll interval, the battery settings, the screen auto-cycle
+    /// time, and the ambient light settings from other [Preferences]. Every greenhouse zone keeps time with the same
+    /// clock, is read at the same time, runs off the same battery, is shown on the same display, and sits under the same sky
+    ///
+    /// - param other: The [Preferences] to copy from
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{ClockFormat, DateFormat, DateTime};
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut first = Preferences::default();
+    /// first.date = DateTime::new(2024, 6, 1, 12, 30, 0);
+    /// first.clock_format = ClockFormat::TwelveHour;
+    /// first.date_format = DateFormat::Iso;
+    /// first.dst_enabled = true;
+    /// first.battery.power_saving = false;
+    /// first.auto_cycle_seconds = Some(10);
+    /// first.ambient.grow_cutoff = Some(70);
+    /// first.temperature = (50, 90);
+    ///
+    /// let mut second = Preferences::default();
+    /// second.copy_clock_from(&first);
+    /// assert!(second.date == first.date);
+    /// assert!(second.clock_format == ClockFormat::TwelveHour);
+    /// assert!(second.date_format == DateFormat::Iso);
+    /// assert!(second.dst_enabled);
+    /// assert!(!second.battery.power_saving);
+    /// assert_eq!(second.auto_cycle_seconds, Some(10));
+    /// assert_eq!(second.ambient.grow_cutoff, Some(70));
+    /// assert_eq!(second.temperature, (60, 80)); // Zone settings are kept
+    /// ```
+    pub fn copy_clock_from(&mut self, other: &Preferences) {
+        self.date = other.date;
+        self.clock_format = other.clock_format;
+        self.date_format = other.date_format;
+        self.dst_enabled = other.dst_enabled;
+        self.dst_rule = other.dst_rule;
+        self.sensor_interval_ms = other.sensor_interval_ms;
+        self.battery = other.battery;
+        self.auto_cycle_seconds = other.auto_cycle_seconds;
+        self.ambient = other.ambient;
+    }
+
+    /// Restores every setting to its default.
+    /// The date is kept, since it is the current time rather than a setting.
+    /// The last save is kept too, so the defaults are saved next
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{ClockFormat, DateFormat, DateTime};
+    /// use gem_rs::preferences::{LightWindow, Preferences};
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.date = DateTime::new(2024, 6, 1, 12, 30, 0);
+    /// preferences.temperature = (50, 90);
+    /// preferences.humidity = (40, 50);
+    /// preferences.set_default_watering_time();
+    /// preferences.clock_format = ClockFormat::TwelveHour;
+    /// preferences.date_format = DateFormat::Mdy;
+    /// preferences.sensor_interval_ms = 60_000;
+    /// preferences.watering_duration_minutes = 10;
+    /// preferences.dst_enabled = true;
+    /// preferences.frost_threshold = 40;
+    /// preferences.temp_offset = 0;
+    /// preferences.soil_calibration = Some((3000, 1200));
+    /// preferences.soil_moisture = (20, 80);
+    /// preferences.light = Some(LightWindow::default());
+    /// preferences.mark_clean();
+    ///
+    /// preferences.reset();
+    /// let mut expected = Preferences::default();
+    /// expected.date = DateTime::new(2024, 6, 1, 12, 30, 0);
+    /// assert!(preferences == expected);
+    /// assert!(preferences.is_dirty()); // The defaults have not been saved yet
+    /// ```
+    pub fn reset(&mut self) {
+        *self = Preferences {
+            date: self.date,
+            saved: self.saved,
+            ..Preferences::default()
+        };
+    }
+
+    /// Checks that every value is legal without changing anything
+    ///
+    /// returns the first [PrefError] found
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::{PrefError, Preferences};
+    ///
+    /// let mut preferences = Preferences::default();
+    /// assert!(preferences.validate().is_ok());
+    ///
+    /// preferences.temp_offset = i16::MIN;
+    /// assert!(preferences.validate() == Err(PrefError::TemperatureOffset));
+    ///
+    /// // A corrupt interval would all but stop the sensor from being read
+    /// let mut preferences = Preferences::default();
+    /// preferences.sensor_interval_ms = u32::MAX;
+    /// assert!(preferences.validate() == Err(PrefError::SensorInterval));
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.watering_duration_minutes = 255;
+    /// assert!(preferences.validate() == Err(PrefError::WateringDuration));
+    /// ```
+    pub fn validate(&self) -> Result<(), PrefError> {
+        if self.temperature.0 > self.temperature.1 {
+            return Err(PrefError::TemperatureRange);
+        }
+        if self.humidity.0 > self.humidity.1 || self.humidity.1 > 100 {
+            return Err(PrefError::HumidityRange);
+        }
+        if !self.date.is_valid() {
+            return Err(PrefError::Date);
+        }
+        for window in self.watering.iter() {
+            if window.start.0 > 59
+                || window.start.1 > 23
+                || window.end.0 > 59
+                || window.end.1 > 23
+                || window.start_minutes() > window.end_minutes()
+                || window.days > EVERY_DAY
+            {
+                return Err(PrefError::Watering);
+            }
+        }
+        if !SENSOR_INTERVALS.contains(&self.sensor_interval_ms) {
+            return Err(PrefError::SensorInterval);
+        }
+        if self.watering_duration_minutes > MAX_WATERING_DURATION {
+            return Err(PrefError::WateringDuration);
+        }
+        if self.temp_offset.unsigned_abs() > MAX_TEMP_OFFSET as u16 {
+            return Err(PrefError::TemperatureOffset);
+        }
+        if self.soil_moisture.0 > self.soil_moisture.1 || self.soil_moisture.1 > 100 {
+            return Err(PrefError::SoilMoisture);
+        }
+        if let Some(window) = self.light {
+            if window.start.0 > 59 || window.start.1 > 23 || window.end.0 > 59 || window.end.1 > 23
+            {
+                return Err(PrefError::Light);
+            }
+        }
+        if self.smoothing == 0 || self.smoothing > MAX_SMOOTHING {
+            return Err(PrefError::Smoothing);
+        }
+        if self.vpd.0 > self.vpd.1 || self.vpd.1 > MAX_VPD {
+            return Err(PrefError::Vpd);
+        }
+        if self.humidity_offset.unsigned_abs() > MAX_HUMIDITY_OFFSET as u8 {
+            return Err(PrefError::HumidityOffset);
+        }
+        if self.gas_warmup_minutes > MAX_GAS_WARMUP_MINUTES {
+            return Err(PrefError::GasWarmup);
+        }
+        let margins = self.margins;
+        if [margins.vent, margins.sprinklers, margins.heater]
+            .iter()
+            .any(|margin| *margin > MAX_HYSTERESIS_MARGIN)
+        {
+            return Err(PrefError::Margins);
+        }
+        if !(MIN_BATTERY_DIVIDER..=MAX_BATTERY_DIVIDER).contains(&self.battery.divider)
+            || self.battery.low_voltage > MAX_LOW_BATTERY
+        {
+            return Err(PrefError::Battery);
+        }
+        if self
+            .air_venting
+            .is_some_and(|percent| !(MIN_AIR_DROP..=MAX_AIR_DROP).contains(&percent))
+        {
+            return Err(PrefError::AirVenting);
+        }
+        if self.temperature_alarm.0 > self.temperature_alarm.1 {
+            return Err(PrefError::TemperatureAlarm);
+        }
+        if self.humidity_alarm.0 > self.humidity_alarm.1 || self.humidity_alarm.1 > 100 {
+            return Err(PrefError::HumidityAlarm);
+        }
+        if self
+            .auto_cycle_seconds
+            .is_some_and(|seconds| !(MIN_AUTO_CYCLE..=MAX_AUTO_CYCLE).contains(&seconds))
+        {
+            return Err(PrefError::AutoCycle);
+        }
+        if [self.ambient.backlight_dark, self.ambient.grow_cutoff]
+            .into_iter()
+            .flatten()
+            .any(|percent| !(MIN_AMBIENT_THRESHOLD..=MAX_AMBIENT_THRESHOLD).contains(&percent))
+        {
+            return Err(PrefError::Ambient);
+        }
+        Ok(())
+    }
+
+    /// Fixes every illegal value. This should be called once after an edit completes
+    ///
+    /// - Reversed ranges and alarm bounds are swapped, humidity and soil moisture are capped at 100%,
+    ///   and the VPD range is capped at [MAX_VPD]
+    /// - The date and time are clamped to the nearest valid value
+    /// - The sensor poll interval is snapped to the nearest of the [SENSOR_INTERVALS]
+    /// - The watering duration is capped at [MAX_WATERING_DURATION]
+    /// - Watering windows are fixed by [Preferences::normalize_watering()]
+    /// - Grow light times are clamped; the window may wrap past midnight
+    /// - The temperature and humidity offsets are clamped to [MAX_TEMP_OFFSET] and [MAX_HUMIDITY_OFFSET]
+    /// - The smoothing weight is clamped between 1 and [MAX_SMOOTHING]
+    /// - The gas sensor warm-up time is capped at [MAX_GAS_WARMUP_MINUTES]
+    /// - The hysteresis margins are capped at [MAX_HYSTERESIS_MARGIN]
+    /// - The battery divider is clamped between [MIN_BATTERY_DIVIDER] and [MAX_BATTERY_DIVIDER],
+    ///   and the low battery threshold is capped at [MAX_LOW_BATTERY]
+    /// - The air venting threshold is clamped between [MIN_AIR_DROP] and [MAX_AIR_DROP]
+    /// - The screen auto-cycle time is clamped between [MIN_AUTO_CYCLE] and [MAX_AUTO_CYCLE]
+    /// - The brightness thresholds are clamped between [MIN_AMBIENT_THRESHOLD] and [MAX_AMBIENT_THRESHOLD]
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    /// use gem_rs::preferences::{Preferences, WateringWindow, EVERY_DAY};
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.temperature = (85, 65);                       // Reversed
+    /// preferences.date = DateTime::new(2023, 2, 30, 25, 0, 0);  // Hour 25, Feb 30
+    /// preferences.sensor_interval_ms = 7000;                    // Not one of the choices
+    /// preferences.add_watering_window(WateringWindow {
+    ///     start: (30, 7),                              // 07:30
+    ///     end: (0, 6),                                 // 06:00
+    ///     ..WateringWindow::default()
+    /// }).ok();
+    /// assert!(preferences.validate().is_err());
+    ///
+    /// preferences.normalize();
+    /// assert_eq!(preferences.temperature, (65, 85));
+    /// assert_eq!(preferences.sensor_interval_ms, 5000);
+    /// assert_eq!(preferences.date, DateTime::new(2023, 2, 28, 23, 0, 0));
+    /// assert!(preferences.watering[0] == WateringWindow { start: (0, 6), end: (30, 7), days: EVERY_DAY });
+    /// assert!(preferences.validate().is_ok());
+    /// ```
+    pub fn normalize(&mut self) {
+        if self.temperature.0 > self.temperature.1 {
+            core::mem::swap(&mut self.temperature.0, &mut self.temperature.1);
+        }
+        self.humidity.1 = self.humidity.1.min(100);
+        if self.humidity.0 > self.humidity.1 {
+            core::mem::swap(&mut self.humidity.0, &mut self.humidity.1);
+        }
+        if self.temperature_alarm.0 > self.temperature_alarm.1 {
+            core::mem::swap(&mut self.temperature_alarm.0, &mut self.temperature_alarm.1);
+        }
+        self.humidity_alarm.1 = self.humidity_alarm.1.min(100);
+        if self.humidity_alarm.0 > self.humidity_alarm.1 {
+            core::mem::swap(&mut self.humidity_alarm.0, &mut self.humidity_alarm.1);
+        }
+
+        self.date.normalize();
+
+        self.normalize_watering();
+
+        let interval = self.sensor_interval_ms;
+        self.sensor_interval_ms = SENSOR_INTERVALS
+            .into_iter()
+            .min_by_key(|choice| choice.abs_diff(interval))
+            .unwrap_or(SENSOR_DELAY);
+        self.watering_duration_minutes = self.watering_duration_minutes.min(MAX_WATERING_DURATION);
+
+        self.temp_offset = self.temp_offset.clamp(-MAX_TEMP_OFFSET, MAX_TEMP_OFFSET);
+        self.humidity_offset = self
+            .humidity_offset
+            .clamp(-MAX_HUMIDITY_OFFSET, MAX_HUMIDITY_OFFSET);
+
+        self.soil_moisture.1 = self.soil_moisture.1.min(100);
+        if self.soil_moisture.0 > self.soil_moisture.1 {
+            core::mem::swap(&mut self.soil_moisture.0, &mut self.soil_moisture.1);
+        }
+
+        if let Some(window) = self.light.as_mut() {
+            window.start = (window.start.0.min(59), window.start.1.min(23));
+            window.end = (window.end.0.min(59), window.end.1.min(23));
+        }
+
+        self.smoothing = self.smoothing.clamp(1, MAX_SMOOTHING);
+        self.gas_warmup_minutes = self.gas_warmup_minutes.min(MAX_GAS_WARMUP_MINUTES);
+        for margin in [
+            &mut self.margins.vent,
+            &mut self.margins.sprinklers,
+            &mut self.margins.heater,
+        ] {
+            *margin = (*margin).min(MAX_HYSTERESIS_MARGIN);
+        }
+        self.battery.divider = self
+            .battery
+            .divider
+            .clamp(MIN_BATTERY_DIVIDER, MAX_BATTERY_DIVIDER);
+        self.battery.low_voltage = self.battery.low_voltage.min(MAX_LOW_BATTERY);
+        if let Some(percent) = self.air_venting.as_mut() {
+            *percent = (*percent).clamp(MIN_AIR_DROP, MAX_AIR_DROP);
+        }
+        if let Some(seconds) = self.auto_cycle_seconds.as_mut() {
+            *seconds = (*seconds).clamp(MIN_AUTO_CYCLE, MAX_AUTO_CYCLE);
+        }
+        for percent in [
+            &mut self.ambient.backlight_dark,
+            &mut self.ambient.grow_cutoff,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *percent = (*percent).clamp(MIN_AMBIENT_THRESHOLD, MAX_AMBIENT_THRESHOLD);
+        }
+
+        self.vpd.1 = self.vpd.1.min(MAX_VPD);
+        if self.vpd.0 > self.vpd.1 {
+            core::mem::swap(&mut self.vpd.0, &mut self.vpd.1);
+        }
+    }
+
+    /// Fixes every watering window: the times are clamped to a valid time, and days past Saturday are dropped.
+    ///
+    /// A window that ends before it starts is reversed. Unlike the grow light's, watering windows do not
+    /// wrap past midnight, since a reversed window is far more likely a slip than a night of watering;
+    /// watering across midnight takes two windows. Whether the hours or only the minutes are reversed,
+    /// the times are compared as a whole. A window that starts and ends at the same time is kept,
+    /// and waters for that one minute, since [WateringWindow::contains()] includes its end
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::{Preferences, WateringWindow, EVERY_DAY};
+    ///
+    /// let mut preferences = Preferences::default();
+    /// for (start, end) in [
+    ///     ((45, 6), (15, 6)),  // 06:45 to 06:15: the same hour, reversed minutes
+    ///     ((30, 7), (30, 7)),  // 07:30 to 07:30: starts as it ends
+    ///     ((0, 22), (0, 2)),   // 22:00 to 02:00: reversed hours
+    ///     ((75, 30), (0, 25)), // Times that do not exist
+    /// ] {
+    ///     let window = WateringWindow { start, end, days: 0xFF };
+    ///     preferences.add_watering_window(window).ok();
+    /// }
+    /// assert!(preferences.validate().is_err());
+    ///
+    /// preferences.normalize_watering();
+    /// let windows: [((u8, u8), (u8, u8)); 4] = core::array::from_fn(|index| {
+    ///     let window = preferences.watering[index];
+    ///     (window.start, window.end)
+    /// });
+    /// assert_eq!(windows, [
+    ///     ((15, 6), (45, 6)),  // 06:15 to 06:45
+    ///     ((30, 7), (30, 7)),  // Kept as it was
+    ///     ((0, 2), (0, 22)),   // 02:00 to 22:00
+    ///     ((0, 23), (59, 23)), // 23:00 to 23:59, once clamped and reversed
+    /// ]);
+    /// assert!(preferences.watering.iter().all(|window| window.days == EVERY_DAY));
+    /// assert!(preferences.validate().is_ok());
+    ///
+    /// // The one-minute window waters at 07:30 only
+    /// let window = preferences.watering[1];
+    /// assert!(window.contains(7 * 60 + 30));
+    /// assert!(!window.contains(7 * 60 + 31));
+    /// ```
+    pub fn normalize_watering(&mut self) {
+        for window in self.watering.iter_mut() {
+            let WateringWindow { start, end, days } = window;
+            *start = (start.0.min(59), start.1.min(23));
+            *end = (end.0.min(59), end.1.min(23));
+            if time_to_minutes(*start) > time_to_minutes(*end) {
+                core::mem::swap(start, end);
+            }
+            *days &= EVERY_DAY;
+        }
+    }
+
+    /// Checks if daylight saving time is in effect.
+    /// Transitions follow [Preferences::dst_rule] on the stored standard-time date
+    ///
+    /// returns true if DST is enabled and the current date is within the DST period
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default(); // US rules
+    /// preferences.dst_enabled = true;
+    ///
+    /// // Spring forward: Mar 10 2024, 02:00 jumps to 03:00
+    /// preferences.date = DateTime::new(2024, 3, 10, 1, 59, 59);
+    /// assert!(!preferences.is_dst());
+    /// assert_eq!(preferences.get_date_formatted().0.as_str(), "01:59:59");
+    /// preferences.date.tick();
+    /// assert!(preferences.is_dst());
+    /// assert_eq!(preferences.get_date_formatted().0.as_str(), "03:00:00");
+    ///
+    /// // Fall back: Nov 3 2024, 02:00 returns to 01:00
+    /// preferences.date = DateTime::new(2024, 11, 3, 0, 59, 59);
+    /// assert!(preferences.is_dst());
+    /// assert_eq!(preferences.get_date_formatted().0.as_str(), "01:59:59");
+    /// preferences.date.tick();
+    /// assert!(!preferences.is_dst());
+    /// assert_eq!(preferences.get_date_formatted().0.as_str(), "01:00:00");
+    /// ```
+    pub fn is_dst(&self) -> bool {
+        self.dst_enabled && self.dst_active(self.date)
+    }
+
+    /// Gets the local date and time: the stored standard time plus an hour during DST
+    ///
+    /// returns the local [DateTime]
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateTime;
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.dst_enabled = true;
+    /// preferences.date = DateTime::new(2024, 7, 31, 23, 30, 0); // Standard time
+    /// assert_eq!(preferences.local_date(), DateTime::new(2024, 8, 1, 0, 30, 0));
+    ///
+    /// preferences.set_local_date(DateTime::new(2024, 8, 1, 12, 0, 0)); // Noon local time
+    /// assert_eq!(preferences.date, DateTime::new(2024, 8, 1, 11, 0, 0));
+    /// ```
+    pub fn local_date(&self) -> DateTime {
+        if self.is_dst() {
+            self.date.shift_hour(true)
+        } else {
+            self.date
+        }
+    }
+
+    /// Sets the date from a local date and time, storing it in standard time.
+    /// During the repeated hour when DST ends, the earlier (DST) hour is used
+    ///
+    /// - param local: The local [DateTime]
+    pub fn set_local_date(&mut self, local: DateTime) {
+        let standard = local.shift_hour(false);
+        self.date = if self.dst_enabled && self.dst_active(standard) {
+            standard
+        } else {
+            local
+        };
+    }
+
+    /// Checks if a standard-time date falls within the DST period of [Preferences::dst_rule]
+    ///
+    /// - param date: The date in standard time
+    ///
+    /// returns true if the date is within the DST period
+    fn dst_active(&self, date: DateTime) -> bool {
+        // Orders (month, day, hour) so transitions can be compared directly
+        let key =
+            |month: u8, day: u8, hour: u8| (month as i32 * 32 + day as i32) * 24 + hour as i32;
+        let rule = &self.dst_rule;
+        let now = key(date.month, date.day, date.hour);
+        let start = key(
+            rule.start_month,
+            nth_sunday(rule.start_week, rule.start_month, date.year),
+            rule.start_hour,
+        );
+        // The end hour is on the DST clock, which is an hour ahead of standard time
+        let end = key(
+            rule.end_month,
+            nth_sunday(rule.end_week, rule.end_month, date.year),
+            rule.end_hour,
+        ) - 1;
+
+        if start < end {
+            start <= now && now < end
+        } else {
+            // Southern hemisphere: DST spans the new year
+            now >= start || now < end
+        }
+    }
+
+    /// Gets the date in the `HH:MM:SS DD/MM/YYYY` format.
+    /// The date is in local time, see [Preferences::local_date()].
+    /// With [ClockFormat::TwelveHour] the time is `H:MM:SS AM` instead,
+    /// and the date is ordered by [Preferences::date_format]
+    ///
+    /// returns: `(HH:MM:SS, DD/MM/YYYY)`
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{ClockFormat, DateFormat, DateTime};
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.date = DateTime::new(2023, 12, 31, 23, 59, 59); // 23:59:59 Dec 31 2023
+    /// assert_eq!(preferences.get_date_formatted().1.as_str(), "31/12/2023");
+    ///
+    /// preferences.date.tick(); // Roll over into the new year
+    /// let (time, date) = preferences.get_date_formatted();
+    /// assert_eq!(time.as_str(), "00:00:00");
+    /// assert_eq!(date.as_str(), "01/01/2024");
+    ///
+    /// preferences.clock_format = ClockFormat::TwelveHour;
+    /// assert_eq!(preferences.get_date_formatted().0.as_str(), "12:00:00 AM"); // Midnight
+    ///
+    /// preferences.date_format = DateFormat::Iso;
+    /// assert_eq!(preferences.get_date_formatted().1.as_str(), "2024-01-01");
+    /// ```
+    pub fn get_date_formatted(&self) -> (String<11>, String<10>) {
+        self.local_date()
+            .format(self.clock_format, self.date_format)
+    }
+
+    /// Checks if it is time to enable the sprinklers, using local time.
+    /// Watering lasts [Preferences::watering_duration_minutes] from the start of a window,
+    /// but never past its end, and only on the window's days
+    ///
+    /// returns if the current time is within the watering part of any window.
+    /// Returns false if there are no watering windows set
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::Weekday;
+    /// use gem_rs::preferences::{Preferences, WateringWindow};
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.add_watering_window(WateringWindow {
+    ///     start: (0, 6), // 06:00
+    ///     end: (0, 7),   // 07:00
+    ///     ..WateringWindow::default()
+    /// }).ok();
+    /// preferences.watering_duration_minutes = 10;
+    ///
+    /// preferences.date.hour = 6;
+    /// preferences.date.minute = 9; // 06:09
+    /// assert!(preferences.is_watering_time());
+    /// preferences.date.minute = 10; // 06:10, 10 minutes have passed
+    /// assert!(!preferences.is_watering_time());
+    ///
+    /// preferences.watering_duration_minutes = 0; // Whole window
+    /// assert!(preferences.is_watering_time());
+    ///
+    /// preferences.watering[0].toggle_day(Weekday::Saturday); // Jan 1 2000 is a Saturday
+    /// assert!(!preferences.is_watering_time());
+    /// ```
+    pub fn is_watering_time(&self) -> bool {
+        let date = self.local_date();
+        let current_minutes = date.minutes_of_day();
+        let weekday = date.weekday();
+        self.watering.iter().any(|window| {
+            window.runs_on(weekday)
+                && window.contains(current_minutes)
+                && (self.watering_duration_minutes == 0
+                    || current_minutes - window.start_minutes()
+                        < self.watering_duration_minutes as u16)
+        })
+    }
+
+    /// Works out how long until the next watering window starts. Windows are looked for up to a week ahead,
+    /// so once today's have passed, the next one is found tomorrow or on the next day it runs
+    ///
+    /// returns the minutes until the next window starts, 0 while [Preferences::is_watering_time()],
+    /// or None if no window runs on any day
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::Weekday;
+    /// use gem_rs::preferences::{Preferences, WateringWindow};
+    ///
+    /// let mut preferences = Preferences::default(); // Jan 1 2000 is a Saturday
+    /// assert_eq!(preferences.minutes_until_watering(), None);
+    /// preferences.add_watering_window(WateringWindow {
+    ///     start: (0, 6), // 06:00
+    ///     end: (0, 7),   // 07:00
+    ///     ..WateringWindow::default()
+    /// }).ok();
+    ///
+    /// preferences.date.hour = 5; // 05:00
+    /// assert_eq!(preferences.minutes_until_watering(), Some(60));
+    /// preferences.date.hour = 6; // 06:00
+    /// assert_eq!(preferences.minutes_until_watering(), Some(0));
+    ///
+    /// // Past today's window, so across midnight to tomorrow's
+    /// preferences.date.hour = 23;
+    /// preferences.date.minute = 30; // 23:30
+    /// assert_eq!(preferences.minutes_until_watering(), Some(6 * 60 + 30));
+    ///
+    /// // Only on Mondays, two days on
+    /// for weekday in [Weekday::Sunday, Weekday::Tuesday, Weekday::Wednesday, Weekday::Thursday,
+    ///     Weekday::Friday, Weekday::Saturday] {
+    ///     preferences.watering[0].toggle_day(weekday);
+    /// }
+    /// assert_eq!(preferences.minutes_until_watering(), Some(24 * 60 + 6 * 60 + 30));
+    ///
+    /// // The earliest of several windows counts
+    /// preferences.add_watering_window(WateringWindow {
+    ///     start: (45, 23), // 23:45
+    ///     end: (50, 23),   // 23:50
+    ///     ..WateringWindow::default()
+    /// }).ok();
+    /// assert_eq!(preferences.minutes_until_watering(), Some(15));
+    ///
+    /// // Never on any day
+    /// preferences.watering[0].days = 0;
+    /// preferences.watering[1].days = 0;
+    /// assert_eq!(preferences.minutes_until_watering(), None);
+    /// ```
+    pub fn minutes_until_watering(&self) -> Option<u16> {
+        if self.is_watering_time() {
+            return Some(0);
+        }
+        let date = self.local_date();
+        let now = date.minutes_of_day();
+        let today = date.day_of_week() as usize;
+        self.watering
+            .iter()
+            .flat_map(|window| {
+                // Today's window only counts if it has not started yet; it comes round again in a week
+                (0..=7u16).filter_map(move |days| {
+                    let weekday = Weekday::ALL[(today + days as usize) % Weekday::ALL.len()];
+                    let start = window.start_minutes();
+                    (window.runs_on(weekday) && (days > 0 || start > now))
+                        .then(|| days * MINUTES_PER_DAY + start - now)
+                })
+            })
+            .min()
+    }
+
+    /// Formats when the next watering window starts: `Next in 45m`, `Next in 3h05m`, `Next in 2d06h`,
+    /// `Watering now`, or `Next: None`; see [Preferences::minutes_until_watering()]
+    ///
+    /// returns a [String] of length 16 containing the formatted time
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::Weekday;
+    /// use gem_rs::preferences::{Preferences, WateringWindow};
+    ///
+    /// let mut preferences = Preferences::default(); // 00:00 on a Saturday
+    /// assert_eq!(preferences.format_next_watering().as_str(), "Next: None");
+    /// preferences.add_watering_window(WateringWindow {
+    ///     start: (5, 3), // 03:05
+    ///     end: (0, 4),   // 04:00
+    ///     ..WateringWindow::default()
+    /// }).ok();
+    /// assert_eq!(preferences.format_next_watering().as_str(), "Next in 3h05m");
+    /// preferences.date.hour = 3;
+    /// preferences.date.minute = 5;
+    /// assert_eq!(preferences.format_next_watering().as_str(), "Watering now");
+    /// preferences.date.minute = 0;
+    /// assert_eq!(preferences.format_next_watering().as_str(), "Next in 5m");
+    /// preferences.date.hour = 4;
+    /// preferences.date.minute = 1; // 04:01, past the window, which only runs on Saturdays
+    /// preferences.watering[0].days = Weekday::Saturday.mask();
+    /// assert_eq!(preferences.format_next_watering().as_str(), "Next in 6d23h");
+    /// ```
+    pub fn format_next_watering(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.minutes_until_watering() {
+            None => uwrite!(str, "{}: {}", NEXT_WATERING, NONE),
+            Some(0) => uwrite!(str, "{}", WATERING_NOW),
+            Some(minutes) if minutes < 60 => uwrite!(str, "{} in {}m", NEXT_WATERING, minutes),
+            Some(minutes) if minutes < MINUTES_PER_DAY => uwrite!(
+                str,
+                "{} in {}h{}m",
+                NEXT_WATERING,
+                minutes / 60,
+                pad_number((minutes % 60) as u8).as_str()
+            ),
+            Some(minutes) => uwrite!(
+                str,
+                "{} in {}d{}h",
+                NEXT_WATERING,
+                minutes / MINUTES_PER_DAY,
+                pad_number((minutes % MINUTES_PER_DAY / 60) as u8).as_str()
+            ),
+        }
+        .unwrap();
+        str
+    }
+
+    /// Gets how long watering started by hand lasts: [Preferences::watering_duration_minutes],
+    /// or [MANUAL_WATERING_MINUTES] if that is set to water for the whole window
+    ///
+    /// returns the duration in milliseconds
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default(); // Whole window
+    /// assert_eq!(preferences.manual_watering_ms(), 600_000);
+    /// preferences.watering_duration_minutes = 3;
+    /// assert_eq!(preferences.manual_watering_ms(), 180_000);
+    /// ```
+    pub fn manual_watering_ms(&self) -> u32 {
+        let minutes = match self.watering_duration_minutes {
+            0 => MANUAL_WATERING_MINUTES,
+            minutes => minutes,
+        };
+        minutes as u32 * 60_000
+    }
+
+    /// Gets how long the BME680 is given to warm up after it is set up
+    ///
+    /// returns the warm-up time in milliseconds
+    pub fn gas_warmup_ms(&self) -> u32 {
+        self.gas_warmup_minutes as u32 * 60_000
+    }
+
+    /// Checks if the grow light should be on
+    ///
+    /// returns true if there is a grow light schedule and the local time is within it
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::{LightWindow, Preferences};
+    ///
+    /// let mut preferences = Preferences::default(); // 00:00
+    /// assert!(!preferences.is_light_time()); // No schedule
+    ///
+    /// preferences.light = Some(LightWindow {
+    ///     start: (0, 20), // 20:00
+    ///     end: (0, 4),    // 04:00
+    /// });
+    /// assert!(preferences.is_light_time());
+    /// preferences.date.hour = 4; // 04:00
+    /// assert!(!preferences.is_light_time());
+    /// ```
+    pub fn is_light_time(&self) -> bool {
+        let current_minutes = self.local_date().minutes_of_day();
+        self.light
+            .is_some_and(|window| window.contains(current_minutes))
+    }
+
+    /// Formats the grow light schedule: `HH:MM - HH:MM`
+    ///
+    /// returns a [String] of length 16 containing the formatted times,
+    /// or the [NONE] message if there is no schedule
+    pub fn format_light_time(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        if let Some(window) = self.light {
+            uwrite!(
+                str,
+                "{}:{} - {}:{}",
+                pad_number(window.start.1).as_str(),
+                pad_number(window.start.0).as_str(),
+                pad_number(window.end.1).as_str(),
+                pad_number(window.end.0).as_str(),
+            )
+            .unwrap();
+        } else {
+            uwrite!(str, "{}", NONE).unwrap();
+        }
+        str
+    }
+
+    /// Formats a watering window: `HH:MM - HH:MM`
+    ///
+    /// - param index: The index of the watering window to format
+    ///
+    /// Returns a [String] of length 16 containing the formatted times,
+    /// or the [NONE] message if there is no window at the index
+    pub fn format_watering_time(&self, index: usize) -> String<16> {
+        let mut str: String<16> = String::new();
+        if let Some(window) = self.watering.get(index) {
+            uwrite!(
+                str,
+                "{}:{} - {}:{}",
+                pad_number(window.start.1).as_str(),
+                pad_number(window.start.0).as_str(),
+                pad_number(window.end.1).as_str(),
+                pad_number(window.end.0).as_str(),
+            )
+            .unwrap();
+        } else {
+            uwrite!(str, "{}", NONE).unwrap();
+        }
+        str
+    }
+
+    /// Formats the temperature offset: `Offset: -8.9C`
+    ///
+    /// returns a [String] of length 16 containing the formatted offset
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// assert_eq!(preferences.format_temp_offset().as_str(), "Offset: -8.9C");
+    /// preferences.temp_offset = -5;
+    /// assert_eq!(preferences.format_temp_offset().as_str(), "Offset: -0.5C");
+    /// preferences.temp_offset = 12;
+    /// assert_eq!(preferences.format_temp_offset().as_str(), "Offset: +1.2C");
+    /// ```
+    pub fn format_temp_offset(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        let sign = if self.temp_offset < 0 { "-" } else { "+" };
+        let tenths = self.temp_offset.unsigned_abs();
+        uwrite!(
+            str,
+            "{}: {}{}.{}C",
+            TEMP_OFFSET,
+            sign,
+            tenths / 10,
+            tenths % 10
+        )
+        .unwrap();
+        str
+    }
+
+    /// Formats the humidity offset: `Offset: +3%`
+    ///
+    /// returns a [String] of length 16 containing the formatted offset
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// assert_eq!(preferences.format_humidity_offset().as_str(), "Offset: +0%");
+    /// preferences.humidity_offset = -7;
+    /// assert_eq!(preferences.format_humidity_offset().as_str(), "Offset: -7%");
+    /// preferences.humidity_offset = 12;
+    /// assert_eq!(preferences.format_humidity_offset().as_str(), "Offset: +12%");
+    /// ```
+    pub fn format_humidity_offset(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        let sign = if self.humidity_offset < 0 { "-" } else { "+" };
+        uwrite!(
+            str,
+            "Offset: {}{}%",
+            sign,
+            self.humidity_offset.unsigned_abs()
+        )
+        .unwrap();
+        str
+    }
+
+    /// Formats the smoothing weight: `Smooth: 4/16`, or `Smooth: Off` at [MAX_SMOOTHING]
+    ///
+    /// returns a [String] of length 16 containing the formatted weight
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// assert_eq!(preferences.format_smoothing().as_str(), "Smooth: Off");
+    /// preferences.smoothing = 4;
+    /// assert_eq!(preferences.format_smoothing().as_str(), "Smooth: 4/16");
+    /// ```
+    pub fn format_smoothing(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        if self.smoothing >= MAX_SMOOTHING {
+            uwrite!(str, "{}: {}", SMOOTHING, OFF).unwrap();
+        } else {
+            uwrite!(str, "{}: {}/{}", SMOOTHING, self.smoothing, MAX_SMOOTHING).unwrap();
+        }
+        str
+    }
+
+    /// Formats the gas sensor warm-up time: `Warm-up: 5min`, or `Warm-up: Off` at 0
+    ///
+    /// returns a [String] of length 16 containing the formatted time
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// assert_eq!(preferences.format_gas_warmup().as_str(), "Warm-up: 5min");
+    /// preferences.gas_warmup_minutes = 0;
+    /// assert_eq!(preferences.format_gas_warmup().as_str(), "Warm-up: Off");
+    /// ```
+    pub fn format_gas_warmup(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        if self.gas_warmup_minutes == 0 {
+            uwrite!(str, "{}: {}", GAS_WARMUP, OFF).unwrap();
+        } else {
+            uwrite!(str, "{}: {}min", GAS_WARMUP, self.gas_warmup_minutes).unwrap();
+        }
+        str
+    }
+
+    /// Formats one hysteresis margin: `Vent: 2F`, `Sprinkler: 3%`, or `Heater: 2F`
+    ///
+    /// - param field: The margin: 0 = Vent, 1 = Sprinklers, 2 = Heater
+    ///
+    /// returns a [String] of length 16 containing the formatted margin
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let preferences = Preferences::default();
+    /// assert_eq!(preferences.format_margin(0).as_str(), "Vent: 2F");
+    /// assert_eq!(preferences.format_margin(1).as_str(), "Sprinkler: 3%");
+    /// assert_eq!(preferences.format_margin(2).as_str(), "Heater: 2F");
+    /// ```
+    pub fn format_margin(&self, field: u8) -> String<16> {
+        let mut str: String<16> = String::new();
+        match field {
+            0 => uwrite!(str, "{}: {}F", VENT, self.margins.vent),
+            1 => uwrite!(str, "{}: {}%", SPRINKLER, self.margins.sprinklers),
+            _ => uwrite!(str, "{}: {}F", HEATER, self.margins.heater),
+        }
+        .unwrap();
+        str
+    }
+
+    /// Formats every hysteresis margin on one line: `V2F S3% H2F`
+    ///
+    /// returns a [String] of length 16 containing the formatted margins
+    pub fn format_margins(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        uwrite!(
+            str,
+            "{}{}F {}{}% {}{}F",
+            VENT_LETTER,
+            self.margins.vent,
+            SPRINKLERS_LETTER,
+            self.margins.sprinklers,
+            HEATER_LETTER,
+            self.margins.heater
+        )
+        .unwrap();
+        str
+    }
+
+    /// Formats one battery setting: `Low: 3.40V`, `Divider: 3.00`, or `Saver: On`
+    ///
+    /// - param field: The setting: 0 = Low battery threshold, 1 = Divider, 2 = Power saving
+    ///
+    /// returns a [String] of length 16 containing the formatted setting
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let preferences = Preferences::default();
+    /// assert_eq!(preferences.format_battery_setting(0).as_str(), "Low: 3.40V");
+    /// assert_eq!(preferences.format_battery_setting(1).as_str(), "Divider: 3.00");
+    /// assert_eq!(preferences.format_battery_setting(2).as_str(), "Saver: On");
+    /// ```
+    pub fn format_battery_setting(&self, field: u8) -> String<16> {
+        let mut str: String<16> = String::new();
+        match field {
+            0 => uwrite!(
+                str,
+                "{}: {}V",
+                LOW_BATTERY_THRESHOLD,
+                format_hundredths(self.battery.low_voltage).as_str()
+            ),
+            1 => uwrite!(
+                str,
+                "{}: {}",
+                BATTERY_DIVIDER,
+                format_hundredths(self.battery.divider).as_str()
+            ),
+            _ => uwrite!(
+                str,
+                "{}: {}",
+                POWER_SAVER,
+                on_off(self.battery.power_saving)
+            ),
+        }
+        .unwrap();
+        str
+    }
+
+    /// Formats the air venting threshold: `Air vent: 20%`, or `Air vent: Off`
+    ///
+    /// returns a [String] of length 16 containing the formatted threshold
+    pub fn format_air_venting(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.air_venting {
+            Some(percent) => uwrite!(str, "{}: {}%", AIR_VENT, percent),
+            None => uwrite!(str, "{}: {}", AIR_VENT, OFF),
+        }
+        .unwrap();
+        str
+    }
+
+    /// Formats the screen auto-cycle time: `Cycle: 10s`, or `Cycle: Off`
+    ///
+    /// returns a [String] of length 16 containing the formatted time
+    pub fn format_auto_cycle(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        match self.auto_cycle_seconds {
+            Some(seconds) => uwrite!(str, "{}: {}s", AUTO_CYCLE, seconds),
+            None => uwrite!(str, "{}: {}", AUTO_CYCLE, OFF),
+        }
+        .unwrap();
+        str
+    }
+
+    /// Formats one ambient light setting: `Dark: 20%`, or `Sunny: Off`
+    ///
+    /// - param field: The setting: 0 = [AmbientSettings::backlight_dark], 1 = [AmbientSettings::grow_cutoff]
+    ///
+    /// returns a [String] of length 16 containing the formatted setting
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.ambient.backlight_dark = Some(20);
+    /// assert_eq!(preferences.format_ambient_setting(0).as_str(), "Dark: 20%");
+    /// assert_eq!(preferences.format_ambient_setting(1).as_str(), "Sunny: Off");
+    /// ```
+    pub fn format_ambient_setting(&self, field: u8) -> String<16> {
+        let (name, threshold) = match field {
+            0 => (BACKLIGHT_DARK, self.ambient.backlight_dark),
+            _ => (GROW_CUTOFF, self.ambient.grow_cutoff),
+        };
+        let mut str: String<16> = String::new();
+        match threshold {
+            Some(percent) => uwrite!(str, "{}: {}%", name, percent),
+            None => uwrite!(str, "{}: {}", name, OFF),
+        }
+        .unwrap();
+        str
+    }
+
+    /// Adds a watering window
+    ///
+    /// - param window: The [WateringWindow] to add
+    ///
+    /// returns the index of the new window, or the window back if there is no room left
+    pub fn add_watering_window(&mut self, window: WateringWindow) -> Result<usize, WateringWindow> {
+        self.watering.push(window)?;
+        Ok(self.watering.len() - 1)
+    }
+
+    /// Removes a watering window
+    ///
+    /// - param index: The index of the watering window to remove
+    ///
+    /// returns the removed [WateringWindow], or None if there is no window at the index
+    pub fn remove_watering_window(&mut self, index: usize) -> Option<WateringWindow> {
+        if index < self.watering.len() {
+            Some(self.watering.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over the watering windows
+    ///
+    /// returns an iterator over every [WateringWindow]
+    pub fn watering_windows(&self) -> impl Iterator<Item = &WateringWindow> {
+        self.watering.iter()
+    }
 
-impl Default for Preferences {
-    fn default() -> Self {
-        Preferences {
-            temperature: (60, 80),       // Ideal range is 60F - 80F
-            humidity: (60, 70),          // Ideal range is 60% - 70%
-            date: (0, 0, 0, 1, 1, 2000), // Date: 00:00:00 Jan 1 2000
-            watering: None,              // No default watering times set
+    /// Serializes the Preferences into a compact byte array
+    ///
+    /// Layout:
+    /// - **0**: Format version: [PREFERENCES_VERSION]
+    /// - **1..3**: Temperature range: Low, High
+    /// - **3..5**: Humidity range: Low, High
+    /// - **5..12**: Date: Sec, Min, Hour, Day, Month, Year (2 bytes, little endian)
+    /// - **12**: Amount of watering windows
+    /// - **13..33**: 4 watering windows: Start Min, Start Hour, End Min, End Hour, Days.
+    ///   Unused windows are zeroed
+    /// - **33**: Clock format: 0 = 24-hour, 1 = 12-hour
+    /// - **34..38**: Sensor poll interval in milliseconds (little endian)
+    /// - **38**: Watering duration in minutes
+    /// - **39**: DST: 0 = disabled, 1 = enabled
+    /// - **40..46**: DST rule: Start Month, Start Week, Start Hour, End Month, End Week, End Hour
+    /// - **46**: Frost threshold in Fahrenheit
+    /// - **47..49**: Temperature offset in tenths of a degree Celsius (little endian)
+    /// - **49**: Soil sensor: 0 = none, 1 = calibrated
+    /// - **50..54**: Soil calibration: Dry, Wet (2 bytes each, little endian). Zeroed without a sensor
+    /// - **54..56**: Soil moisture range: Low, High
+    /// - **56**: Grow light: 0 = no schedule, 1 = scheduled
+    /// - **57..61**: Grow light window: Start Min, Start Hour, End Min, End Hour. Zeroed without a schedule
+    /// - **61**: Smoothing weight in sixteenths
+    /// - **62**: Pressure unit: 0 = hPa, 1 = inHg
+    /// - **63**: Date format: 0 = D/M/Y, 1 = M/D/Y, 2 = ISO 8601
+    /// - **64..68**: VPD range in hundredths of a kPa: Low, High (2 bytes each, little endian)
+    /// - **68**: Temperature rate limit in tenths of a degree Fahrenheit per minute
+    /// - **69**: Humidity offset in percent (two's complement)
+    /// - **70**: Gas sensor warm-up time in minutes
+    /// - **71..74**: Hysteresis margins: Vent, Sprinklers, Heater
+    /// - **74..76**: Battery divider ratio in hundredths (little endian)
+    /// - **76..78**: Low battery threshold in hundredths of a volt (little endian)
+    /// - **78**: Power saving while the battery is low: 0 = disabled, 1 = enabled
+    /// - **79**: Air venting threshold in percent: 0 = disabled
+    /// - **80..82**: Temperature alarm bounds in Fahrenheit: Low, High
+    /// - **82..84**: Humidity alarm bounds in percent: Low, High
+    /// - **84**: Screen auto-cycle time in seconds: 0 = disabled
+    /// - **85..89**: Light sensor calibration: Dark, Bright (2 bytes each, little endian)
+    /// - **89**: Brightness the backlight may turn off below, in percent: 0 = disabled
+    /// - **90**: Brightness the grow light is skipped above, in percent: 0 = disabled
+    ///
+    /// returns the serialized Preferences
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::DateFormat;
+    /// use gem_rs::preferences::{LightWindow, Preferences};
+    /// use gem_rs::sensors::PressureUnit;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// preferences.date.year = 2077;
+    /// preferences.set_default_watering_time();
+    /// preferences.soil_calibration = Some((3000, 1200));
+    /// preferences.light = Some(LightWindow::default());
+    /// preferences.pressure_unit = PressureUnit::InchesOfMercury;
+    /// preferences.date_format = DateFormat::Iso;
+    /// preferences.vpd = (40, 160);
+    /// preferences.temperature_rate_limit = 0;
+    /// preferences.humidity_offset = -4;
+    /// preferences.gas_warmup_minutes = 0;
+    /// preferences.margins.vent = 0;
+    /// preferences.battery.low_voltage = 1150;
+    /// preferences.battery.power_saving = false;
+    /// preferences.air_venting = Some(20);
+    /// preferences.temperature_alarm = (40, 100);
+    /// preferences.humidity_alarm = (10, 90);
+    /// preferences.auto_cycle_seconds = Some(8);
+    /// preferences.ambient.calibration = (120, 3900);
+    /// preferences.ambient.grow_cutoff = Some(75);
+    ///
+    /// let bytes = preferences.to_bytes();
+    /// assert!(Preferences::from_bytes(&bytes).ok() == Some(preferences));
+    /// ```
+    pub fn to_bytes(&self) -> [u8; PREFERENCES_BYTES] {
+        let mut bytes = [0; PREFERENCES_BYTES];
+        bytes[VERSION_BYTE] = PREFERENCES_VERSION;
+        bytes[1] = self.temperature.0;
+        bytes[2] = self.temperature.1;
+        bytes[3] = self.humidity.0;
+        bytes[4] = self.humidity.1;
+        bytes[5] = self.date.second;
+        bytes[6] = self.date.minute;
+        bytes[7] = self.date.hour;
+        bytes[8] = self.date.day;
+        bytes[9] = self.date.month;
+        bytes[10..12].copy_from_slice(&self.date.year.to_le_bytes());
+        bytes[12] = self.watering.len() as u8;
+        for (i, window) in self.watering.iter().enumerate() {
+            let start = 13 + i * WATERING_WINDOW_BYTES;
+            bytes[start] = window.start.0;
+            bytes[start + 1] = window.start.1;
+            bytes[start + 2] = window.end.0;
+            bytes[start + 3] = window.end.1;
+            bytes[start + 4] = window.days;
+        }
+        bytes[CLOCK_FORMAT_BYTE] = match self.clock_format {
+            ClockFormat::TwentyFourHour => 0,
+            ClockFormat::TwelveHour => 1,
+        };
+        bytes[CLOCK_FORMAT_BYTE + 1..CLOCK_FORMAT_BYTE + 5]
+            .copy_from_slice(&self.sensor_interval_ms.to_le_bytes());
+        bytes[CLOCK_FORMAT_BYTE + 5] = self.watering_duration_minutes;
+        bytes[DST_BYTE] = self.dst_enabled as u8;
+        bytes[DST_BYTE + 1] = self.dst_rule.start_month;
+        bytes[DST_BYTE + 2] = self.dst_rule.start_week;
+        bytes[DST_BYTE + 3] = self.dst_rule.start_hour;
+        bytes[DST_BYTE + 4] = self.dst_rule.end_month;
+        bytes[DST_BYTE + 5] = self.dst_rule.end_week;
+        bytes[DST_BYTE + 6] = self.dst_rule.end_hour;
+        bytes[DST_BYTE + 7] = self.frost_threshold;
+        bytes[DST_BYTE + 8..DST_BYTE + 10].copy_from_slice(&self.temp_offset.to_le_bytes());
+        if let Some((dry, wet)) = self.soil_calibration {
+            bytes[SOIL_BYTE] = 1;
+            bytes[SOIL_BYTE + 1..SOIL_BYTE + 3].copy_from_slice(&dry.to_le_bytes());
+            bytes[SOIL_BYTE + 3..SOIL_BYTE + 5].copy_from_slice(&wet.to_le_bytes());
+        }
+        bytes[SOIL_BYTE + 5] = self.soil_moisture.0;
+        bytes[SOIL_BYTE + 6] = self.soil_moisture.1;
+        if let Some(window) = self.light {
+            bytes[LIGHT_BYTE] = 1;
+            bytes[LIGHT_BYTE + 1] = window.start.0;
+            bytes[LIGHT_BYTE + 2] = window.start.1;
+            bytes[LIGHT_BYTE + 3] = window.end.0;
+            bytes[LIGHT_BYTE + 4] = window.end.1;
         }
+        bytes[SMOOTHING_BYTE] = self.smoothing;
+        bytes[PRESSURE_UNIT_BYTE] = match self.pressure_unit {
+            PressureUnit::Hectopascals => 0,
+            PressureUnit::InchesOfMercury => 1,
+        };
+        bytes[DATE_FORMAT_BYTE] = match self.date_format {
+            DateFormat::Dmy => 0,
+            DateFormat::Mdy => 1,
+            DateFormat::Iso => 2,
+        };
+        bytes[VPD_BYTE..VPD_BYTE + 2].copy_from_slice(&self.vpd.0.to_le_bytes());
+        bytes[VPD_BYTE + 2..VPD_BYTE + 4].copy_from_slice(&self.vpd.1.to_le_bytes());
+        bytes[RATE_LIMIT_BYTE] = self.temperature_rate_limit;
+        bytes[HUMIDITY_OFFSET_BYTE] = self.humidity_offset as u8;
+        bytes[GAS_WARMUP_BYTE] = self.gas_warmup_minutes;
+        bytes[MARGINS_BYTE] = self.margins.vent;
+        bytes[MARGINS_BYTE + 1] = self.margins.sprinklers;
+        bytes[MARGINS_BYTE + 2] = self.margins.heater;
+        bytes[BATTERY_BYTE..BATTERY_BYTE + 2].copy_from_slice(&self.battery.divider.to_le_bytes());
+        bytes[BATTERY_BYTE + 2..BATTERY_BYTE + 4]
+            .copy_from_slice(&self.battery.low_voltage.to_le_bytes());
+        bytes[BATTERY_BYTE + 4] = self.battery.power_saving as u8;
+        bytes[AIR_VENT_BYTE] = self.air_venting.unwrap_or(0);
+        bytes[ALARM_BYTE] = self.temperature_alarm.0;
+        bytes[ALARM_BYTE + 1] = self.temperature_alarm.1;
+        bytes[ALARM_BYTE + 2] = self.humidity_alarm.0;
+        bytes[ALARM_BYTE + 3] = self.humidity_alarm.1;
+        bytes[AUTO_CYCLE_BYTE] = self.auto_cycle_seconds.unwrap_or(0);
+        bytes[AMBIENT_BYTE..AMBIENT_BYTE + 2]
+            .copy_from_slice(&self.ambient.calibration.0.to_le_bytes());
+        bytes[AMBIENT_BYTE + 2..AMBIENT_BYTE + 4]
+            .copy_from_slice(&self.ambient.calibration.1.to_le_bytes());
+        bytes[AMBIENT_BYTE + 4] = self.ambient.backlight_dark.unwrap_or(0);
+        bytes[AMBIENT_BYTE + 5] = self.ambient.grow_cutoff.unwrap_or(0);
+        bytes
     }
-}
 
-impl Preferences {
-    /// Increments timer by 1 second
-    pub fn tick_time(&mut self) {
-        self.date.0 += 1;
-
-        // Check for rollovers
-        // Sec
-        if self.date.0 >= 60 {
-            self.date.1 += self.date.0 / 60;
-            self.date.0 %= 60;
-        } else {
-            return;
+    /// Deserializes Preferences from bytes made by [Preferences::to_bytes()]
+    ///
+    /// - param bytes: the serialized Preferences
+    ///
+    /// returns the Preferences, or a [ParseError] if the bytes are invalid
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::datetime::{ClockFormat, DateFormat, DateTime};
+    /// use gem_rs::preferences::{
+    ///     DstRule, LightWindow, ParseError, Preferences, WateringWindow, MAX_WATERING_DURATION,
+    ///     SENSOR_INTERVALS,
+    /// };
+    /// use gem_rs::sensors::PressureUnit;
+    ///
+    /// let mut bytes = Preferences::default().to_bytes();
+    /// assert!(Preferences::from_bytes(&bytes[..10]) == Err(ParseError::Length));
+    ///
+    /// // A corrupt temperature offset of i16::MIN is rejected rather than overflowing
+    /// bytes[47] = 0x00;
+    /// bytes[48] = 0x80;
+    /// assert!(Preferences::from_bytes(&bytes) == Err(ParseError::InvalidValue));
+    ///
+    /// // Bytes from another layout, or an erased EEPROM, are not misread
+    /// let mut bytes = Preferences::default().to_bytes();
+    /// bytes[0] = 0xFF;
+    /// assert!(Preferences::from_bytes(&bytes) == Err(ParseError::Version));
+    ///
+    /// // Any normalized Preferences survive a round trip. A seeded xorshift makes them up
+    /// let mut state = 0x2545_F491_u32;
+    /// let mut next = move || {
+    ///     state ^= state << 13;
+    ///     state ^= state >> 17;
+    ///     state ^= state << 5;
+    ///     state
+    /// };
+    /// for _ in 0..1000 {
+    ///     let mut preferences = Preferences::default();
+    ///     preferences.temperature = (next() as u8, next() as u8);
+    ///     preferences.humidity = (next() as u8, next() as u8);
+    ///     let (year, month, day) = (next() as u16, next() as u8, next() as u8);
+    ///     preferences.date = DateTime::new(year, month, day, next() as u8, next() as u8, next() as u8);
+    ///     for _ in 0..next() % 5 {
+    ///         let window = WateringWindow {
+    ///             start: (next() as u8, next() as u8),
+    ///             end: (next() as u8, next() as u8),
+    ///             days: next() as u8,
+    ///         };
+    ///         preferences.add_watering_window(window).ok();
+    ///     }
+    ///     if next() % 2 == 0 {
+    ///         preferences.clock_format = ClockFormat::TwelveHour;
+    ///     }
+    ///     preferences.sensor_interval_ms = SENSOR_INTERVALS[next() as usize % SENSOR_INTERVALS.len()];
+    ///     preferences.watering_duration_minutes = (next() % (MAX_WATERING_DURATION as u32 + 1)) as u8;
+    ///     preferences.dst_enabled = next() % 2 == 0;
+    ///     preferences.dst_rule = DstRule {
+    ///         start_month: (next() % 12) as u8 + 1,
+    ///         start_week: (next() % 5) as u8 + 1,
+    ///         start_hour: (next() % 24) as u8,
+    ///         end_month: (next() % 12) as u8 + 1,
+    ///         end_week: (next() % 5) as u8 + 1,
+    ///         end_hour: (next() % 24) as u8,
+    ///     };
+    ///     preferences.frost_threshold = next() as u8;
+    ///     preferences.temp_offset = next() as i16;
+    ///     preferences.soil_calibration = (next() % 2 == 0).then(|| (next() as u16, next() as u16));
+    ///     preferences.soil_moisture = (next() as u8, next() as u8);
+    ///     preferences.light = (next() % 2 == 0).then(|| LightWindow {
+    ///         start: (next() as u8, next() as u8),
+    ///         end: (next() as u8, next() as u8),
+    ///     });
+    ///     preferences.smoothing = next() as u8;
+    ///     if next() % 2 == 0 {
+    ///         preferences.pressure_unit = PressureUnit::InchesOfMercury;
+    ///     }
+    ///     preferences.date_format = [DateFormat::Dmy, DateFormat::Mdy, DateFormat::Iso][next() as usize % 3];
+    ///     preferences.vpd = (next() as u16, next() as u16);
+    ///     preferences.temperature_rate_limit = next() as u8;
+    ///     preferences.humidity_offset = next() as i8;
+    ///     preferences.gas_warmup_minutes = next() as u8;
+    ///     preferences.margins.vent = next() as u8;
+    ///     preferences.margins.sprinklers = next() as u8;
+    ///     preferences.margins.heater = next() as u8;
+    ///     preferences.battery.divider = next() as u16;
+    ///     preferences.battery.low_voltage = next() as u16;
+    ///     preferences.battery.power_saving = next() % 2 == 0;
+    ///     preferences.air_venting = (next() % 2 == 0).then(|| next() as u8);
+    ///     preferences.temperature_alarm = (next() as u8, next() as u8);
+    ///     preferences.humidity_alarm = (next() as u8, next() as u8);
+    ///     preferences.auto_cycle_seconds = (next() % 2 == 0).then(|| next() as u8);
+    ///     preferences.ambient.calibration = (next() as u16, next() as u16);
+    ///     preferences.ambient.backlight_dark = (next() % 2 == 0).then(|| next() as u8);
+    ///     preferences.ambient.grow_cutoff = (next() % 2 == 0).then(|| next() as u8);
+    ///     preferences.normalize();
+    ///
+    ///     let bytes = preferences.to_bytes();
+    ///     assert!(Preferences::from_bytes(&bytes).ok() == Some(preferences));
+    /// }
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Preferences, ParseError> {
+        if bytes.len() < PREFERENCES_BYTES {
+            return Err(ParseError::Length);
         }
-        // Min
-        if self.date.1 >= 60 {
-            self.date.2 += self.date.1 / 60;
-            self.date.1 %= 60;
-        } else {
-            return;
+        if bytes[VERSION_BYTE] != PREFERENCES_VERSION {
+            return Err(ParseError::Version);
         }
-        // Hr
-        if self.date.2 >= 24 {
-            self.date.3 += self.date.2 / 24;
-            self.date.2 %= 24;
-        } else {
-            return;
+
+        let window_count = bytes[12] as usize;
+        if window_count > MAX_WATERING_WINDOWS {
+            return Err(ParseError::InvalidValue);
+        }
+        let mut watering = Vec::new();
+        for i in 0..window_count {
+            let start = 13 + i * WATERING_WINDOW_BYTES;
+            watering
+                .push(WateringWindow {
+                    start: (bytes[start], bytes[start + 1]),
+                    end: (bytes[start + 2], bytes[start + 3]),
+                    days: bytes[start + 4],
+                })
+                .ok();
         }
 
-        // Handle month and day rollovers
-        loop {
-            let days_in_month = self.get_days_in_month();
+        let clock_format = match bytes[CLOCK_FORMAT_BYTE] {
+            0 => ClockFormat::TwentyFourHour,
+            1 => ClockFormat::TwelveHour,
+            _ => return Err(ParseError::InvalidValue),
+        };
 
-            if self.date.3 > days_in_month {
-                self.date.3 -= days_in_month;
-                self.date.4 += 1;
-            } else {
-                break;
-            }
+        let sensor_interval_ms = u32::from_le_bytes([
+            bytes[CLOCK_FORMAT_BYTE + 1],
+            bytes[CLOCK_FORMAT_BYTE + 2],
+            bytes[CLOCK_FORMAT_BYTE + 3],
+            bytes[CLOCK_FORMAT_BYTE + 4],
+        ]);
+        if sensor_interval_ms == 0 {
+            return Err(ParseError::InvalidValue);
+        }
 
-            if self.date.4 > 12 {
-                self.date.4 = 1;
-                self.date.5 += 1;
-            }
+        let dst_enabled = match bytes[DST_BYTE] {
+            0 => false,
+            1 => true,
+            _ => return Err(ParseError::InvalidValue),
+        };
+        let power_saving = match bytes[BATTERY_BYTE + 4] {
+            0 => false,
+            1 => true,
+            _ => return Err(ParseError::InvalidValue),
+        };
+        let dst_rule = DstRule {
+            start_month: bytes[DST_BYTE + 1],
+            start_week: bytes[DST_BYTE + 2],
+            start_hour: bytes[DST_BYTE + 3],
+            end_month: bytes[DST_BYTE + 4],
+            end_week: bytes[DST_BYTE + 5],
+            end_hour: bytes[DST_BYTE + 6],
+        };
+        let valid_transition = |month: u8, week: u8, hour: u8| {
+            (1..=12).contains(&month) && (1..=5).contains(&week) && hour < 24
+        };
+        if !valid_transition(
+            dst_rule.start_month,
+            dst_rule.start_week,
+            dst_rule.start_hour,
+        ) || !valid_transition(dst_rule.end_month, dst_rule.end_week, dst_rule.end_hour)
+        {
+            return Err(ParseError::InvalidValue);
         }
 
-        // Update the date tuple
-        self.date = (
-            self.date.0,
-            self.date.1,
-            self.date.2,
-            self.date.3,
-            self.date.4,
-            self.date.5,
-        );
+        let temp_offset = i16::from_le_bytes([bytes[DST_BYTE + 8], bytes[DST_BYTE + 9]]);
+        if temp_offset.unsigned_abs() > MAX_TEMP_OFFSET as u16 {
+            return Err(ParseError::InvalidValue);
+        }
+
+        let humidity_offset = bytes[HUMIDITY_OFFSET_BYTE] as i8;
+        if humidity_offset.unsigned_abs() > MAX_HUMIDITY_OFFSET as u8 {
+            return Err(ParseError::InvalidValue);
+        }
+
+        let soil_calibration = match bytes[SOIL_BYTE] {
+            0 => None,
+            1 => Some((
+                u16::from_le_bytes([bytes[SOIL_BYTE + 1], bytes[SOIL_BYTE + 2]]),
+                u16::from_le_bytes([bytes[SOIL_BYTE + 3], bytes[SOIL_BYTE + 4]]),
+            )),
+            _ => return Err(ParseError::InvalidValue),
+        };
+
+        let light = match bytes[LIGHT_BYTE] {
+            0 => None,
+            1 => Some(LightWindow {
+                start: (bytes[LIGHT_BYTE + 1], bytes[LIGHT_BYTE + 2]),
+                end: (bytes[LIGHT_BYTE + 3], bytes[LIGHT_BYTE + 4]),
+            }),
+            _ => return Err(ParseError::InvalidValue),
+        };
+
+        let smoothing = bytes[SMOOTHING_BYTE];
+        if smoothing == 0 || smoothing > MAX_SMOOTHING {
+            return Err(ParseError::InvalidValue);
+        }
+
+        let pressure_unit = match bytes[PRESSURE_UNIT_BYTE] {
+            0 => PressureUnit::Hectopascals,
+            1 => PressureUnit::InchesOfMercury,
+            _ => return Err(ParseError::InvalidValue),
+        };
+
+        let date_format = match bytes[DATE_FORMAT_BYTE] {
+            0 => DateFormat::Dmy,
+            1 => DateFormat::Mdy,
+            2 => DateFormat::Iso,
+            _ => return Err(ParseError::InvalidValue),
+        };
+
+        let mut preferences = Preferences {
+            temperature: (bytes[1], bytes[2]),
+            humidity: (bytes[3], bytes[4]),
+            date: DateTime {
+                second: bytes[5],
+                minute: bytes[6],
+                hour: bytes[7],
+                day: bytes[8],
+                month: bytes[9],
+                year: u16::from_le_bytes([bytes[10], bytes[11]]),
+            },
+            watering,
+            clock_format,
+            sensor_interval_ms,
+            watering_duration_minutes: bytes[CLOCK_FORMAT_BYTE + 5],
+            dst_enabled,
+            dst_rule,
+            frost_threshold: bytes[DST_BYTE + 7],
+            temp_offset,
+            soil_calibration,
+            soil_moisture: (bytes[SOIL_BYTE + 5], bytes[SOIL_BYTE + 6]),
+            light,
+            smoothing,
+            pressure_unit,
+            date_format,
+            vpd: (
+                u16::from_le_bytes([bytes[VPD_BYTE], bytes[VPD_BYTE + 1]]),
+                u16::from_le_bytes([bytes[VPD_BYTE + 2], bytes[VPD_BYTE + 3]]),
+            ),
+            temperature_rate_limit: bytes[RATE_LIMIT_BYTE],
+            humidity_offset,
+            gas_warmup_minutes: bytes[GAS_WARMUP_BYTE],
+            margins: HysteresisMargins {
+                vent: bytes[MARGINS_BYTE],
+                sprinklers: bytes[MARGINS_BYTE + 1],
+                heater: bytes[MARGINS_BYTE + 2],
+            },
+            battery: BatterySettings {
+                divider: u16::from_le_bytes([bytes[BATTERY_BYTE], bytes[BATTERY_BYTE + 1]]),
+                low_voltage: u16::from_le_bytes([bytes[BATTERY_BYTE + 2], bytes[BATTERY_BYTE + 3]]),
+                power_saving,
+            },
+            air_venting: match bytes[AIR_VENT_BYTE] {
+                0 => None,
+                percent => Some(percent),
+            },
+            temperature_alarm: (bytes[ALARM_BYTE], bytes[ALARM_BYTE + 1]),
+            humidity_alarm: (bytes[ALARM_BYTE + 2], bytes[ALARM_BYTE + 3]),
+            auto_cycle_seconds: match bytes[AUTO_CYCLE_BYTE] {
+                0 => None,
+                seconds => Some(seconds),
+            },
+            ambient: AmbientSettings {
+                calibration: (
+                    u16::from_le_bytes([bytes[AMBIENT_BYTE], bytes[AMBIENT_BYTE + 1]]),
+                    u16::from_le_bytes([bytes[AMBIENT_BYTE + 2], bytes[AMBIENT_BYTE + 3]]),
+                ),
+                backlight_dark: match bytes[AMBIENT_BYTE + 4] {
+                    0 => None,
+                    percent => Some(percent),
+                },
+                grow_cutoff: match bytes[AMBIENT_BYTE + 5] {
+                    0 => None,
+                    percent => Some(percent),
+                },
+            },
+            saved: 0,
+        };
+        // These are the saved settings
+        preferences.mark_clean();
+        Ok(preferences)
     }
 
-    /// Gets the date in the `HH:MM:SS DD/MM/YYYY` format
-    /// Since the indexes start at 0 and months and days start at 1,
-    /// the function ensures that 1 is added
+    /// Checks if any setting changed since the last save, so unchanged settings are not written again.
+    /// The date is not a setting, since it changes every second
     ///
-    /// returns: `(HH:MM:SS, DD/MM/YYYY)`
-    pub fn get_date_formatted(&mut self) -> (String<8>, String<10>) {
-        // Format the date as a string
-        let mut val1: String<8> = String::new();
-        let mut val2: String<10> = String::new();
-        // Format time
-        uwrite!(
-            &mut val1,
-            "{}:{}:{}",
-            Self::pad_number(self.date.2).as_str(),
-            Self::pad_number(self.date.1).as_str(),
-            Self::pad_number(self.date.0).as_str(),
-        )
-        .unwrap();
+    /// returns true if the settings need saving
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    ///
+    /// let mut preferences = Preferences::default();
+    /// assert!(!preferences.is_dirty());
+    ///
+    /// preferences.date.tick(); // Time passing is not a change
+    /// assert!(!preferences.is_dirty());
+    ///
+    /// preferences.temperature.1 += 1;
+    /// assert!(preferences.is_dirty());
+    /// preferences.temperature.1 -= 1; // Changing it back leaves nothing to save
+    /// assert!(!preferences.is_dirty());
+    ///
+    /// preferences.set_default_watering_time();
+    /// assert!(preferences.is_dirty());
+    /// let saved = preferences.to_bytes();
+    /// preferences.mark_clean();
+    /// assert!(!preferences.is_dirty());
+    ///
+    /// // Loaded settings are already saved
+    /// assert!(!Preferences::from_bytes(&saved).unwrap().is_dirty());
+    /// ```
+    pub fn is_dirty(&self) -> bool {
+        self.checksum() != self.saved
+    }
 
-        // Format date
-        uwrite!(
-            &mut val2,
-            "{}/{}/{}",
-            Self::pad_number(self.date.3).as_str(),
-            Self::pad_number(self.date.4).as_str(),
-            self.date.5
-        )
-        .unwrap();
+    /// Records that the settings were saved
+    pub fn mark_clean(&mut self) {
+        self.saved = self.checksum();
+    }
+
+    /// Hashes every setting except the date with 32-bit FNV-1a
+    ///
+    /// returns the checksum
+    fn checksum(&self) -> u32 {
+        let bytes = self.to_bytes();
+        bytes[..DATE_BYTES.start]
+            .iter()
+            .chain(&bytes[DATE_BYTES.end..])
+            .fold(0x811C_9DC5, |hash, byte| {
+                (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+            })
+    }
 
-        (val1, val2)
+    /// Adds a watering window from `00:00 to 01:00`
+    ///
+    /// returns the index of the new window, or None if there is no room left
+    pub fn set_default_watering_time(&mut self) -> Option<usize> {
+        self.add_watering_window(WateringWindow::default()).ok()
     }
 
-    /// Pads a number with a zero before it if < 10
+    /// Gets the heater's switch points for a [Hysteresis::falling()]:
+    /// on below the lower temperature bound, off [HysteresisMargins::heater] degrees above it
     ///
-    /// **NOTE: Only supports values <100**
+    /// returns the switch points in Fahrenheit: Low, High
+    pub fn heater_points(&self) -> (u8, u8) {
+        let low = self.temperature.0;
+        (low, low.saturating_add(self.margins.heater))
+    }
+
+    /// Gets the roof vent's switch points for a [Hysteresis::rising()]:
+    /// open above the upper temperature bound, closed [HysteresisMargins::vent] degrees below it
     ///
-    /// - param num: number to be padded
+    /// returns the switch points in Fahrenheit: Low, High
+    pub fn vent_points(&self) -> (u8, u8) {
+        let high = self.temperature.1;
+        (high.saturating_sub(self.margins.vent), high)
+    }
+
+    /// Gets the frost alarm's switch points for a [Hysteresis::falling()]:
+    /// on below the frost threshold, off [FROST_MARGIN] degrees above it
     ///
-    /// returns: [String] with formatted value
-    fn pad_number(num: u8) -> String<2> {
-        let mut padded = String::new();
-        if num < 10 {
-            uwrite!(padded, "0{}", num).unwrap();
-        } else {
-            uwrite!(padded, "{}", num).unwrap();
+    /// returns the switch points in Fahrenheit: Low, High
+    pub fn frost_points(&self) -> (u8, u8) {
+        let low = self.frost_threshold;
+        (low, low.saturating_add(FROST_MARGIN))
+    }
+
+    /// Gets the humidifying sprinklers' switch points for a [Hysteresis::falling()]:
+    /// on [HysteresisMargins::sprinklers] below the lower humidity bound, off the same amount above it
+    ///
+    /// returns the switch points in relative humidity percent: Low, High
+    pub fn humidity_points(&self) -> (u8, u8) {
+        let bound = self.humidity.0;
+        let margin = self.margins.sprinklers;
+        (bound.saturating_sub(margin), bound.saturating_add(margin))
+    }
+
+    /// Gets the off-schedule soil watering's switch points for a [Hysteresis::falling()]:
+    /// on below the lower soil moisture bound, off at the upper bound
+    ///
+    /// returns the switch points in soil moisture percent: Low, High
+    pub fn soil_points(&self) -> (u8, u8) {
+        self.soil_moisture
+    }
+}
+
+/// A Schmitt trigger: an on/off output with separate switch points, so a value hovering
+/// around a single threshold does not make the output chatter
+///
+/// - **points**: The switch points: Low, High
+/// - **rising**: Whether the output turns on when the value is high, rather than low
+/// - **on**: The current latched state of the output
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::preferences::Hysteresis;
+///
+/// // On below 60, off from 62
+/// let mut heater = Hysteresis::falling((60, 62));
+/// let sweep = [63, 61, 60, 59, 60, 61, 62, 61, 60, 59];
+/// let states = sweep.map(|temp| heater.update(temp));
+/// assert_eq!(
+///     states,
+///     [false, false, false, true, true, true, false, false, false, true]
+/// );
+///
+/// // On above 82, off from 80
+/// let mut vent = Hysteresis::rising((80, 82));
+/// let sweep = [79, 81, 82, 83, 82, 81, 80, 81, 83];
+/// let states = sweep.map(|temp| vent.update(temp));
+/// assert_eq!(
+///     states,
+///     [false, false, false, true, true, true, false, false, true]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hysteresis<T> {
+    points: (T, T),
+    rising: bool,
+    on: bool,
+}
+
+impl<T: PartialOrd + Copy> Hysteresis<T> {
+    /// Creates a Hysteresis that turns on below the low point and off at or above the high point,
+    /// e.g. for a heater. It starts off
+    ///
+    /// - param points: The switch points: Low, High
+    ///
+    /// returns a new instance of Hysteresis
+    pub fn falling(points: (T, T)) -> Hysteresis<T> {
+        Self {
+            points,
+            rising: false,
+            on: false,
         }
-        padded
     }
 
-    /// Calculates if it is leap year
+    /// Creates a Hysteresis that turns on above the high point and off at or below the low point,
+    /// e.g. for a vent. It starts off
     ///
-    /// - param year: The current year
+    /// - param points: The switch points: Low, High
     ///
-    /// returns if the year is leap year
-    fn is_leap_year(year: u16) -> bool {
-        year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+    /// returns a new instance of Hysteresis
+    pub fn rising(points: (T, T)) -> Hysteresis<T> {
+        Self {
+            points,
+            rising: true,
+            on: false,
+        }
     }
 
-    /// Gets the next index for the current day depending on the month and leap year
+    /// Sets the latched state, e.g. to continue from an output's current state
+    ///
+    /// - param on: Whether the output is on
     ///
-    /// - param increment: If the values are incrementing (not decrementing)
+    /// returns the Hysteresis
+    pub fn with_state(mut self, on: bool) -> Hysteresis<T> {
+        self.on = on;
+        self
+    }
+
+    /// Moves the switch points, keeping the latched state
     ///
-    /// returns the next day's index
-    pub fn change_days(&self, increment: bool) -> u8 {
-        let days_in_month: u8 = self.get_days_in_month();
-        inclusive_iterator(self.date.3, 1, days_in_month, increment)
+    /// - param points: The switch points: Low, High
+    pub fn set_points(&mut self, points: (T, T)) {
+        self.points = points;
+    }
+
+    /// Turns the output off until the value next crosses the switch point
+    pub fn reset(&mut self) {
+        self.on = false;
     }
 
-    /// Gets the amount of days in the current month
+    /// Feeds a new value in. Between the switch points, the output keeps its state
     ///
-    /// returns the amount of days in the month
-    pub fn get_days_in_month(&self) -> u8 {
-        match self.date.4 {
-            2 => {
-                // Feb
-                if Self::is_leap_year(self.date.5) {
-                    29
-                } else {
-                    28
-                }
+    /// - param value: The current value
+    ///
+    /// returns whether the output is on
+    pub fn update(&mut self, value: T) -> bool {
+        let (low, high) = self.points;
+        if self.rising {
+            if value > high {
+                self.on = true;
+            } else if value <= low {
+                self.on = false;
             }
-            4 | 6 | 9 | 11 => 30, // Apr, Jun, Sep, Nov
-            _ => 31,              // Other months
+        } else if value < low {
+            self.on = true;
+        } else if value >= high {
+            self.on = false;
         }
+        self.on
     }
 
-    /// Checks if it is time to enable the sprinklers
+    /// Checks the latched state without feeding a new value in
     ///
-    /// returns if the current time is within the watering time.
-    /// Returns false if there is no watering time set
-    pub fn is_watering_time(&self) -> bool {
-        if let Some(watering_time) = self.watering {
-            let current_minutes: u16 = (self.date.2 * 60 + self.date.1) as u16; // Convert current time to total minutes
-            let start_minutes: u16 = (watering_time.1 * 60 + watering_time.0) as u16; // Convert start time to total minutes
-            let end_minutes: u16 = (watering_time.3 * 60 + watering_time.2) as u16; // Convert end time to total minutes
+    /// returns whether the output is on
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+}
 
-            current_minutes >= start_minutes && current_minutes <= end_minutes
-        } else {
-            false
+/// Errors that can occur while building [Preferences]
+///
+/// - **InvalidRange**: A humidity bound is above 100%
+/// - **InvalidDate**: The date does not exist
+/// - **InvalidTime**: A watering time is not a valid time of day
+/// - **TooManyWindows**: More than [MAX_WATERING_WINDOWS] watering windows were added
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BuildError {
+    InvalidRange,
+    InvalidDate,
+    InvalidTime,
+    TooManyWindows,
+}
+
+/// Builds [Preferences] starting from [Preferences::default()], validating every value.
+/// The first invalid value is reported by [PreferencesBuilder::build()]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::DateTime;
+/// use gem_rs::preferences::{BuildError, PreferencesBuilder};
+///
+/// let preferences = PreferencesBuilder::new()
+///     .temperature_range(85, 65) // Reversed ranges are swapped
+///     .humidity_range(50, 70)
+///     .date(2024, 2, 29, 13, 30, 0) // 13:30:00 Feb 29 2024
+///     .watering((6, 0), (6, 30))    // 06:00 - 06:30
+///     .build()
+///     .unwrap();
+/// assert_eq!(preferences.temperature, (65, 85));
+/// assert_eq!(preferences.date, DateTime::new(2024, 2, 29, 13, 30, 0));
+///
+/// let invalid = PreferencesBuilder::new().date(2023, 2, 29, 0, 0, 0).build();
+/// assert!(invalid.err() == Some(BuildError::InvalidDate)); // 2023 is not a leap year
+/// ```
+pub struct PreferencesBuilder {
+    preferences: Preferences,
+    error: Option<BuildError>,
+}
+
+impl Default for PreferencesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PreferencesBuilder {
+    /// Creates a new PreferencesBuilder with the default [Preferences]
+    ///
+    /// returns a new instance of PreferencesBuilder
+    pub fn new() -> PreferencesBuilder {
+        Self {
+            preferences: Preferences::default(),
+            error: None,
         }
     }
 
-    /// Formats the watering time: `HH:MM - HH:MM`
+    /// Sets the acceptable temperature range. The bounds are swapped if reversed
     ///
-    /// Returns a [String] of length 16 containing the formatted times
-    pub fn format_watering_time(&self) -> String<16> {
-        let mut str: String<16> = String::new();
-        if let Some(watering_time) = self.watering {
-            uwrite!(
-                str,
-                "{}:{} - {}:{}",
-                Self::pad_number(watering_time.1).as_str(),
-                Self::pad_number(watering_time.0).as_str(),
-                Self::pad_number(watering_time.3).as_str(),
-                Self::pad_number(watering_time.2).as_str(),
-            )
-            .unwrap();
-        } else {
-            uwrite!(str, "None").unwrap();
+    /// - param low: The lower bound in Fahrenheit
+    /// - param high: The upper bound in Fahrenheit
+    pub fn temperature_range(mut self, low: u8, high: u8) -> PreferencesBuilder {
+        self.preferences.temperature = (low.min(high), low.max(high));
+        self
+    }
+
+    /// Sets the acceptable relative humidity range. The bounds are swapped if reversed
+    ///
+    /// - param low: The lower bound percentage
+    /// - param high: The upper bound percentage
+    pub fn humidity_range(mut self, low: u8, high: u8) -> PreferencesBuilder {
+        if low > 100 || high > 100 {
+            return self.fail(BuildError::InvalidRange);
         }
-        str
+        self.preferences.humidity = (low.min(high), low.max(high));
+        self
+    }
+
+    /// Sets the current date and time
+    ///
+    /// - param year: The year
+    /// - param month: The month (1-12)
+    /// - param day: The day (1-31)
+    /// - param hour: The hour (0-23)
+    /// - param minute: The minute (0-59)
+    /// - param second: The second (0-59)
+    pub fn date(
+        mut self,
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> PreferencesBuilder {
+        let date = DateTime::new(year, month, day, hour, minute, second);
+        if !date.is_valid() {
+            return self.fail(BuildError::InvalidDate);
+        }
+        self.preferences.date = date;
+        self
+    }
+
+    /// Adds a watering window. The times are swapped if reversed
+    ///
+    /// - param start: The time watering starts: Hour, Min
+    /// - param end: The time watering ends: Hour, Min
+    pub fn watering(mut self, start: (u8, u8), end: (u8, u8)) -> PreferencesBuilder {
+        if start.0 > 23 || start.1 > 59 || end.0 > 23 || end.1 > 59 {
+            return self.fail(BuildError::InvalidTime);
+        }
+        let (start, end) = (start.min(end), start.max(end));
+        let window = WateringWindow {
+            start: (start.1, start.0),
+            end: (end.1, end.0),
+            days: EVERY_DAY,
+        };
+        if self.preferences.add_watering_window(window).is_err() {
+            return self.fail(BuildError::TooManyWindows);
+        }
+        self
+    }
+
+    /// Records the first error to report on [PreferencesBuilder::build()]
+    fn fail(mut self, error: BuildError) -> PreferencesBuilder {
+        self.error.get_or_insert(error);
+        self
     }
 
-    /// Sets the watering time from `00:00 to 01:00`
-    pub fn set_default_watering_time(&mut self) {
-        self.watering = Some((0, 0, 0, 1));
+    /// Finishes building
+    ///
+    /// returns the [Preferences], or the first [BuildError] that occurred
+    pub fn build(self) -> Result<Preferences, BuildError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.preferences),
+        }
     }
 }
 
@@ -246,3 +2700,306 @@ pub fn inclusive_iterator(current_val: u8, min_val: u8, max_val: u8, increment:
         current_val - 1
     }
 }
+
+/// Decides whether the heater should be running
+///
+/// The heater turns on below the lower temperature bound and stays on
+/// until the temperature is [HysteresisMargins::heater] degrees above it
+///
+/// - param temp: The current temperature in Fahrenheit
+/// - param heating: Whether the heater is currently running
+/// - param preferences: [Preferences] instance
+///
+/// returns if the heater should be running
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{should_heat, Preferences};
+///
+///  let preferences = Preferences::default(); // Lower bound is 60F
+///  assert!(should_heat(59, false, &preferences));  // Too cold, turn on
+///  assert!(should_heat(61, true, &preferences));   // Still within the deadband
+///  assert!(!should_heat(62, true, &preferences));  // Warm enough, turn off
+/// ```
+pub fn should_heat(temp: u8, heating: bool, preferences: &Preferences) -> bool {
+    Hysteresis::falling(preferences.heater_points())
+        .with_state(heating)
+        .update(temp)
+}
+
+/// Decides whether the frost alarm should be sounding.
+/// This is separate from the heater, so a failed heater is still noticed
+///
+/// The alarm starts below [Preferences::frost_threshold] and clears
+/// once the temperature is [FROST_MARGIN] degrees above it
+///
+/// - param temp: The current temperature in Fahrenheit
+/// - param alarming: Whether the frost alarm is currently sounding
+/// - param preferences: [Preferences] instance
+///
+/// returns if the frost alarm should be sounding
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{frost_alarm, Preferences};
+///
+///  let preferences = Preferences::default(); // Frost threshold is 36F
+///  assert!(!frost_alarm(36, false, &preferences)); // Not below the threshold yet
+///  assert!(frost_alarm(35, false, &preferences));  // Freezing, sound the alarm
+///  assert!(frost_alarm(37, true, &preferences));   // Still within the margin
+///  assert!(!frost_alarm(38, true, &preferences));  // Recovered, clear the alarm
+/// ```
+pub fn frost_alarm(temp: u8, alarming: bool, preferences: &Preferences) -> bool {
+    Hysteresis::falling(preferences.frost_points())
+        .with_state(alarming)
+        .update(temp)
+}
+
+/// Decides how fast the cooling fan should run.
+/// The duty cycle rises linearly from 0% at the upper temperature bound
+/// to 100% at [FAN_SPAN] degrees above it
+///
+/// - param temp: The current temperature in Fahrenheit
+/// - param preferences: [Preferences] instance
+///
+/// returns the fan's duty cycle from 0 to 100 percent
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{fan_duty, Preferences};
+///
+///  let preferences = Preferences::default(); // Upper bound is 80F
+///  assert_eq!(fan_duty(75, &preferences), 0);   // Cool enough, fan off
+///  assert_eq!(fan_duty(80, &preferences), 0);   // At the bound, fan off
+///  assert_eq!(fan_duty(85, &preferences), 50);  // Halfway through the span
+///  assert_eq!(fan_duty(90, &preferences), 100); // Full speed
+///  assert_eq!(fan_duty(120, &preferences), 100); // Clamped
+/// ```
+pub fn fan_duty(temp: u8, preferences: &Preferences) -> u8 {
+    let excess = temp.saturating_sub(preferences.temperature.1) as u16;
+    (excess * 100 / FAN_SPAN as u16).min(100) as u8
+}
+
+/// Decides how far the roof vent should be open.
+/// The angle rises linearly from closed at the upper temperature bound
+/// to [MAX_VENT_ANGLE] at [VENT_SPAN] degrees above it.
+/// While the temperature is within the range and rising too fast, the vent opens to at least
+/// [RATE_VENT_ANGLE] early, before the upper bound is reached; see [is_rising_fast()]
+///
+/// - param temp: The current temperature in Fahrenheit
+/// - param rate: How fast the temperature is changing, in tenths of a degree Fahrenheit per minute;
+///   see [crate::sensors::TemperatureRate::temperature_rate_per_min()]
+/// - param preferences: [Preferences] instance
+///
+/// returns the vent angle from 0 to [MAX_VENT_ANGLE] degrees
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{vent_angle, Preferences};
+///
+///  let preferences = Preferences::default(); // Upper bound is 80F
+///  assert_eq!(vent_angle(75, 0, &preferences), 0);    // Cool enough, closed
+///  assert_eq!(vent_angle(80, 0, &preferences), 0);    // At the bound, closed
+///  assert_eq!(vent_angle(85, 0, &preferences), 90);   // Halfway through the span
+///  assert_eq!(vent_angle(90, 0, &preferences), 180);  // Fully open
+///  assert_eq!(vent_angle(120, 0, &preferences), 180); // Clamped
+///
+///  // The sun came out: 2.5F per minute
+///  assert_eq!(vent_angle(75, 25, &preferences), 90);   // Opened early
+///  assert_eq!(vent_angle(90, 25, &preferences), 180);  // Already open further
+///  assert_eq!(vent_angle(55, 25, &preferences), 0);    // Warming up from the cold is welcome
+/// ```
+pub fn vent_angle(temp: u8, rate: i16, preferences: &Preferences) -> u8 {
+    let excess = temp.saturating_sub(preferences.temperature.1) as u16;
+    let angle =
+        (excess * MAX_VENT_ANGLE as u16 / VENT_SPAN as u16).min(MAX_VENT_ANGLE as u16) as u8;
+    if temp >= preferences.temperature.0 && is_rising_fast(rate, preferences) {
+        angle.max(RATE_VENT_ANGLE)
+    } else {
+        angle
+    }
+}
+
+/// Checks if the temperature is rising faster than [Preferences::temperature_rate_limit],
+/// which warns of a problem before the upper bound is crossed
+///
+/// - param rate: How fast the temperature is changing, in tenths of a degree Fahrenheit per minute
+/// - param preferences: [Preferences] instance
+///
+/// returns true if the rate is at or above the limit, and the limit is on
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{is_rising_fast, Preferences};
+///
+///  let mut preferences = Preferences::default(); // 2.0F per minute
+///  assert!(!is_rising_fast(19, &preferences));
+///  assert!(is_rising_fast(20, &preferences));
+///  assert!(!is_rising_fast(-40, &preferences)); // Cooling down
+///
+///  preferences.temperature_rate_limit = 0; // Off
+///  assert!(!is_rising_fast(100, &preferences));
+/// ```
+pub fn is_rising_fast(rate: i16, preferences: &Preferences) -> bool {
+    preferences.temperature_rate_limit != 0 && rate >= preferences.temperature_rate_limit as i16
+}
+
+/// Decides whether the sprinklers should be running to raise the humidity
+///
+/// The sprinklers turn on once the humidity drops [HysteresisMargins::sprinklers] below the
+/// lower humidity bound and turn off once it rises the same amount above it.
+/// In between, the sprinklers keep their previous state
+///
+/// - param humidity: The current relative humidity percentage
+/// - param sprinkling: Whether the sprinklers are currently running
+/// - param preferences: [Preferences] instance
+///
+/// returns if the sprinklers should be running
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{should_humidify, Preferences};
+///
+///  let preferences = Preferences::default(); // Lower bound is 60%
+///  assert!(should_humidify(56, false, &preferences));  // Too dry, turn on
+///  assert!(!should_humidify(59, false, &preferences)); // Within the margin, stay off
+///  assert!(should_humidify(62, true, &preferences));   // Within the margin, stay on
+///  assert!(!should_humidify(64, true, &preferences));  // Humid enough, turn off
+/// ```
+pub fn should_humidify(humidity: u8, sprinkling: bool, preferences: &Preferences) -> bool {
+    Hysteresis::falling(preferences.humidity_points())
+        .with_state(sprinkling)
+        .update(humidity)
+}
+
+/// Decides whether the soil should be watered off-schedule because it is too dry
+///
+/// Watering starts once the soil moisture drops below the lower soil moisture bound
+/// and continues until it reaches the upper bound
+///
+/// - param moisture: The current soil moisture percentage
+/// - param soaking: Whether the soil is currently being watered off-schedule
+/// - param preferences: [Preferences] instance
+///
+/// returns if the sprinklers should be running to water the soil
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{should_soak, Preferences};
+///
+///  let preferences = Preferences::default(); // Soil moisture range is 30% - 60%
+///  assert!(should_soak(25, false, &preferences));  // Too dry, turn on
+///  assert!(!should_soak(45, false, &preferences)); // Within the range, stay off
+///  assert!(should_soak(45, true, &preferences));   // Within the range, stay on
+///  assert!(!should_soak(60, true, &preferences));  // Wet enough, turn off
+/// ```
+pub fn should_soak(moisture: u8, soaking: bool, preferences: &Preferences) -> bool {
+    Hysteresis::falling(preferences.soil_points())
+        .with_state(soaking)
+        .update(moisture)
+}
+
+/// Decides whether the sprinklers should be running.
+/// They run during a watering window or while something else needs water.
+/// Scheduled watering is skipped or cut short once the soil is moist enough
+///
+/// - param needed: Whether [should_humidify()] or [should_soak()] wants the sprinklers running
+/// - param moisture: The current soil moisture percentage, or None without a soil sensor
+/// - param preferences: [Preferences] instance
+///
+/// returns if the sprinklers should be running
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{sprinklers_should_run, Preferences};
+///
+///  let mut preferences = Preferences::default(); // 00:00, no watering windows
+///  assert!(!sprinklers_should_run(false, None, &preferences)); // Not watering, humid enough
+///  assert!(sprinklers_should_run(true, None, &preferences));   // Not watering, too dry
+///
+///  preferences.set_default_watering_time(); // Water from 00:00 to 01:00
+///  assert!(sprinklers_should_run(false, None, &preferences));     // Watering, humid enough
+///  assert!(sprinklers_should_run(true, None, &preferences));      // Watering, too dry
+///  assert!(sprinklers_should_run(false, Some(40), &preferences)); // Watering, soil is dry
+///  assert!(!sprinklers_should_run(false, Some(60), &preferences)); // Soil is already moist
+/// ```
+pub fn sprinklers_should_run(
+    needed: bool,
+    moisture: Option<u8>,
+    preferences: &Preferences,
+) -> bool {
+    let soil_is_moist = moisture.is_some_and(|moisture| moisture >= preferences.soil_moisture.1);
+    needed || (preferences.is_watering_time() && !soil_is_moist)
+}
+
+/// How the vapor pressure deficit compares to [Preferences::vpd]
+///
+/// - **Low**: The air is too humid, so plants transpire too little and risk mold
+/// - **Ok**: Within the target range
+/// - **High**: The air is too dry, so plants transpire too much and wilt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VpdStatus {
+    Low,
+    Ok,
+    High,
+}
+
+impl VpdStatus {
+    /// Gets the status's name for the LCD
+    ///
+    /// returns the name; Ex: `OK`
+    pub fn name(self) -> &'static str {
+        match self {
+            VpdStatus::Low => VPD_LOW,
+            VpdStatus::Ok => VPD_OK,
+            VpdStatus::High => VPD_HIGH,
+        }
+    }
+}
+
+/// Classifies a vapor pressure deficit against the target range. The bounds are within the range
+///
+/// - param vpd: The VPD in hundredths of a kPa, e.g. from [crate::sensors::get_vpd()]
+/// - param preferences: [Preferences] instance
+///
+/// returns the [VpdStatus]
+///
+/// ## Example:
+/// ```rust
+///  use gem_rs::preferences::{vpd_status, Preferences, VpdStatus};
+///
+///  let preferences = Preferences::default(); // 0.8 - 1.2 kPa
+///  assert_eq!(vpd_status(79, &preferences), VpdStatus::Low);
+///  assert_eq!(vpd_status(80, &preferences), VpdStatus::Ok);
+///  assert_eq!(vpd_status(120, &preferences), VpdStatus::Ok);
+///  assert_eq!(vpd_status(158, &preferences), VpdStatus::High); // 25C at 50%
+///  assert_eq!(vpd_status(0, &preferences), VpdStatus::Low);    // Saturated
+/// ```
+pub fn vpd_status(vpd: u16, preferences: &Preferences) -> VpdStatus {
+    if vpd < preferences.vpd.0 {
+        VpdStatus::Low
+    } else if vpd > preferences.vpd.1 {
+        VpdStatus::High
+    } else {
+        VpdStatus::Ok
+    }
+}
+
+/// Somewhere [Preferences] are kept while the controller is off, like [crate::eeprom::Eeprom].
+/// Without one, the settings are only logged for a bridge to keep; see [crate::telemetry::preferences]
+pub trait PreferenceStore {
+    /// Why saving failed
+    type Error;
+
+    /// Saves the preferences so they survive a restart
+    ///
+    /// - param preferences: [Preferences] instance
+    ///
+    /// returns the [PreferenceStore::Error] if they could not be saved
+    fn save(&mut self, preferences: &Preferences) -> Result<(), Self::Error>;
+
+    /// Loads the preferences saved last
+    ///
+    /// returns the [Preferences], or None if there are none or they could not be read
+    fn load(&mut self) -> Option<Preferences>;
+}
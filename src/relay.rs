@@ -0,0 +1,142 @@
+use core::convert::Infallible;
+use embedded_hal::digital::{Error, PinState, StatefulOutputPin};
+
+use panic_probe as _;
+
+/// A digital output, e.g. a relay. Each zone is wired to different pins, so their types are erased
+pub type Switch<'a> = &'a mut dyn StatefulOutputPin<Error = Infallible>;
+
+/// Which level of its pin switches an output on. Many relay boards switch on when their input is
+/// pulled low; wiring one to an active-high output runs it whenever it should be off
+///
+/// - **High**: The output is on while its pin is high
+/// - **Low**: The output is on while its pin is low
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActivePolarity {
+    High,
+    Low,
+}
+
+impl ActivePolarity {
+    /// Gets the pin level that switches the output on or off
+    ///
+    /// - param active: Whether the output should be on
+    ///
+    /// returns the [PinState] to drive the pin to
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use embedded_hal::digital::PinState;
+    /// use gem_rs::relay::ActivePolarity;
+    ///
+    /// assert_eq!(ActivePolarity::High.state(true), PinState::High);
+    /// assert_eq!(ActivePolarity::Low.state(true), PinState::Low);
+    /// assert_eq!(ActivePolarity::Low.state(false), PinState::High);
+    /// ```
+    pub fn state(self, active: bool) -> PinState {
+        match self {
+            ActivePolarity::High => active.into(),
+            ActivePolarity::Low => (!active).into(),
+        }
+    }
+
+    /// Gets the pin level that keeps the output off, e.g. to set a pin up without switching it on
+    ///
+    /// returns the [PinState] the output is off in
+    pub fn inactive_state(self) -> PinState {
+        self.state(false)
+    }
+}
+
+/// The sprinkler relays' polarity. Use [ActivePolarity::Low] for an active-low relay board
+pub const SPRINKLER_POLARITY: ActivePolarity = ActivePolarity::High;
+/// The heater relays' polarity. Use [ActivePolarity::Low] for an active-low relay board
+pub const HEATER_POLARITY: ActivePolarity = ActivePolarity::High;
+/// The grow light relay's polarity. Use [ActivePolarity::Low] for an active-low relay board
+pub const GROW_LIGHT_POLARITY: ActivePolarity = ActivePolarity::High;
+/// The vent relays' polarity, with the `relay-vent` feature. Use [ActivePolarity::Low] for an active-low relay board
+pub const VENT_RELAY_POLARITY: ActivePolarity = ActivePolarity::High;
+
+/// A relay, or any other output switched on and off by one pin. Every write goes through
+/// [Relay::activate()] and [Relay::deactivate()], so the [ActivePolarity] is only dealt with here
+///
+/// - **pin**: The output's pin, usually a [Switch]; its errors are of type `E`
+/// - **polarity**: Which level of the pin switches the output on
+///
+/// ## Example:
+/// ```rust
+/// use embedded_hal_mock::eh1::digital::{Mock, State, Transaction};
+/// use gem_rs::relay::{ActivePolarity, Relay};
+///
+/// let mut pin = Mock::new(&[
+///     // An active-low relay board is switched off as soon as it is wrapped
+///     Transaction::set(State::High),
+///     Transaction::get(State::High),
+///     // Switched on, then off again
+///     Transaction::set(State::Low),
+///     Transaction::get(State::Low),
+///     Transaction::set(State::High),
+///     Transaction::get(State::High),
+///     // Active-high, as most boards
+///     Transaction::set(State::Low),
+///     Transaction::set(State::High),
+/// ]);
+///
+/// let mut sprinklers = Relay::new(&mut pin, ActivePolarity::Low);
+/// assert!(!sprinklers.is_active());
+/// sprinklers.activate();
+/// assert!(sprinklers.is_active());
+/// sprinklers.set_active(false);
+/// assert!(!sprinklers.is_active());
+/// drop(sprinklers);
+///
+/// let mut heater = Relay::new(&mut pin, ActivePolarity::High);
+/// heater.activate();
+/// drop(heater);
+/// pin.done();
+/// ```
+pub struct Relay<'a, E = Infallible> {
+    pin: &'a mut dyn StatefulOutputPin<Error = E>,
+    polarity: ActivePolarity,
+}
+
+impl<'a, E: Error> Relay<'a, E> {
+    /// Creates a new Relay and switches it off
+    ///
+    /// - param pin: The output's pin, usually a [Switch]
+    /// - param polarity: Which level of the pin switches the output on
+    ///
+    /// returns a new instance of Relay
+    pub fn new(
+        pin: &'a mut dyn StatefulOutputPin<Error = E>,
+        polarity: ActivePolarity,
+    ) -> Relay<'a, E> {
+        let mut relay = Self { pin, polarity };
+        relay.deactivate();
+        relay
+    }
+
+    /// Switches the output on
+    pub fn activate(&mut self) {
+        self.set_active(true);
+    }
+
+    /// Switches the output off
+    pub fn deactivate(&mut self) {
+        self.set_active(false);
+    }
+
+    /// Switches the output on or off
+    ///
+    /// - param active: Whether the output should be on
+    pub fn set_active(&mut self, active: bool) {
+        self.pin.set_state(self.polarity.state(active)).unwrap();
+    }
+
+    /// Reads back whether the output was switched on
+    ///
+    /// returns true if the pin is driven to its active level
+    pub fn is_active(&mut self) -> bool {
+        self.pin.is_set_high().unwrap() == (self.polarity == ActivePolarity::High)
+    }
+}
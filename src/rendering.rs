@@ -1,19 +1,207 @@
-use crate::preferences::{inclusive_iterator, Preferences};
 use embedded_hal::delay::DelayNs;
-use embedded_hal::digital::InputPin;
-use hd44780_driver::bus::FourBitBus;
+use embedded_hal::digital::OutputPin;
+use hd44780_driver::bus::{DataBus, FourBitBus};
 use hd44780_driver::charset::{CharsetUniversal, EmptyFallback};
 use hd44780_driver::memory_map::StandardMemoryMap;
-use hd44780_driver::HD44780;
+use hd44780_driver::{Cursor, CursorBlink, HD44780};
 use heapless::String;
-use rp_pico::hal::gpio::bank0::{Gpio0, Gpio1, Gpio10, Gpio11, Gpio12, Gpio2, Gpio3, Gpio4, Gpio5};
-use rp_pico::hal::gpio::{FunctionSio, Pin, PullDown, SioInput, SioOutput};
-use rp_pico::hal::Timer;
+use rp_pico::hal::gpio::bank0::{Gpio0, Gpio1, Gpio16, Gpio2, Gpio3, Gpio4, Gpio5};
+use rp_pico::hal::gpio::{FunctionSio, Pin, PullDown, SioOutput};
 use ufmt::uwrite;
 
+use crate::error::GemError;
+use crate::messages::FAULT_CODE;
+use crate::selftest::{FIRMWARE_NAME, FIRMWARE_VERSION};
+
 use panic_probe as _;
 
-pub type Lcd = HD44780<
+/// The amount of characters that fit on one line of the [Lcd]
+pub const LCD_WIDTH: u8 = 16;
+/// The amount of lines on the [Lcd]
+pub const LCD_HEIGHT: u8 = 2;
+/// How many times setting up the [Lcd] is attempted before running without it
+pub const LCD_INIT_RETRIES: u8 = 5;
+
+/// The columns of the hour and minute fields in a line from
+/// [crate::preferences::Preferences::format_watering_time()]: Start Hour, Start Min, End Hour, End Min
+const WATERING_FIELD_COLUMNS: [u8; 4] = [0, 3, 8, 11];
+/// The column of Sunday in a `Days: SMTWTFS` line from [crate::preferences::WateringWindow::format_days()]
+const DAYS_FIELD_COLUMN: u8 = 6;
+
+/// CGRAM slot holding the degree symbol. Write `°` in a line to render it.
+///
+/// The HD44780 has only 8 custom character slots (0-7), and all of them are in use:
+/// - **0**: Degree symbol (`°`)
+/// - **1**: Up arrow (`↑`)
+/// - **2**: Down arrow (`↓`)
+/// - **3-6**: Gauge cells with 1 to 4 columns filled; see [render_gauge()]
+/// - **7**: `é`, which the [CharacterRom::A00] lacks; see [lcd_byte()]
+///
+/// While a graph is shown, slots 0-6 hold its bars instead; see [GlyphSet].
+/// Any other character has to come from the [LCD_ROM] or fall back to plain ASCII
+pub const DEGREE_GLYPH: u8 = 0;
+/// CGRAM slot holding the up arrow. Write `↑` in a line to render it
+pub const UP_ARROW_GLYPH: u8 = 1;
+/// CGRAM slot holding the down arrow. Write `↓` in a line to render it
+pub const DOWN_ARROW_GLYPH: u8 = 2;
+/// CGRAM slots holding the partly filled gauge cells, with 1 to 4 pixel columns filled from the left
+pub const GAUGE_GLYPHS: [u8; 4] = [3, 4, 5, 6];
+/// CGRAM slot holding `é`. Write `é` in a line to render it
+pub const E_ACUTE_GLYPH: u8 = 7;
+/// CGRAM slots holding the sparkline bars while a graph is shown, 1 to 7 pixel rows tall;
+/// see [render_sparkline()]. The 8th level is the fully filled cell
+pub const SPARKLINE_GLYPHS: [u8; 7] = [0, 1, 2, 3, 4, 5, 6];
+/// How many heights a sparkline bar can be, one per pixel row of a character cell
+pub const SPARKLINE_LEVELS: u8 = 8;
+
+/// The HD44780 character ROM's fully filled cell
+const FULL_CELL: u8 = 0xFF;
+/// The amount of pixel columns in one character cell
+pub const CELL_COLUMNS: u16 = 5;
+
+/// 5x8 bitmap of the degree symbol
+const DEGREE_BITMAP: [u8; 8] = [
+    0b00110, 0b01001, 0b01001, 0b00110, 0b00000, 0b00000, 0b00000, 0b00000,
+];
+
+/// 5x8 bitmap of the up arrow
+const UP_ARROW_BITMAP: [u8; 8] = [
+    0b00100, 0b01110, 0b10101, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000,
+];
+
+/// 5x8 bitmap of the down arrow
+const DOWN_ARROW_BITMAP: [u8; 8] = [
+    0b00100, 0b00100, 0b00100, 0b00100, 0b10101, 0b01110, 0b00100, 0b00000,
+];
+
+/// 5x8 bitmap of `é`
+const E_ACUTE_BITMAP: [u8; 8] = [
+    0b00010, 0b00100, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110, 0b00000,
+];
+
+/// 5x8 bitmap of a gauge cell with the left `columns` pixel columns filled.
+/// The top and bottom rows are left blank so the bar stands apart from the text
+///
+/// - param columns: the amount of columns to fill, from 1 to 4
+///
+/// returns the bitmap
+const fn gauge_bitmap(columns: u16) -> [u8; 8] {
+    let row = (0b11111 << (CELL_COLUMNS - columns)) as u8 & 0b11111;
+    [0, row, row, row, row, row, row, 0]
+}
+
+/// 5x8 bitmap of a sparkline bar with the bottom `rows` pixel rows filled
+///
+/// - param rows: the amount of rows to fill, from 1 to 7
+///
+/// returns the bitmap
+const fn bar_bitmap(rows: u8) -> [u8; 8] {
+    let mut bitmap = [0; 8];
+    let mut row = 8 - rows as usize;
+    while row < 8 {
+        bitmap[row] = 0b11111;
+        row += 1;
+    }
+    bitmap
+}
+
+/// The sets of glyphs the [Lcd]'s 8 CGRAM slots can hold. Only one set fits at a time,
+/// and characters already on the screen change as soon as their slot does
+///
+/// - **Text**: The degree symbol, arrows, gauge cells, and `é`; see [DEGREE_GLYPH]
+/// - **Sparkline**: The bars graphs are drawn with; see [SPARKLINE_GLYPHS]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GlyphSet {
+    Text,
+    Sparkline,
+}
+
+/// The character sets HD44780 displays are made with. Only the characters past ASCII differ
+///
+/// - **A00**: Japanese, with katakana and a few European letters; most 16x2 modules have this one
+/// - **A02**: European, with the Latin-1 letters from `À` to `ÿ` in the same places as Unicode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CharacterRom {
+    A00,
+    A02,
+}
+
+/// The character ROM of the [Lcd]. Build with the `rom-a02` feature for a European panel
+#[cfg(not(feature = "rom-a02"))]
+pub const LCD_ROM: CharacterRom = CharacterRom::A00;
+/// The character ROM of the [Lcd]. Build without the `rom-a02` feature for a Japanese panel
+#[cfg(feature = "rom-a02")]
+pub const LCD_ROM: CharacterRom = CharacterRom::A02;
+
+/// Finds the byte that shows a character the display's charset cannot, since it only knows ASCII.
+/// The custom glyphs come first, then the character ROM. Accented letters that the ROM lacks
+/// fall back to their plain letter, so `è` shows as `e` on an [CharacterRom::A00] display
+///
+/// - param c: the character to show
+/// - param rom: the display's [CharacterRom]
+///
+/// returns the byte to write, or None for ASCII and characters that cannot be shown
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::rendering::{lcd_byte, CharacterRom, DEGREE_GLYPH, E_ACUTE_GLYPH};
+///
+/// assert_eq!(lcd_byte('°', CharacterRom::A00), Some(DEGREE_GLYPH)); // Custom glyph
+/// assert_eq!(lcd_byte('é', CharacterRom::A00), Some(E_ACUTE_GLYPH));
+/// assert_eq!(lcd_byte('ü', CharacterRom::A00), Some(0xF5)); // From the ROM
+/// assert_eq!(lcd_byte('µ', CharacterRom::A00), Some(0xE4));
+/// assert_eq!(lcd_byte('à', CharacterRom::A00), Some(b'a')); // Plain letter
+///
+/// assert_eq!(lcd_byte('é', CharacterRom::A02), Some(0xE9)); // Latin-1
+/// assert_eq!(lcd_byte('µ', CharacterRom::A02), Some(0xB5));
+///
+/// assert_eq!(lcd_byte('A', CharacterRom::A00), None); // Left to the charset
+/// assert_eq!(lcd_byte('€', CharacterRom::A02), None); // Unsupported
+/// ```
+pub fn lcd_byte(c: char, rom: CharacterRom) -> Option<u8> {
+    match c {
+        '°' => return Some(DEGREE_GLYPH),
+        '↑' => return Some(UP_ARROW_GLYPH),
+        '↓' => return Some(DOWN_ARROW_GLYPH),
+        _ => {}
+    }
+    match rom {
+        CharacterRom::A00 => match c {
+            'é' => Some(E_ACUTE_GLYPH),
+            'ä' => Some(0xE1),
+            'ß' => Some(0xE2),
+            'µ' => Some(0xE4),
+            'ñ' => Some(0xEE),
+            'ö' => Some(0xEF),
+            'ü' => Some(0xF5),
+            'à' | 'á' | 'â' | 'ã' | 'å' => Some(b'a'),
+            'ç' => Some(b'c'),
+            'è' | 'ê' | 'ë' => Some(b'e'),
+            'ì' | 'í' | 'î' | 'ï' => Some(b'i'),
+            'ò' | 'ó' | 'ô' | 'õ' => Some(b'o'),
+            'ù' | 'ú' | 'û' => Some(b'u'),
+            'À' | 'Á' | 'Â' | 'Ä' => Some(b'A'),
+            'É' | 'È' => Some(b'E'),
+            'Ö' => Some(b'O'),
+            'Ü' => Some(b'U'),
+            _ => None,
+        },
+        CharacterRom::A02 => match c {
+            'µ' => Some(0xB5),
+            'À'..='ÿ' => Some(c as u8),
+            _ => None,
+        },
+    }
+}
+
+/// A `COLS`x`ROWS` HD44780 display on any [DataBus].
+/// Every rendering function accepts this, so the display can be wired to any pins.
+/// It is 16x2 unless specified; use `GenericLcd<B, 20, 4>` with `MemoryMap2004` for a 20x4 panel
+pub type GenericLcd<B, const COLS: u8 = LCD_WIDTH, const ROWS: u8 = LCD_HEIGHT> =
+    HD44780<B, StandardMemoryMap<COLS, ROWS>, EmptyFallback<CharsetUniversal>>;
+
+/// The default [GenericLcd] wiring: RS, EN, D4-D7 on GPIO0-GPIO5
+pub type Lcd = GenericLcd<
     FourBitBus<
         Pin<Gpio0, FunctionSio<SioOutput>, PullDown>,
         Pin<Gpio1, FunctionSio<SioOutput>, PullDown>,
@@ -22,53 +210,672 @@ pub type Lcd = HD44780<
         Pin<Gpio4, FunctionSio<SioOutput>, PullDown>,
         Pin<Gpio5, FunctionSio<SioOutput>, PullDown>,
     >,
-    StandardMemoryMap<16, 2>,
-    EmptyFallback<CharsetUniversal>,
 >;
 
+/// The default pin driving the [Lcd]'s backlight
+pub type Backlight = Pin<Gpio16, FunctionSio<SioOutput>, PullDown>;
+
+/// What the rendering functions draw on. [GenericLcd] implements it for real displays, and
+/// `MockLcd`, with the `mock-lcd` feature, records what would be drawn, so the layouts can be tested without one
+pub trait DisplaySurface {
+    /// The amount of characters that fit on one line
+    const COLS: u8;
+    /// The amount of lines
+    const ROWS: u8;
+
+    /// Blanks the display and moves the cursor to the top left
+    ///
+    /// - param delay: Delay instance
+    ///
+    /// returns [GemError::Display] if the display did not accept the command
+    fn clear(&mut self, delay: &mut impl DelayNs) -> Result<(), GemError>;
+
+    /// Moves the cursor
+    ///
+    /// - param position: The column and row to move to: X, Y
+    /// - param delay: Delay instance
+    ///
+    /// returns [GemError::Display] if the display did not accept the command
+    fn set_cursor(&mut self, position: (u8, u8), delay: &mut impl DelayNs) -> Result<(), GemError>;
+
+    /// Writes plain text at the cursor. Use [write_localized()] for text with glyphs or accents
+    ///
+    /// - param text: The text to write
+    /// - param delay: Delay instance
+    ///
+    /// returns [GemError::Display] if the display did not accept the text
+    fn write_str(&mut self, text: &str, delay: &mut impl DelayNs) -> Result<(), GemError>;
+
+    /// Writes one character code at the cursor, e.g. a CGRAM slot or a [CharacterRom] character
+    ///
+    /// - param byte: The character code
+    /// - param delay: Delay instance
+    ///
+    /// returns [GemError::Display] if the display did not accept the character
+    fn write_byte(&mut self, byte: u8, delay: &mut impl DelayNs) -> Result<(), GemError>;
+
+    /// Uploads a glyph into a CGRAM slot
+    ///
+    /// - param slot: The CGRAM slot, from 0 to 7
+    /// - param bitmap: The 5x8 glyph, one row per byte
+    /// - param delay: Delay instance
+    ///
+    /// returns [GemError::Display] if the display did not accept the glyph
+    fn set_custom_char(
+        &mut self,
+        slot: u8,
+        bitmap: &[u8; 8],
+        delay: &mut impl DelayNs,
+    ) -> Result<(), GemError>;
+
+    /// Hides the cursor and stops it blinking
+    ///
+    /// - param delay: Delay instance
+    ///
+    /// returns [GemError::Display] if the display did not accept the command
+    fn hide_cursor(&mut self, delay: &mut impl DelayNs) -> Result<(), GemError>;
+}
+
+impl<B: DataBus, const COLS: u8, const ROWS: u8> DisplaySurface for GenericLcd<B, COLS, ROWS> {
+    const COLS: u8 = COLS;
+    const ROWS: u8 = ROWS;
+
+    fn clear(&mut self, delay: &mut impl DelayNs) -> Result<(), GemError> {
+        HD44780::clear(self, delay)?;
+        Ok(())
+    }
+
+    fn set_cursor(&mut self, position: (u8, u8), delay: &mut impl DelayNs) -> Result<(), GemError> {
+        self.set_cursor_xy(position, delay)?;
+        Ok(())
+    }
+
+    fn write_str(&mut self, text: &str, delay: &mut impl DelayNs) -> Result<(), GemError> {
+        HD44780::write_str(self, text, delay)?;
+        Ok(())
+    }
+
+    fn write_byte(&mut self, byte: u8, delay: &mut impl DelayNs) -> Result<(), GemError> {
+        HD44780::write_byte(self, byte, delay)?;
+        Ok(())
+    }
+
+    fn set_custom_char(
+        &mut self,
+        slot: u8,
+        bitmap: &[u8; 8],
+        delay: &mut impl DelayNs,
+    ) -> Result<(), GemError> {
+        HD44780::set_custom_char(self, slot, bitmap, delay)?;
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self, delay: &mut impl DelayNs) -> Result<(), GemError> {
+        self.set_cursor_visibility(Cursor::Invisible, delay)?;
+        self.set_cursor_blink(CursorBlink::Off, delay)?;
+        Ok(())
+    }
+}
+
+/// Uploads the custom glyphs into the [Lcd]'s CGRAM.
+/// This should be called once after the [Lcd] is set up; see [setup_display()]
+///
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the display did not accept a glyph
+pub fn register_custom_glyphs<D: DisplaySurface>(
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    lcd.set_custom_char(DEGREE_GLYPH, &DEGREE_BITMAP, delay)?;
+    lcd.set_custom_char(UP_ARROW_GLYPH, &UP_ARROW_BITMAP, delay)?;
+    lcd.set_custom_char(DOWN_ARROW_GLYPH, &DOWN_ARROW_BITMAP, delay)?;
+    lcd.set_custom_char(E_ACUTE_GLYPH, &E_ACUTE_BITMAP, delay)?;
+    for (columns, slot) in (1..).zip(GAUGE_GLYPHS) {
+        lcd.set_custom_char(slot, &gauge_bitmap(columns), delay)?;
+    }
+    Ok(())
+}
+
+/// Uploads a [GlyphSet] into the [Lcd]'s CGRAM, unless it already holds it.
+/// Call this before drawing anything that uses the set's glyphs
+///
+/// - param wanted: The [GlyphSet] to use
+/// - param loaded: The [GlyphSet] the display holds, or None if it is unknown, e.g. after a [GemError::Display]
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the display did not accept a glyph
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{LcdOperation, MockDelay, MockLcd};
+/// use gem_rs::rendering::{load_glyphs, GlyphSet};
+///
+/// let mut lcd: MockLcd = MockLcd::new();
+/// let mut loaded = Some(GlyphSet::Text);
+/// load_glyphs(GlyphSet::Text, &mut loaded, &mut lcd, &mut MockDelay).unwrap();
+/// assert!(lcd.operations.is_empty()); // Already there
+///
+/// load_glyphs(GlyphSet::Sparkline, &mut loaded, &mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(loaded, Some(GlyphSet::Sparkline));
+/// assert_eq!(lcd.operations.len(), 7);
+/// assert_eq!(lcd.operations[0], LcdOperation::CustomChar(0));
+/// ```
+pub fn load_glyphs<D: DisplaySurface>(
+    wanted: GlyphSet,
+    loaded: &mut Option<GlyphSet>,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    if *loaded == Some(wanted) {
+        return Ok(());
+    }
+    // Half a set may be in if a glyph fails
+    *loaded = None;
+    match wanted {
+        GlyphSet::Text => register_custom_glyphs(lcd, delay)?,
+        GlyphSet::Sparkline => {
+            for (rows, slot) in (1..).zip(SPARKLINE_GLYPHS) {
+                lcd.set_custom_char(slot, &bar_bitmap(rows), delay)?;
+            }
+        }
+    }
+    *loaded = Some(wanted);
+    Ok(())
+}
+
+/// Gets the [Lcd] ready for rendering: hides the cursor and uploads the custom glyphs.
+/// Call this once the [Lcd] is set up, and again to bring it back after a [GemError::Display],
+/// since a glitch may have scrambled its settings
+///
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the display did not respond
+pub fn setup_display<D: DisplaySurface>(
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    lcd.hide_cursor(delay)?;
+    register_custom_glyphs(lcd, delay)?;
+    lcd.clear(delay)?;
+    Ok(())
+}
+
+/// Cuts a line down to the characters that fit in `width` columns.
+/// Glyph placeholders like `°` take up one column
+///
+/// - param line: text to fit
+/// - param width: the amount of columns available
+///
+/// returns the part of the line that fits, and whether anything was cut off
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::rendering::fit_to_width;
+///
+/// assert_eq!(fit_to_width("Temp: 72°F", 16), ("Temp: 72°F", false));
+/// assert_eq!(fit_to_width("0123456789ABCDEF", 16), ("0123456789ABCDEF", false));
+/// assert_eq!(fit_to_width("0123456789ABCDEFGH", 16), ("0123456789ABCDEF", true));
+/// assert_eq!(fit_to_width("°°°", 2), ("°°", true));
+/// ```
+pub fn fit_to_width(line: &str, width: usize) -> (&str, bool) {
+    match line.char_indices().nth(width) {
+        Some((end, _)) => (&line[..end], true),
+        None => (line, false),
+    }
+}
+
+/// Writes text at the current cursor position, swapping glyph placeholders like `°` and
+/// accented letters for the bytes the [LCD_ROM] shows them with; see [lcd_byte()].
+/// At most [DisplaySurface::COLS] characters are written
+///
+/// - param line: text to write
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns true if the line was too long and was cut off, or [GemError::Display] if it could not be written
+pub fn write_localized<D: DisplaySurface>(
+    line: &str,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<bool, GemError> {
+    let (line, truncated) = fit_to_width(line, D::COLS as usize);
+    for c in line.chars() {
+        match lcd_byte(c, LCD_ROM) {
+            Some(byte) => lcd.write_byte(byte, delay)?,
+            None => lcd.write_str(c.encode_utf8(&mut [0; 4]), delay)?,
+        }
+    }
+    Ok(truncated)
+}
+
+/// Turns the [Lcd]'s backlight on or off
+///
+/// - param on: whether the backlight should be lit
+/// - param backlight: The backlight pin, e.g. [Backlight]
+pub fn set_backlight(on: bool, backlight: &mut impl OutputPin) {
+    if on {
+        backlight.set_high().unwrap();
+    } else {
+        backlight.set_low().unwrap();
+    }
+}
+
 /// Basic function for rendering text onto the LCD.
-/// It only clears the screen when the top line is written to
+/// It only clears the screen when the top line is written to.
+/// Text past the display's width is cut off; use [render_scrolling()] to show all of it
 ///
 /// - param line: text to render
 /// - param top_line: if the top line is to be written to
-/// - param lcd: [Lcd] instance
-pub fn render_screen(line: &str, top_line: bool, lcd: &mut Lcd, delay: &mut Timer) {
-    // Set cursor to the correct line
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+///
+/// returns true if the line was too long and was cut off, or [GemError::Display] if it could not be written
+pub fn render_screen<D: DisplaySurface>(
+    line: &str,
+    top_line: bool,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<bool, GemError> {
     if top_line {
         // Reset screen
-        lcd.clear(delay).unwrap();
-        lcd.set_cursor_pos(0, delay).unwrap();
+        lcd.clear(delay)?;
+        render_line(line, 0, lcd, delay)
     } else {
-        lcd.set_cursor_xy((0, 1), delay).unwrap();
+        render_line(line, 1, lcd, delay)
     }
-    lcd.write_str(line, delay).unwrap();
+}
+
+/// Renders both lines of a two-line screen in one go, without the blink of clearing it first.
+/// Each line is padded with spaces to the display's width, so it overwrites whatever was there before,
+/// and any rows below are blanked. The screen never shows a blank bottom line between refreshes
+/// the way it can when each line is drawn with [render_screen()]
+///
+/// - param top: text to render on the top line
+/// - param bottom: text to render on the bottom line
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns true if either line was too long and was cut off, or [GemError::Display] if they could not be written
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{LcdOperation, MockDelay, MockLcd};
+/// use gem_rs::rendering::{render_screen, render_two_lines};
+///
+/// let mut lcd: MockLcd<16, 4> = MockLcd::new();
+/// render_screen("Soil: 45%", true, &mut lcd, &mut MockDelay).unwrap();
+/// render_screen("(30%, 60%)", false, &mut lcd, &mut MockDelay).unwrap();
+/// lcd.operations.clear();
+///
+/// assert!(!render_two_lines("12:30", "31/05 Fri", &mut lcd, &mut MockDelay).unwrap());
+/// assert_eq!(lcd.row(0).as_str(), "12:30           ");
+/// assert_eq!(lcd.row(1).as_str(), "31/05 Fri       ");
+/// assert_eq!(lcd.row(2).as_str(), "                ");
+/// assert!(!lcd.operations.contains(&LcdOperation::Clear));
+///
+/// // Text past the edge is still cut off
+/// assert!(render_two_lines("Temp: 72°F", "0123456789ABCDEFGH", &mut lcd, &mut MockDelay).unwrap());
+/// assert_eq!(lcd.row(0).as_str(), "Temp: 72°F      ");
+/// assert_eq!(lcd.row(1).as_str(), "0123456789ABCDEF");
+/// ```
+pub fn render_two_lines<D: DisplaySurface>(
+    top: &str,
+    bottom: &str,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<bool, GemError> {
+    let top_truncated = render_padded_line(top, 0, lcd, delay)?;
+    let bottom_truncated = render_padded_line(bottom, 1, lcd, delay)?;
+    for row in 2..D::ROWS {
+        render_padded_line("", row, lcd, delay)?;
+    }
+    Ok(top_truncated || bottom_truncated)
+}
+
+/// Renders text at the start of a row, then fills the rest of the row with spaces
+///
+/// - param line: text to render
+/// - param row: the row to write to; clamped to the last row
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns true if the line was too long and was cut off, or [GemError::Display] if it could not be written
+fn render_padded_line<D: DisplaySurface>(
+    line: &str,
+    row: u8,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<bool, GemError> {
+    let truncated = render_line(line, row, lcd, delay)?;
+    let written = fit_to_width(line, D::COLS as usize).0.chars().count();
+    for _ in written..D::COLS as usize {
+        lcd.write_byte(b' ', delay)?;
+    }
+    Ok(truncated)
+}
+
+/// Renders text at the start of any row without clearing the screen.
+/// This allows the extra rows of larger displays to be used
+///
+/// - param line: text to render
+/// - param row: the row to write to; clamped to the last row
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns true if the line was too long and was cut off, or [GemError::Display] if it could not be written
+pub fn render_line<D: DisplaySurface>(
+    line: &str,
+    row: u8,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<bool, GemError> {
+    lcd.set_cursor((0, row.min(D::ROWS - 1)), delay)?;
+    write_localized(line, lcd, delay)
+}
+
+/// Works out how much of a gauge to fill for a value within a range.
+/// Values outside the range fill the gauge completely or not at all
+///
+/// - param value: the current reading
+/// - param min: the value at the gauge's left end
+/// - param max: the value at the gauge's right end
+/// - param columns: the gauge's width in pixel columns
+///
+/// returns the amount of pixel columns to fill
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::rendering::gauge_fill;
+///
+/// assert_eq!(gauge_fill(70, 60, 80, 60), 30); // Halfway
+/// assert_eq!(gauge_fill(61, 60, 80, 60), 3);  // Finer than a whole cell
+/// assert_eq!(gauge_fill(50, 60, 80, 60), 0);  // Below the range
+/// assert_eq!(gauge_fill(95, 60, 80, 60), 60); // Above the range
+/// assert_eq!(gauge_fill(70, 70, 70, 60), 60); // An empty range is full at its limit
+/// ```
+pub fn gauge_fill(value: u8, min: u8, max: u8, columns: u16) -> u16 {
+    if max <= min {
+        return if value >= max { columns } else { 0 };
+    }
+    let span = (max - min) as u32;
+    let offset = (value.clamp(min, max) - min) as u32;
+    ((offset * columns as u32 + span / 2) / span) as u16
+}
+
+/// Renders a bar gauge across the bottom line showing where a value falls within a range,
+/// with the range's limits written at the bar's ends: `60███▌     80`.
+/// Each cell is filled one pixel column at a time using the [GAUGE_GLYPHS]
+///
+/// - param value: the current reading
+/// - param min: the lower limit, at the bar's left end
+/// - param max: the upper limit, at the bar's right end
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the gauge could not be written
+pub fn render_gauge<D: DisplaySurface>(
+    value: u8,
+    min: u8,
+    max: u8,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    let mut low: String<3> = String::new();
+    uwrite!(low, "{}", min).unwrap();
+    let mut high: String<3> = String::new();
+    uwrite!(high, "{}", max).unwrap();
+    let cells = (D::COLS as usize).saturating_sub(low.len() + high.len()) as u16;
+    let filled = gauge_fill(value, min, max, cells * CELL_COLUMNS);
+
+    lcd.set_cursor((0, D::ROWS - 1), delay)?;
+    write_localized(&low, lcd, delay)?;
+    for cell in 0..cells {
+        let columns = filled.saturating_sub(cell * CELL_COLUMNS).min(CELL_COLUMNS);
+        let byte = match columns {
+            0 => b' ',
+            CELL_COLUMNS => FULL_CELL,
+            partial => GAUGE_GLYPHS[partial as usize - 1],
+        };
+        lcd.write_byte(byte, delay)?;
+    }
+    write_localized(&high, lcd, delay)?;
+    Ok(())
+}
+
+/// Works out how tall a sparkline bar is for a value within a range.
+/// Every value gets at least the bottom row, so the line never has gaps
+///
+/// - param value: the sample
+/// - param min: the value at the bottom of the graph
+/// - param max: the value at the top of the graph
+///
+/// returns the bar's height in pixel rows, from 1 to [SPARKLINE_LEVELS]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::rendering::{sparkline_level, SPARKLINE_LEVELS};
+///
+/// assert_eq!(sparkline_level(58, 58, 74), 1); // The lowest sample
+/// assert_eq!(sparkline_level(74, 58, 74), SPARKLINE_LEVELS); // The highest
+/// assert_eq!(sparkline_level(66, 58, 74), 5); // Halfway, rounded
+/// assert_eq!(sparkline_level(70, 70, 70), 1); // A flat line sits at the bottom
+/// ```
+pub fn sparkline_level(value: u16, min: u16, max: u16) -> u8 {
+    if max <= min {
+        return 1;
+    }
+    let span = (max - min) as u32;
+    let offset = (value.clamp(min, max) - min) as u32;
+    1 + ((offset * (SPARKLINE_LEVELS - 1) as u32 + span / 2) / span) as u8
+}
+
+/// Renders samples as a sparkline across a row, one column each, scaled to their lowest and highest.
+/// The newest sample is at the right edge; older samples past the left edge are left out.
+/// The [GlyphSet::Sparkline] must be loaded first; see [load_glyphs()]
+///
+/// - param values: the samples, oldest first
+/// - param row: the row to draw on; clamped to the last row
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the sparkline could not be written
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{MockDelay, MockLcd};
+/// use gem_rs::rendering::{render_sparkline, SPARKLINE_GLYPHS};
+///
+/// let mut lcd: MockLcd = MockLcd::new();
+/// render_sparkline(&[58, 66, 74], 1, &mut lcd, &mut MockDelay).unwrap();
+/// let bars = [SPARKLINE_GLYPHS[0], SPARKLINE_GLYPHS[4], 0xFF];
+/// assert_eq!(lcd.row_bytes(1)[..13], [b' '; 13]);
+/// assert_eq!(lcd.row_bytes(1)[13..], bars);
+/// ```
+pub fn render_sparkline<D: DisplaySurface>(
+    values: &[u16],
+    row: u8,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    let values = &values[values.len().saturating_sub(D::COLS as usize)..];
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+
+    lcd.set_cursor((0, row.min(D::ROWS - 1)), delay)?;
+    for _ in values.len()..D::COLS as usize {
+        lcd.write_byte(b' ', delay)?;
+    }
+    for value in values {
+        let byte = match sparkline_level(*value, min, max) {
+            SPARKLINE_LEVELS => FULL_CELL,
+            level => SPARKLINE_GLYPHS[level as usize - 1],
+        };
+        lcd.write_byte(byte, delay)?;
+    }
+    Ok(())
+}
+
+/// Renders a line that may be longer than the [Lcd] is wide.
+/// Each call shifts a [DisplaySurface::COLS] character window one step across the line,
+/// so it should be called on every refresh to keep the text moving.
+/// Lines that fit on the screen are rendered like [render_screen()]
+///
+/// - param line: text to render
+/// - param top_line: if the top line is to be written to
+/// - param offset: the current scroll position in characters; keep it between calls and reset it to 0 for new text
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the line could not be written
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{MockDelay, MockLcd};
+/// use gem_rs::rendering::render_scrolling;
+///
+/// // 18 characters, though `°` and `↑` take up more than one byte each
+/// let line = "Temp: 72°F ↑ 3°/hr";
+/// let mut lcd: MockLcd = MockLcd::new();
+/// let mut offset = 0;
+/// for window in [
+///     "Temp: 72°F ↑ 3°/",
+///     "emp: 72°F ↑ 3°/h",
+///     "mp: 72°F ↑ 3°/hr",
+///     "Temp: 72°F ↑ 3°/", // Back to the start
+/// ] {
+///     render_scrolling(line, true, &mut offset, &mut lcd, &mut MockDelay).unwrap();
+///     assert_eq!(lcd.row(0).as_str(), window);
+/// }
+/// ```
+pub fn render_scrolling<D: DisplaySurface>(
+    line: &str,
+    top_line: bool,
+    offset: &mut usize,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    let width = D::COLS as usize;
+    let length = line.chars().count();
+    if length <= width {
+        *offset = 0;
+        render_screen(line, top_line, lcd, delay)?;
+        return Ok(());
+    }
+
+    // Restart once the end of the line has been shown
+    if *offset + width > length {
+        *offset = 0;
+    }
+    let start = line
+        .char_indices()
+        .nth(*offset)
+        .map_or(0, |(index, _)| index);
+    let (window, _) = fit_to_width(&line[start..], width);
+    render_screen(window, top_line, lcd, delay)?;
+    *offset += 1;
+    Ok(())
+}
+
+/// Renders a fault's message with its code underneath: `Code E22`
+///
+/// - param error: The [GemError] to show
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the screen could not be written
+pub fn render_fault<D: DisplaySurface>(
+    error: GemError,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    render_screen(error.message(), true, lcd, delay)?;
+    let mut code: String<16> = String::new();
+    uwrite!(code, "{} E{}", FAULT_CODE, error.code()).unwrap();
+    render_screen(&code, false, lcd, delay)?;
+    Ok(())
+}
+
+/// Renders the boot splash: the firmware's name with its version underneath
+///
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the screen could not be written
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{MockDelay, MockLcd};
+/// use gem_rs::rendering::render_splash;
+///
+/// let mut lcd: MockLcd = MockLcd::new();
+/// render_splash(&mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(lcd.row(0).as_str(), "gem-rs          ");
+/// assert!(lcd.row(1).starts_with("v1."));
+/// ```
+pub fn render_splash<D: DisplaySurface>(
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    render_screen(FIRMWARE_NAME, true, lcd, delay)?;
+    render_screen(FIRMWARE_VERSION, false, lcd, delay)?;
+    Ok(())
 }
 
 /// Renders the Preferences on screen with a `^` cursor
 ///
 /// - param line: The preferences line
 /// - param left_cursor: If the lower bound is selected
-/// - param lcd: [Lcd] instance
-/// - param delay: [Timer] instance
-pub fn render_edit_screen<const N: usize>(
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the screen could not be written
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{MockDelay, MockLcd};
+/// use gem_rs::rendering::render_edit_screen;
+/// use heapless::String;
+///
+/// let mut line: String<16> = String::new();
+/// line.push_str("Temp: 60F - 80F").unwrap();
+///
+/// // The lower bound is at the left edge
+/// let mut lcd: MockLcd = MockLcd::new();
+/// render_edit_screen(&line, true, &mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(lcd.row(0).as_str(), "Temp: 60F - 80F ");
+/// assert_eq!(lcd.row(1).as_str(), "^               ");
+///
+/// // The upper bound is at the right edge
+/// render_edit_screen(&line, false, &mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(lcd.row(1).as_str(), "               ^");
+///
+/// // A wider display puts it at its own right edge
+/// let mut lcd: MockLcd<20, 4> = MockLcd::new();
+/// render_edit_screen(&line, false, &mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(lcd.row(1).as_str(), "                   ^");
+/// ```
+pub fn render_edit_screen<D: DisplaySurface, const N: usize>(
     line: &String<N>,
     left_cursor: bool,
-    lcd: &mut Lcd,
-    delay: &mut Timer,
-) {
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
     // Clear
-    lcd.clear(delay).unwrap();
+    lcd.clear(delay)?;
 
     // Write top info
-    lcd.set_cursor_pos(0, delay).unwrap();
-    lcd.write_str(line, delay).unwrap();
+    lcd.set_cursor((0, 0), delay)?;
+    write_localized(line, lcd, delay)?;
 
     // Create selection cursor
     if left_cursor {
-        render_selector(true, 0, lcd, delay);
+        render_selector(true, 0, lcd, delay)
     } else {
-        render_selector(false, 0, lcd, delay);
-        render_selector(true, 15, lcd, delay);
+        render_selector(false, 0, lcd, delay)?;
+        render_selector(true, D::COLS - 1, lcd, delay)
     }
 }
 
@@ -76,155 +883,126 @@ pub fn render_edit_screen<const N: usize>(
 ///
 /// - param line: The preferences line
 /// - param index: If index of the element being edited
-/// - param lcd: [Lcd] instance
-/// - param delay: Timer instance
-pub fn render_watering_edit_screen<const N: usize>(
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the screen could not be written
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::mock_lcd::{MockDelay, MockLcd};
+/// use gem_rs::rendering::render_watering_edit_screen;
+/// use heapless::String;
+///
+/// let mut line: String<16> = String::new();
+/// line.push_str("06:00 - 07:30").unwrap();
+///
+/// // The selector sits under the first digit of each field
+/// let mut lcd: MockLcd = MockLcd::new();
+/// for (index, expected) in [
+///     "^               ", // Start hour
+///     "   ^            ", // Start minute
+///     "        ^       ", // End hour
+///     "           ^    ", // End minute
+/// ]
+/// .iter()
+/// .enumerate()
+/// {
+///     render_watering_edit_screen(&line, index as i32, &mut lcd, &mut MockDelay).unwrap();
+///     assert_eq!(lcd.row(0).as_str(), "06:00 - 07:30   ");
+///     assert_eq!(lcd.row(1).as_str(), *expected);
+/// }
+///
+/// // Out of range indexes stay on the nearest field
+/// render_watering_edit_screen(&line, 9, &mut lcd, &mut MockDelay).unwrap();
+/// assert_eq!(lcd.row(1).as_str(), "           ^    ");
+/// ```
+pub fn render_watering_edit_screen<D: DisplaySurface, const N: usize>(
     line: &String<N>,
     index: i32,
-    lcd: &mut Lcd,
-    delay: &mut Timer,
-) {
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
     // Clear
-    lcd.clear(delay).unwrap();
+    lcd.clear(delay)?;
 
     // Write top info
-    lcd.set_cursor_pos(0, delay).unwrap();
-    lcd.write_str(line, delay).unwrap();
+    lcd.set_cursor((0, 0), delay)?;
+    write_localized(line, lcd, delay)?;
 
-    // Create selection cursor
-    match index {
-        1 => {
-            render_selector(false, 0, lcd, delay);
-            render_selector(true, 3, lcd, delay);
-        }
-        0 => {
-            render_selector(true, 0, lcd, delay);
-        }
-        2 => {
-            render_selector(false, 3, lcd, delay);
-            render_selector(true, 8, lcd, delay);
-        }
-        _ => {
-            render_selector(false, 8, lcd, delay);
-            render_selector(true, 11, lcd, delay);
-        }
+    // Create selection cursor, erasing the previous field's
+    let field = index.clamp(0, 3) as usize;
+    if field > 0 {
+        render_selector(false, WATERING_FIELD_COLUMNS[field - 1], lcd, delay)?;
     }
+    render_selector(true, WATERING_FIELD_COLUMNS[field], lcd, delay)
+}
+
+/// Renders the watering days editing screen with a `^` cursor under the day being edited
+///
+/// - param line: The days line: `Days: SMTWTFS`
+/// - param day: The day being edited: 0 = Sunday, 6 = Saturday
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+/// - param delay: Delay instance
+///
+/// returns [GemError::Display] if the screen could not be written
+pub fn render_days_edit_screen<D: DisplaySurface, const N: usize>(
+    line: &String<N>,
+    day: u8,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    // Clear
+    lcd.clear(delay)?;
+
+    // Write top info
+    lcd.set_cursor((0, 0), delay)?;
+    write_localized(line, lcd, delay)?;
+
+    // Create selection cursor
+    render_selector(true, DAYS_FIELD_COLUMN + day.min(6), lcd, delay)
 }
 
 /// Renders the current date unit `(min, hr, day, etc.)` on the first line with a `^` cursor on the second line
 ///
 /// - param line: The date line
-/// - param lcd: [Lcd] instance
-pub fn render_date_edit_screen<const N: usize>(line: &String<N>, lcd: &mut Lcd, delay: &mut Timer) {
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+///
+/// returns [GemError::Display] if the screen could not be written
+pub fn render_date_edit_screen<D: DisplaySurface, const N: usize>(
+    line: &String<N>,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
     // Clear
-    lcd.clear(delay).unwrap();
+    lcd.clear(delay)?;
 
     // Write date segment
-    lcd.set_cursor_pos(0, delay).unwrap();
-    lcd.write_str(line, delay).unwrap();
+    lcd.set_cursor((0, 0), delay)?;
+    write_localized(line, lcd, delay)?;
 
     // Create selection cursor
-    render_selector(true, 7, lcd, delay);
+    render_selector(true, 7, lcd, delay)
 }
 
-/// Renders a `^` on the bottom line at the specified position
+/// Renders a `^` on the line below the edited line at the specified position
 ///
 /// - param active: whether to add a `^`
-/// - param bottom_pos: the x-coordinate on the bottom row
-/// - param lcd: [Lcd] instance
-pub fn render_selector(active: bool, bottom_pos: u8, lcd: &mut Lcd, delay: &mut Timer) {
-    lcd.set_cursor_xy((bottom_pos, 1), delay).unwrap();
+/// - param bottom_pos: the x-coordinate on the second row; clamped to the last column
+/// - param lcd: [DisplaySurface] instance, e.g. [Lcd]
+///
+/// returns [GemError::Display] if the selector could not be written
+pub fn render_selector<D: DisplaySurface>(
+    active: bool,
+    bottom_pos: u8,
+    lcd: &mut D,
+    delay: &mut impl DelayNs,
+) -> Result<(), GemError> {
+    lcd.set_cursor((bottom_pos.min(D::COLS - 1), 1), delay)?;
     if active {
-        lcd.write_str("^", delay).unwrap();
+        lcd.write_str("^", delay)?;
     } else {
-        lcd.write_str(" ", delay).unwrap();
-    }
-}
-
-/// Renders configuration screens for various parts of the date system
-///
-/// - param unit: The current unit; Ex: Minutes
-/// - param info_str: [String] for data
-/// - param min: The minimum value for the unit
-/// - param max: The maximum value for the unit
-/// - param preference: Current variable being assigned
-/// - param preferences: [Preferences] instance
-/// - param lcd: [Lcd] instance
-/// - param delay: [Timer] instance
-/// - param up_button: Up button instance
-/// - param down_button: Down button instance
-/// - param select_button: Select button instance
-///
-/// returns the inputted preference value after modification
-///
-/// ## Example:
-/// ```rust
-/// use rp_pico::hal::Timer;
-/// use gem_rs::preferences::Preferences;
-/// use gem_rs::rendering::{render_time_config_screen, Lcd};
-///
-/// let mut preferences = Preferences::default();
-/// let mut info_str: heapless::String<11>; // Must be a heapless String with size 11
-/// let mut lcd: Lcd;
-/// let mut delay: Timer;
-/// let mut up_button;     // GPIO
-/// let mut down_button;   // GPIO
-/// let mut select_button; // GPIO
-///
-/// preferences.date.1 = render_time_config_screen( // Set the Minutes to the return value
-///     "Minute",           // Name of the unit is "Minute"
-///     &mut info_str,
-///     0,                  // The minimum minute value is 0
-///     59,                 // The maximum minute value is 59
-///     preferences.date.1, // Pass the minute variable
-///     &mut preferences,
-///     &mut lcd,
-///     &mut delay,
-///     &mut up_button,
-///     &mut down_button,
-///     &mut select_button,
-///  );
-/// ```
-#[allow(clippy::too_many_arguments)]
-pub fn render_time_config_screen(
-    unit: &str,
-    info_str: &mut String<11>,
-    min: u8,
-    max: u8,
-    mut preference: u8,
-    preferences: &mut Preferences,
-    lcd: &mut Lcd,
-    delay: &mut Timer,
-    up_button: &mut Pin<Gpio10, FunctionSio<SioInput>, PullDown>,
-    down_button: &mut Pin<Gpio11, FunctionSio<SioInput>, PullDown>,
-    select_button: &mut Pin<Gpio12, FunctionSio<SioInput>, PullDown>,
-) -> u8 {
-    let mut refresh: bool = true;
-    let mut update_date: bool = false;
-    loop {
-        if refresh {
-            uwrite!(info_str, "{}: {}", unit, preference).unwrap();
-            render_date_edit_screen(info_str, lcd, delay);
-            info_str.clear();
-            refresh = false;
-        }
-
-        delay.delay_ms(500);
-
-        if update_date {
-            preferences.tick_time();
-        }
-        update_date = !update_date;
-
-        if up_button.is_high().unwrap() {
-            preference = inclusive_iterator(preference, min, max, true);
-            refresh = true;
-        } else if down_button.is_high().unwrap() {
-            preference = inclusive_iterator(preference, min, max, false);
-            refresh = true;
-        } else if select_button.is_high().unwrap() {
-            break;
-        }
+        lcd.write_str(" ", delay)?;
     }
-    preference
+    Ok(())
 }
@@ -0,0 +1,140 @@
+use embedded_hal::i2c::I2c;
+
+use crate::datetime::{DateTime, MAX_YEAR, MIN_YEAR};
+
+use panic_probe as _;
+
+/// The DS3231's I2C address
+pub const DS3231_ADDRESS: u8 = 0x68;
+/// The first timekeeping register (seconds)
+const TIME_REGISTER: u8 = 0x00;
+/// Set in the month register when the year is past 2099
+const CENTURY_BIT: u8 = 0b1000_0000;
+
+/// Errors that can occur while talking to the RTC
+///
+/// - **Bus**: The RTC did not respond on the I2C bus
+/// - **InvalidDate**: The date cannot be stored in or was not read from the RTC correctly
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RtcError {
+    Bus,
+    InvalidDate,
+}
+
+/// A DS3231 real-time clock
+///
+/// The DS3231 handles leap years itself for the years 2000-2199
+///
+/// ## Example:
+/// ```rust
+/// use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+/// use gem_rs::datetime::DateTime;
+/// use gem_rs::rtc::{Rtc, DS3231_ADDRESS};
+///
+/// // The time registers in BCD: seconds, minutes, hours, weekday, day, month, year
+/// let registers = vec![0x00, 0x30, 0x12, 0x01, 0x01, 0x06, 0x24];
+/// let mut i2c = Mock::new(&[
+///     Transaction::write(DS3231_ADDRESS, [vec![0x00], registers.clone()].concat()),
+///     Transaction::write_read(DS3231_ADDRESS, vec![0x00], registers),
+/// ]);
+/// let mut rtc = Rtc::new(&mut i2c);
+///
+/// let noon = DateTime::new(2024, 6, 1, 12, 30, 0); // 12:30:00 Jun 1 2024
+/// rtc.write_date(&noon).unwrap();
+/// assert_eq!(rtc.read_date().unwrap(), noon);
+/// i2c.done();
+/// ```
+pub struct Rtc<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C: I2c> Rtc<I2C> {
+    /// Creates a new instance of Rtc
+    ///
+    /// - param i2c: The I2C bus the DS3231 is on
+    ///
+    /// returns a new instance of Rtc
+    pub fn new(i2c: I2C) -> Rtc<I2C> {
+        Self { i2c }
+    }
+
+    /// Reads the current date and time
+    ///
+    /// returns the [DateTime]
+    pub fn read_date(&mut self) -> Result<DateTime, RtcError> {
+        let mut registers = [0; 7];
+        self.i2c
+            .write_read(DS3231_ADDRESS, &[TIME_REGISTER], &mut registers)
+            .map_err(|_| RtcError::Bus)?;
+
+        let year =
+            2000 + if registers[5] & CENTURY_BIT != 0 {
+                100
+            } else {
+                0
+            } + bcd_to_bin(registers[6]) as u16;
+        let date = DateTime {
+            second: bcd_to_bin(registers[0] & 0x7F),
+            minute: bcd_to_bin(registers[1] & 0x7F),
+            hour: bcd_to_bin(registers[2] & 0x3F), // Always kept in 24-hour mode
+            day: bcd_to_bin(registers[4] & 0x3F),
+            month: bcd_to_bin(registers[5] & 0x1F),
+            year,
+        };
+
+        // A chip that lost power may hold garbage
+        if !date.is_valid() {
+            return Err(RtcError::InvalidDate);
+        }
+        Ok(date)
+    }
+
+    /// Sets the current date and time
+    ///
+    /// - param date: The [DateTime] to set
+    ///
+    /// returns [RtcError::InvalidDate] if the year is outside 2000-2199
+    pub fn write_date(&mut self, date: &DateTime) -> Result<(), RtcError> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&date.year) {
+            return Err(RtcError::InvalidDate);
+        }
+        let years_since_2000 = date.year - 2000;
+        let century = if years_since_2000 >= 100 {
+            CENTURY_BIT
+        } else {
+            0
+        };
+
+        let registers = [
+            TIME_REGISTER,
+            bin_to_bcd(date.second),
+            bin_to_bcd(date.minute),
+            bin_to_bcd(date.hour), // 24-hour mode
+            1,                     // The day of the week is unused
+            bin_to_bcd(date.day),
+            bin_to_bcd(date.month) | century,
+            bin_to_bcd((years_since_2000 % 100) as u8),
+        ];
+        self.i2c
+            .write(DS3231_ADDRESS, &registers)
+            .map_err(|_| RtcError::Bus)
+    }
+}
+
+/// Converts a binary-coded decimal byte to binary
+///
+/// - param bcd: The BCD value
+///
+/// returns the binary value
+fn bcd_to_bin(bcd: u8) -> u8 {
+    (bcd >> 4) * 10 + (bcd & 0x0F)
+}
+
+/// Converts a binary byte (<100) to binary-coded decimal
+///
+/// - param bin: The binary value
+///
+/// returns the BCD value
+fn bin_to_bcd(bin: u8) -> u8 {
+    ((bin / 10) << 4) | (bin % 10)
+}
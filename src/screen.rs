@@ -0,0 +1,230 @@
+use crate::preferences::Preferences;
+use crate::zone::ZONE_COUNT;
+
+use panic_probe as _;
+
+/// The screens shown on the LCD, in the order UP cycles through them
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::screen::Screen;
+///
+/// let mut screen = Screen::Temperature;
+/// for _ in 0..Screen::ALL.len() {
+///     screen = screen.next();
+/// }
+/// assert_eq!(screen, Screen::Temperature); // Wrapped back to the start
+///
+/// for _ in 0..Screen::ALL.len() {
+///     screen = screen.prev();
+/// }
+/// assert_eq!(screen, Screen::Temperature);
+///
+/// assert_eq!(Screen::Temperature.prev(), Screen::Diagnostics);
+/// assert_eq!(Screen::Diagnostics.next(), Screen::Temperature);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Screen {
+    Temperature,
+    Humidity,
+    Pressure,
+    AirQuality,
+    Date,
+    Watering,
+    Soil,
+    Light,
+    Ambient,
+    DewPoint,
+    Vpd,
+    Sensors,
+    Stats,
+    History,
+    Graph,
+    Overrides,
+    Calibration,
+    Margins,
+    Features,
+    Zone,
+    Reset,
+    Uptime,
+    Battery,
+    Memory,
+    Diagnostics,
+}
+
+impl Screen {
+    /// Every screen, in display order
+    pub const ALL: [Screen; 25] = [
+        Screen::Temperature,
+        Screen::Humidity,
+        Screen::Pressure,
+        Screen::AirQuality,
+        Screen::Date,
+        Screen::Watering,
+        Screen::Soil,
+        Screen::Light,
+        Screen::Ambient,
+        Screen::DewPoint,
+        Screen::Vpd,
+        Screen::Sensors,
+        Screen::Stats,
+        Screen::History,
+        Screen::Graph,
+        Screen::Overrides,
+        Screen::Calibration,
+        Screen::Margins,
+        Screen::Features,
+        Screen::Zone,
+        Screen::Reset,
+        Screen::Uptime,
+        Screen::Battery,
+        Screen::Memory,
+        Screen::Diagnostics,
+    ];
+
+    /// Gets the screen after this one, wrapping to the first
+    ///
+    /// returns the next Screen
+    pub fn next(self) -> Screen {
+        Self::ALL[(self as usize + 1) % Self::ALL.len()]
+    }
+
+    /// Gets the screen before this one, wrapping to the last
+    ///
+    /// returns the previous Screen
+    pub fn prev(self) -> Screen {
+        Self::ALL[(self as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Checks if this screen belongs to a feature that is in use.
+    /// Screens for optional features are hidden until they are turned on from [Screen::Features],
+    /// [Screen::Zone] is only shown when there is more than one zone, [Screen::Sensors] only when there is one,
+    /// [Screen::Ambient] only when built with the `light-sensor` feature,
+    /// and [Screen::Memory] only when built with the `stack-usage` feature
+    ///
+    /// - param preferences: [Preferences] instance
+    ///
+    /// returns true if the screen should be shown
+    pub fn is_enabled(self, preferences: &Preferences) -> bool {
+        match self {
+            Screen::Watering => !preferences.watering.is_empty(),
+            Screen::Soil => preferences.soil_calibration.is_some(),
+            Screen::Light => preferences.light.is_some(),
+            Screen::Zone => ZONE_COUNT > 1,
+            Screen::Sensors => ZONE_COUNT == 1,
+            Screen::Ambient => cfg!(feature = "light-sensor"),
+            Screen::Memory => cfg!(feature = "stack-usage"),
+            _ => true,
+        }
+    }
+
+    /// Gets the next screen that is enabled, skipping disabled ones
+    ///
+    /// - param preferences: [Preferences] instance
+    ///
+    /// returns the next enabled Screen
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::{LightWindow, Preferences};
+    /// use gem_rs::screen::Screen;
+    ///
+    /// let mut preferences = Preferences::default(); // No watering, soil sensor, or light
+    /// assert_eq!(Screen::Date.next_enabled(&preferences), Screen::DewPoint);
+    /// assert_eq!(Screen::DewPoint.prev_enabled(&preferences), Screen::Date);
+    ///
+    /// preferences.light = Some(LightWindow::default());
+    /// assert_eq!(Screen::Date.next_enabled(&preferences), Screen::Light);
+    /// assert_eq!(Screen::DewPoint.prev_enabled(&preferences), Screen::Light);
+    ///
+    /// // Only enabled screens are visited, and both directions wrap back to the start
+    /// let mut screen = Screen::Temperature;
+    /// let mut visited = 0;
+    /// loop {
+    ///     screen = screen.next_enabled(&preferences);
+    ///     visited += 1;
+    ///     assert!(screen.is_enabled(&preferences));
+    ///     if screen == Screen::Temperature {
+    ///         break;
+    ///     }
+    /// }
+    /// // Watering, Soil, and Zone were skipped, and Ambient and Memory unless built with their features
+    /// let enabled = Screen::ALL.iter().filter(|screen| screen.is_enabled(&preferences));
+    /// assert_eq!(visited, enabled.count());
+    /// for _ in 0..visited {
+    ///     screen = screen.prev_enabled(&preferences);
+    /// }
+    /// assert_eq!(screen, Screen::Temperature);
+    /// ```
+    pub fn next_enabled(self, preferences: &Preferences) -> Screen {
+        let mut screen = self.next();
+        while !screen.is_enabled(preferences) {
+            screen = screen.next();
+        }
+        screen
+    }
+
+    /// Gets the previous screen that is enabled, skipping disabled ones
+    ///
+    /// - param preferences: [Preferences] instance
+    ///
+    /// returns the previous enabled Screen
+    pub fn prev_enabled(self, preferences: &Preferences) -> Screen {
+        let mut screen = self.prev();
+        while !screen.is_enabled(preferences) {
+            screen = screen.prev();
+        }
+        screen
+    }
+
+    /// Checks if this screen is for changing settings or switching zones rather than reading,
+    /// so cycling through the screens on their own skips it
+    ///
+    /// returns true for [Screen::Overrides], [Screen::Calibration], [Screen::Margins],
+    /// [Screen::Features], [Screen::Zone], and [Screen::Reset]
+    pub fn is_settings(self) -> bool {
+        matches!(
+            self,
+            Screen::Overrides
+                | Screen::Calibration
+                | Screen::Margins
+                | Screen::Features
+                | Screen::Zone
+                | Screen::Reset
+        )
+    }
+
+    /// Gets the next screen to show while the screens cycle on their own;
+    /// see [Preferences::auto_cycle_seconds]. Disabled and settings screens are skipped
+    ///
+    /// - param preferences: [Preferences] instance
+    ///
+    /// returns the next enabled Screen that is not a settings screen
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::preferences::Preferences;
+    /// use gem_rs::screen::Screen;
+    ///
+    /// let preferences = Preferences::default();
+    /// assert_eq!(Screen::Temperature.next_cycled(&preferences), Screen::Humidity);
+    /// assert_eq!(Screen::Graph.next_cycled(&preferences), Screen::Uptime);
+    ///
+    /// // Every reading comes up in turn, and no settings screen does
+    /// let mut screen = Screen::Temperature;
+    /// loop {
+    ///     screen = screen.next_cycled(&preferences);
+    ///     assert!(screen.is_enabled(&preferences) && !screen.is_settings());
+    ///     if screen == Screen::Temperature {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    pub fn next_cycled(self, preferences: &Preferences) -> Screen {
+        let mut screen = self.next_enabled(preferences);
+        while screen.is_settings() {
+            screen = screen.next_enabled(preferences);
+        }
+        screen
+    }
+}
@@ -0,0 +1,135 @@
+use heapless::{String, Vec};
+use ufmt::uwrite;
+
+use crate::messages::{FAIL, PASS, TEST_DISPLAY, TEST_OUTPUTS, TEST_SENSOR};
+use crate::zone::ZONE_COUNT;
+
+use panic_probe as _;
+
+/// The firmware's name, shown on the boot splash
+pub const FIRMWARE_NAME: &str = env!("CARGO_PKG_NAME");
+/// The firmware's version, shown on the boot splash: `v1.0.0`
+pub const FIRMWARE_VERSION: &str = concat!("v", env!("CARGO_PKG_VERSION"));
+
+/// How long the boot splash stays up in milliseconds
+pub const SPLASH_MS: u32 = 1500;
+/// How long each passed self-test result stays up in milliseconds
+pub const RESULT_MS: u32 = 700;
+/// How long each failed self-test result stays up in milliseconds, so it is not missed
+pub const FAILED_RESULT_MS: u32 = 3000;
+/// How long each output is switched on while it is tested in milliseconds; long enough to hear a relay click
+pub const TOGGLE_MS: u32 = 150;
+
+/// How many results a [SelfTest] has: the display, then each zone's sensor and outputs
+pub const SELF_TEST_RESULTS: usize = 1 + 2 * ZONE_COUNT;
+
+/// What the boot self-test found, so an installer can see the unit is healthy before leaving it
+///
+/// - **display**: Whether the LCD was set up
+/// - **sensors**: Whether each zone's BME680 answered and was set up
+/// - **outputs**: Whether each zone's relays switched on and off; see [crate::zone::Zone::test_outputs()]
+///
+/// A missing sensor is critical: its zone has nothing to act on, so it is held in its safe state
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::selftest::SelfTest;
+///
+/// let mut test = SelfTest::new(true);
+/// test.sensors[0] = true;
+/// test.outputs[0] = true;
+/// assert!(test.passed());
+/// assert!(!test.is_critical());
+///
+/// let lines = test.results();
+/// assert_eq!(lines[0].0.as_str(), "LCD: PASS");
+/// assert_eq!(lines[1].0.as_str(), "BME680: PASS");
+/// assert_eq!(lines[2].0.as_str(), "Outputs: PASS");
+///
+/// // Running headless is not critical, but a missing sensor is
+/// let mut test = SelfTest::new(false);
+/// test.outputs[0] = true;
+/// assert!(!test.passed());
+/// assert!(test.is_critical());
+/// let (line, passed) = &test.results()[1];
+/// assert_eq!(line.as_str(), "BME680: FAIL");
+/// assert!(!passed);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelfTest {
+    pub display: bool,
+    pub sensors: [bool; ZONE_COUNT],
+    pub outputs: [bool; ZONE_COUNT],
+}
+
+impl SelfTest {
+    /// Creates a new SelfTest with nothing in any zone checked yet
+    ///
+    /// - param display: Whether the LCD was set up
+    ///
+    /// returns a new instance of SelfTest
+    pub fn new(display: bool) -> SelfTest {
+        Self {
+            display,
+            sensors: [false; ZONE_COUNT],
+            outputs: [false; ZONE_COUNT],
+        }
+    }
+
+    /// Checks if every subsystem passed
+    ///
+    /// returns true if nothing failed
+    pub fn passed(&self) -> bool {
+        self.display && self.sensors.iter().chain(self.outputs.iter()).all(|ok| *ok)
+    }
+
+    /// Checks if a zone cannot be looked after, because its sensor is missing
+    ///
+    /// returns true if any zone's BME680 failed
+    pub fn is_critical(&self) -> bool {
+        self.sensors.iter().any(|ok| !ok)
+    }
+
+    /// Formats every result for the LCD, in the order they are shown: `BME680: PASS`.
+    /// With several zones, each zone's results are numbered: `BME680 2: FAIL`
+    ///
+    /// returns each result's line and whether it passed
+    pub fn results(&self) -> Vec<(String<16>, bool), SELF_TEST_RESULTS> {
+        let mut lines = Vec::new();
+        lines
+            .push((
+                format_result(TEST_DISPLAY, None, self.display),
+                self.display,
+            ))
+            .unwrap();
+        for zone in 0..ZONE_COUNT {
+            let number = (ZONE_COUNT > 1).then_some(zone + 1);
+            for (name, passed) in [
+                (TEST_SENSOR, self.sensors[zone]),
+                (TEST_OUTPUTS, self.outputs[zone]),
+            ] {
+                lines
+                    .push((format_result(name, number, passed), passed))
+                    .unwrap();
+            }
+        }
+        lines
+    }
+}
+
+/// Formats one self-test result: `BME680: PASS`, or `BME680 2: PASS` for a numbered zone
+///
+/// - param name: The subsystem's name
+/// - param zone: The zone's number, starting at 1, or None if there is only one
+/// - param passed: Whether the subsystem passed
+///
+/// returns a [String] of length 16 containing the formatted result
+fn format_result(name: &str, zone: Option<usize>, passed: bool) -> String<16> {
+    let mut str: String<16> = String::new();
+    let result = if passed { PASS } else { FAIL };
+    match zone {
+        Some(number) => uwrite!(str, "{} {}: {}", name, number, result).unwrap(),
+        None => uwrite!(str, "{}: {}", name, result).unwrap(),
+    }
+    str
+}
@@ -1,50 +1,410 @@
-use bme680::{Bme680, FieldData, FieldDataCondition, PowerMode};
+use bme680::{
+    Bme680, FieldData, I2CAddress, IIRFilterSize, OversamplingSetting, PowerMode, SettingsBuilder,
+};
+use core::cell::RefCell;
+use core::time::Duration;
 use embedded_hal::delay::DelayNs;
 use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::I2c;
+use embedded_hal_bus::i2c::RefCellDevice;
+use heapless::{Deque, String, Vec};
 use i2c_pio::I2C;
-use rp_pico::hal::gpio::bank0::{Gpio6, Gpio8, Gpio9};
-use rp_pico::hal::gpio::{FunctionNull, FunctionSio, Pin, PullDown, SioOutput};
+use rp_pico::hal::gpio::bank0::{Gpio8, Gpio9};
+use rp_pico::hal::gpio::{FunctionNull, Pin, PullDown};
 use rp_pico::hal::pio::SM0;
 use rp_pico::hal::Timer;
 use rp_pico::pac::PIO0;
+use ufmt::{uDisplay, uWrite, uwrite, Formatter};
+
+use crate::buzzer::{play_pattern, SENSOR_FAULT_PATTERN};
+use crate::datetime::pad_number;
+use crate::messages::{INCHES_OF_MERCURY, MILLIBARS};
+use crate::preferences::MAX_SMOOTHING;
+use crate::telemetry;
 
 use panic_probe as _;
 
-pub type Bme<'a> = Bme680<
-    I2C<'a, PIO0, SM0, Pin<Gpio8, FunctionNull, PullDown>, Pin<Gpio9, FunctionNull, PullDown>>,
-    Timer,
->;
+/// The I2C bus the BME sensor is on
+pub type BmeBus<'a> =
+    I2C<'a, PIO0, SM0, Pin<Gpio8, FunctionNull, PullDown>, Pin<Gpio9, FunctionNull, PullDown>>;
+
+/// The BME sensor. It shares its [BmeBus] so the bus can still be probed with [sensor_present()]
+pub type Bme<'a, D = Timer> = Bme680<RefCellDevice<'a, BmeBus<'a>>, D>;
+
+/// The BME680's I2C address with SDO pulled high (0x77)
+pub const BME680_ADDRESS: I2CAddress = I2CAddress::Secondary;
+/// The BME680's I2C address with SDO pulled low (0x76), so a second sensor can share the bus
+pub const BME680_ALT_ADDRESS: I2CAddress = I2CAddress::Primary;
+/// Both BME680 addresses, in the order [detect_address()] tries them.
+/// Breakout boards ship strapped to either one
+pub const BME680_ADDRESSES: [I2CAddress; 2] = [BME680_ALT_ADDRESS, BME680_ADDRESS];
+/// The register holding the chip ID
+const CHIP_ID_REGISTER: u8 = 0xD0;
+/// The chip ID every BME680 reports
+pub const BME680_CHIP_ID: u8 = 0x61;
+/// How many times setting up the sensor is attempted before giving up
+pub const SENSOR_INIT_RETRIES: u8 = 3;
+/// The most BME680s a [SensorGroup] reads: one at each of the [BME680_ADDRESSES]
+pub const MAX_SENSORS: usize = BME680_ADDRESSES.len();
+
+/// Errors that can occur while reading the BME sensor
+///
+/// - **ModeSet**: The sensor could not be put into Forced mode
+/// - **Read**: The sensor data could not be read
+/// - **Setup**: The sensor answered but could not be set up
+/// - **Absent**: No BME680 answered on the bus
+/// - **NoAddress**: No BME680 answered at either of the [BME680_ADDRESSES]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorError {
+    ModeSet,
+    Read,
+    Setup,
+    Absent,
+    NoAddress,
+}
+
+/// Checks if a BME680 is on the bus by reading its chip ID.
+/// Use this after a failed read to tell a missing sensor apart from a transient error
+///
+/// - param i2c: The I2C bus the sensor is on
+/// - param address: The sensor's address, e.g. [BME680_ADDRESS]
+///
+/// returns true if the sensor answered with [BME680_CHIP_ID]
+pub fn sensor_present(i2c: &mut impl I2c, address: I2CAddress) -> bool {
+    let mut chip_id = [0];
+    i2c.write_read(address.addr(), &[CHIP_ID_REGISTER], &mut chip_id)
+        .is_ok()
+        && chip_id[0] == BME680_CHIP_ID
+}
+
+/// Finds which address the BME680 answers at, trying each of the [BME680_ADDRESSES]
+///
+/// - param i2c: The I2C bus the sensor is on
+///
+/// returns the address that answered, or [SensorError::NoAddress] if neither did
+pub fn detect_address(i2c: &mut impl I2c) -> Result<I2CAddress, SensorError> {
+    BME680_ADDRESSES
+        .into_iter()
+        .find(|address| sensor_present(i2c, *address))
+        .ok_or(SensorError::NoAddress)
+}
+
+/// Sets up the BME sensor, retrying up to [SENSOR_INIT_RETRIES] times.
+/// This is also used to set the sensor up again after it reconnects
+///
+/// - param bus: The shared [BmeBus]
+/// - param delayer: BME sensor delay
+/// - param temp_offset: The temperature correction in tenths of a degree Celsius
+/// - param address: The sensor's address, e.g. [BME680_ADDRESS]
+///
+/// returns the [Bme], or [SensorError::Absent] if no sensor answered, or
+/// [SensorError::Setup] if it answered but could not be set up
+pub fn init_bme<'a, D: DelayNs>(
+    bus: &'a RefCell<BmeBus<'a>>,
+    delayer: &mut D,
+    temp_offset: i16,
+    address: I2CAddress,
+) -> Result<Bme<'a, D>, SensorError> {
+    for attempt in 0..SENSOR_INIT_RETRIES {
+        if attempt > 0 {
+            delayer.delay_ms(100);
+        }
+        let Ok(mut bme) = Bme680::init(RefCellDevice::new(bus), delayer, address) else {
+            continue;
+        };
+
+        if set_temperature_offset(&mut bme, temp_offset, delayer).is_ok()
+            && prep_bme(&mut bme, delayer).is_ok()
+        {
+            return Ok(bme);
+        }
+    }
+
+    if sensor_present(&mut RefCellDevice::new(bus), address) {
+        Err(SensorError::Setup)
+    } else {
+        Err(SensorError::Absent)
+    }
+}
+
+/// Applies the sensor settings with a new temperature offset.
+/// The offset corrects every reading, including the humidity and dew point
+///
+/// - param bme: [Bme] sensor instance
+/// - param temp_offset: The temperature correction in tenths of a degree Celsius
+/// - param delayer: BME sensor delay
+///
+/// returns [SensorError::Setup] if the settings could not be applied
+pub fn set_temperature_offset<I2C: I2c, D: DelayNs>(
+    bme: &mut Bme680<I2C, D>,
+    temp_offset: i16,
+    delayer: &mut D,
+) -> Result<(), SensorError> {
+    let settings = SettingsBuilder::new()
+        .with_humidity_oversampling(OversamplingSetting::OS2x)
+        .with_pressure_oversampling(OversamplingSetting::OS4x)
+        .with_temperature_oversampling(OversamplingSetting::OS8x)
+        .with_temperature_filter(IIRFilterSize::Size3)
+        .with_temperature_offset(temp_offset as f32 / 10.)
+        .with_gas_measurement(Duration::from_millis(1500), 320, 25)
+        .with_run_gas(true)
+        .build();
+    bme.set_sensor_settings(delayer, settings)
+        .map_err(|_| SensorError::Setup)
+}
 
 /// Gets [FieldData] from the BME sensor
 ///
 /// - param bme: [Bme] sensor instance
 /// - param delayer: BME sensor delay
-/// - param alarm: Buzzer Pin
 ///
-/// returns [FieldData]
-pub fn get_bme_data(
-    bme: &mut Bme,
-    delayer: &mut Timer,
-    alarm: &mut Pin<Gpio6, FunctionSio<SioOutput>, PullDown>,
-) -> FieldData {
-    prep_bme(bme, delayer, alarm);
+/// returns [FieldData], or a [SensorError] if the sensor could not be read
+pub fn get_bme_data<I2C: I2c, D: DelayNs>(
+    bme: &mut Bme680<I2C, D>,
+    delayer: &mut D,
+) -> Result<FieldData, SensorError> {
+    prep_bme(bme, delayer)?;
     bme.get_sensor_data(delayer)
-        .unwrap_or((FieldData::default(), FieldDataCondition::Unchanged))
-        .0
+        .map(|(data, _)| data)
+        .map_err(|_| SensorError::Read)
+}
+
+/// One of the BME680s in a [SensorGroup]
+///
+/// - **address**: The sensor's address, e.g. [BME680_ADDRESS]
+/// - **sensor**: The [Bme], or None while it is not connected
+/// - **reading**: The sensor's last reading, or None if its last read failed
+/// - **error**: Why the sensor's last read failed, or None if it succeeded
+pub struct SensorSlot<'a, D = Timer> {
+    pub address: I2CAddress,
+    pub sensor: Option<Bme<'a, D>>,
+    pub reading: Option<SensorReading>,
+    pub error: Option<SensorError>,
 }
 
-/// Gets temperature in Fahrenheit
+/// The BME680s spread around one zone. A large greenhouse is warmer in some spots than others,
+/// so the zone follows the [averaged()] reading of every sensor that could be read.
+/// A sensor failing only drops it from the average; the zone carries on with the rest
+///
+/// - **slots**: One [SensorSlot] per address the zone's sensors may be at
+pub struct SensorGroup<'a, D = Timer> {
+    slots: Vec<SensorSlot<'a, D>, MAX_SENSORS>,
+}
+
+impl<'a, D: DelayNs> SensorGroup<'a, D> {
+    /// Creates a new SensorGroup with no sensors connected. They are set up by [SensorGroup::connect()]
+    ///
+    /// - param addresses: The addresses the sensors may be at; any past [MAX_SENSORS] are ignored
+    ///
+    /// returns a new instance of SensorGroup
+    pub fn new(addresses: &[I2CAddress]) -> SensorGroup<'a, D> {
+        Self {
+            slots: addresses
+                .iter()
+                .take(MAX_SENSORS)
+                .map(|address| SensorSlot {
+                    address: *address,
+                    sensor: None,
+                    reading: None,
+                    error: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Sets up every sensor that answers but is not connected yet.
+    /// This is also used to set sensors up again after they reconnect
+    ///
+    /// - param bus: The shared [BmeBus]
+    /// - param delayer: BME sensor delay
+    /// - param temp_offset: The temperature correction in tenths of a degree Celsius
+    ///
+    /// returns true if a sensor was set up, false if there was none to set up;
+    /// or [SensorError::Setup] if one answered but could not be set up and none is connected,
+    /// or [SensorError::NoAddress] or [SensorError::Absent] if none answered
+    pub fn connect(
+        &mut self,
+        bus: &'a RefCell<BmeBus<'a>>,
+        delayer: &mut D,
+        temp_offset: i16,
+    ) -> Result<bool, SensorError> {
+        let mut connected = false;
+        let mut setup_failed = false;
+        for slot in self.slots.iter_mut().filter(|slot| slot.sensor.is_none()) {
+            if !sensor_present(&mut RefCellDevice::new(bus), slot.address) {
+                continue;
+            }
+            match init_bme(bus, delayer, temp_offset, slot.address) {
+                Ok(sensor) => {
+                    slot.sensor = Some(sensor);
+                    telemetry::sensor::connected(slot.address);
+                    connected = true;
+                }
+                Err(_) => setup_failed = true,
+            }
+        }
+        if self.is_connected() {
+            Ok(connected)
+        } else if setup_failed {
+            Err(SensorError::Setup)
+        } else if self.slots.len() > 1 {
+            Err(SensorError::NoAddress)
+        } else {
+            Err(SensorError::Absent)
+        }
+    }
+
+    /// Checks if any of the sensors is connected
+    ///
+    /// returns true if at least one sensor is set up
+    pub fn is_connected(&self) -> bool {
+        self.slots.iter().any(|slot| slot.sensor.is_some())
+    }
+
+    /// Gets every sensor, connected or not, with its last reading
+    ///
+    /// returns the [SensorSlot]s, in the order of the addresses given to [SensorGroup::new()]
+    pub fn slots(&self) -> &[SensorSlot<'a, D>] {
+        &self.slots
+    }
+
+    /// Reads every connected sensor. A sensor that failed to read is left out; if it no longer answers,
+    /// it is disconnected until [SensorGroup::connect()] sets it up again
+    ///
+    /// - param bus: The shared [BmeBus]
+    /// - param delayer: BME sensor delay
+    /// - param humidity_offset: The humidity correction in percent; see [offset_humidity()]
+    ///
+    /// returns the readings of every sensor that could be read; see [averaged()]
+    pub fn read_all(
+        &mut self,
+        bus: &'a RefCell<BmeBus<'a>>,
+        delayer: &mut D,
+        humidity_offset: i8,
+    ) -> Vec<SensorReading, MAX_SENSORS> {
+        let mut readings = Vec::new();
+        for slot in self.slots.iter_mut() {
+            let result = match slot.sensor.as_mut() {
+                Some(sensor) => get_bme_data(sensor, delayer),
+                None => Err(SensorError::Absent),
+            };
+            match result {
+                Ok(data) => {
+                    let reading = SensorReading::from_field_data(&data, humidity_offset);
+                    slot.reading = Some(reading);
+                    slot.error = None;
+                    readings.push(reading).ok();
+                }
+                Err(error) => {
+                    // A sensor that still answers only had a transient error
+                    let error = if error == SensorError::Absent
+                        || !sensor_present(&mut RefCellDevice::new(bus), slot.address)
+                    {
+                        slot.sensor = None;
+                        SensorError::Absent
+                    } else {
+                        error
+                    };
+                    slot.reading = None;
+                    slot.error = Some(error);
+                }
+            }
+        }
+        readings
+    }
+
+    /// Gets why the last [SensorGroup::read_all()] came back empty. A sensor that answered but
+    /// failed to read is reported over one that is missing, since that is the one worth looking at
+    ///
+    /// returns the [SensorError]
+    pub fn error(&self) -> SensorError {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.error)
+            .find(|error| *error != SensorError::Absent)
+            .unwrap_or(SensorError::Absent)
+    }
+
+    /// Applies a new temperature offset to every connected sensor; see [set_temperature_offset()]
+    ///
+    /// - param temp_offset: The temperature correction in tenths of a degree Celsius
+    /// - param delayer: BME sensor delay
+    ///
+    /// returns [SensorError::Setup] if the settings could not be applied to a sensor
+    pub fn set_temperature_offset(
+        &mut self,
+        temp_offset: i16,
+        delayer: &mut D,
+    ) -> Result<(), SensorError> {
+        self.slots
+            .iter_mut()
+            .filter_map(|slot| slot.sensor.as_mut())
+            .try_for_each(|sensor| set_temperature_offset(sensor, temp_offset, delayer))
+    }
+}
+
+/// Gets temperature in Celsius, as the BME680 reports it
+///
+/// - param data: [FieldData] from [get_bme_data()]
+///
+/// returns the current temperature in Celsius, rounded to the nearest degree, which may be below freezing
+///
+/// ## Example:
+/// ```rust
+/// use bme680::FieldData;
+/// use gem_rs::sensors::get_temperature_celsius;
+///
+/// let data = FieldData::default(); // This is representing `get_bme_data()`
+/// assert_eq!(get_temperature_celsius(&data), 0);
+/// ```
+pub fn get_temperature_celsius(data: &FieldData) -> i8 {
+    libm::roundf(data.temperature_celsius()) as i8
+}
+
+/// Gets temperature in Fahrenheit. It is converted from the sensor's fractional Celsius,
+/// so it is only rounded once and moves in whole Fahrenheit degrees
 ///
 /// - param data: [FieldData] from [get_bme_data()]
 ///
-/// returns the current temperature in Fahrenheit
+/// returns the current temperature in Fahrenheit; see [celsius_to_fahrenheit()]
 pub fn get_temperature(data: &FieldData) -> u8 {
-    (data.temperature_celsius() * (9. / 5.) + 32.) as u8
+    celsius_to_fahrenheit(data.temperature_celsius())
 }
 
-/// Gets percent humidity (whole number)
+/// Converts Celsius to whole degrees Fahrenheit, rounding to the nearest degree.
+///
+/// **NOTE:** The thresholds, margins, and history are all kept in unsigned Fahrenheit, so anything
+/// colder than 0°F is deliberately held at 0°F instead of making them all signed. 0°F is still well under
+/// the frost alarm, and it keeps a deep frost from wrapping around to a hot reading
+///
+/// - param celsius: The temperature in Celsius, e.g. [FieldData::temperature_celsius()]
+///
+/// returns the temperature in Fahrenheit
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::celsius_to_fahrenheit;
+///
+/// assert_eq!(celsius_to_fahrenheit(21.), 70);
+/// assert_eq!(celsius_to_fahrenheit(22.9), 73); // 73.22°F, not 22°C's 72°F
+/// assert_eq!(celsius_to_fahrenheit(0.), 32);
+/// assert_eq!(celsius_to_fahrenheit(-0.9), 30); // 30.38°F, not 0°C's 32°F
+/// assert_eq!(celsius_to_fahrenheit(-5.), 23);
+/// assert_eq!(celsius_to_fahrenheit(-10.3), 13);
+/// assert_eq!(celsius_to_fahrenheit(-17.5), 1);
+/// assert_eq!(celsius_to_fahrenheit(-18.), 0);
+/// assert_eq!(celsius_to_fahrenheit(-40.), 0); // Below 0°F
+/// assert_eq!(celsius_to_fahrenheit(130.), 255);
+/// ```
+pub fn celsius_to_fahrenheit(celsius: f32) -> u8 {
+    let fahrenheit = libm::roundf(celsius * 9. / 5. + 32.);
+    fahrenheit.clamp(0., u8::MAX as f32) as u8
+}
+
+/// Gets percent humidity (whole number), corrected by the calibration offset
 ///
 /// - param data: [FieldData] from [get_bme_data()]
+/// - param offset: The humidity correction in percent; see [offset_humidity()]
 ///
 /// returns the current relative humidity as a percentage (non-decimal)
 ///
@@ -58,11 +418,184 @@ pub fn get_temperature(data: &FieldData) -> u8 {
 ///
 ///
 /// let data = FieldData::default(); // This is representing `get_bme_data()`
-/// let humidity = get_humidity(&data); // Ex: let humidity = 50
+/// let humidity = get_humidity(&data, 0); // Ex: let humidity = 50
 /// print!("Humidity: {}%", humidity); // "Humidity: 50%"
 /// ```
-pub fn get_humidity(data: &FieldData) -> u8 {
-    data.humidity_percent() as u8
+pub fn get_humidity(data: &FieldData, offset: i8) -> u8 {
+    offset_humidity(data.humidity_percent(), offset) as u8
+}
+
+/// How many degrees Fahrenheit the dew point may approach the air temperature before warning
+pub const DEW_POINT_WARNING: u8 = 3;
+
+/// Gets the dew point in Fahrenheit using the Magnus formula
+///
+/// - param data: [FieldData] from [get_bme_data()]
+/// - param humidity_offset: The humidity correction in percent; see [offset_humidity()]
+///
+/// returns the current dew point in Fahrenheit.
+/// Returns 0 if there is no humidity, since the dew point is undefined
+pub fn get_dew_point(data: &FieldData, humidity_offset: i8) -> u8 {
+    const B: f32 = 17.62;
+    const C: f32 = 243.12; // Celsius
+
+    let humidity = offset_humidity(data.humidity_percent(), humidity_offset);
+    if humidity <= 0. {
+        return 0;
+    }
+    let temp = data.temperature_celsius();
+    let gamma = libm::logf(humidity / 100.) + (B * temp) / (C + temp);
+    let dew_point = (C * gamma) / (B - gamma);
+    (dew_point * (9. / 5.) + 32.) as u8
+}
+
+/// Checks if the dew point is close enough to the air temperature for condensation to form
+///
+/// - param data: [FieldData] from [get_bme_data()]
+/// - param humidity_offset: The humidity correction in percent; see [offset_humidity()]
+///
+/// returns if the dew point is within [DEW_POINT_WARNING] degrees of the temperature
+pub fn is_condensation_risk(data: &FieldData, humidity_offset: i8) -> bool {
+    get_temperature(data).saturating_sub(get_dew_point(data, humidity_offset)) <= DEW_POINT_WARNING
+}
+
+/// How many degrees Fahrenheit the heat index may differ from the temperature before it is shown
+pub const HEAT_INDEX_DIFFERENCE: u8 = 2;
+
+/// Gets the heat index ("feels like" temperature) in Fahrenheit
+///
+/// - param data: [FieldData] from [get_bme_data()]
+/// - param humidity_offset: The humidity correction in percent; see [offset_humidity()]
+///
+/// returns the current heat index in Fahrenheit
+pub fn get_heat_index(data: &FieldData, humidity_offset: i8) -> u8 {
+    heat_index(get_temperature(data), get_humidity(data, humidity_offset))
+}
+
+/// Calculates the heat index using the Rothfusz regression.
+/// Below 80F or 40% relative humidity the heat index is not meaningful,
+/// so the temperature is returned as-is
+///
+/// - param temperature: The temperature in Fahrenheit
+/// - param humidity: The relative humidity percentage
+///
+/// returns the heat index in Fahrenheit
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::heat_index;
+///
+/// // Values from the NWS heat index table
+/// assert_eq!(heat_index(90, 60), 100);
+/// assert_eq!(heat_index(100, 40), 109);
+/// assert_eq!(heat_index(86, 80), 100);
+/// assert_eq!(heat_index(70, 90), 70); // Too cool to matter
+/// ```
+pub fn heat_index(temperature: u8, humidity: u8) -> u8 {
+    if temperature < 80 || humidity < 40 {
+        return temperature;
+    }
+    let t = temperature as f32;
+    let rh = humidity as f32;
+    let index = -42.379 + 2.049_015_2 * t + 10.143_331 * rh
+        - 0.224_755_4 * t * rh
+        - 0.006_837_83 * t * t
+        - 0.054_817_17 * rh * rh
+        + 0.001_228_74 * t * t * rh
+        + 0.000_852_82 * t * rh * rh
+        - 0.000_001_99 * t * t * rh * rh;
+    (index + 0.5) as u8
+}
+
+/// Gets the vapor pressure deficit: how far the air is from saturated at its temperature.
+/// It predicts how hard plants transpire better than temperature or humidity alone
+///
+/// - param data: [FieldData] from [get_bme_data()]
+/// - param humidity_offset: The humidity correction in percent; see [offset_humidity()]
+///
+/// returns the current VPD in hundredths of a kPa; see [vpd()]
+pub fn get_vpd(data: &FieldData, humidity_offset: i8) -> u16 {
+    vpd(
+        data.temperature_celsius(),
+        offset_humidity(data.humidity_percent(), humidity_offset),
+    )
+}
+
+/// Calculates the vapor pressure deficit, using the Tetens formula for saturation vapor pressure
+///
+/// - param celsius: The temperature in Celsius
+/// - param humidity: The relative humidity percentage
+///
+/// returns the VPD in hundredths of a kPa. Saturated air has none
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::vpd;
+///
+/// assert_eq!(vpd(25., 50.), 158); // 3.17 kPa at saturation, half of it missing
+/// assert_eq!(vpd(20., 60.), 94);
+/// assert_eq!(vpd(30., 70.), 127);
+/// assert_eq!(vpd(0., 0.), 61);
+/// assert_eq!(vpd(25., 100.), 0);  // Saturated
+/// assert_eq!(vpd(25., 100.5), 0); // Readings just over saturation are still saturated
+/// ```
+pub fn vpd(celsius: f32, humidity: f32) -> u16 {
+    if humidity >= 100. {
+        return 0;
+    }
+    let saturation = 0.6108 * libm::expf(17.27 * celsius / (celsius + 237.3));
+    let deficit = saturation * (1. - humidity.max(0.) / 100.);
+    (deficit * 100. + 0.5) as u16
+}
+
+/// Corrects a relative humidity reading by the calibration offset, which makes up for the BME680
+/// drifting over time. The result is kept between 0% and 100%
+///
+/// - param humidity: The raw relative humidity percentage
+/// - param offset: The correction in percent; see [crate::preferences::Preferences::humidity_offset]
+///
+/// returns the corrected relative humidity percentage
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::offset_humidity;
+///
+/// assert_eq!(offset_humidity(55., 3), 58.);
+/// assert_eq!(offset_humidity(55., -5), 50.);
+/// assert_eq!(offset_humidity(98., 5), 100.); // Never above saturated
+/// assert_eq!(offset_humidity(100., 0), 100.);
+/// assert_eq!(offset_humidity(2., -5), 0.);   // Never below bone dry
+/// assert_eq!(offset_humidity(0., 0), 0.);
+/// assert_eq!(offset_humidity(0., 4), 4.);
+/// assert_eq!(offset_humidity(100., -4), 96.);
+/// ```
+pub fn offset_humidity(humidity: f32, offset: i8) -> f32 {
+    (humidity + offset as f32).clamp(0., 100.)
+}
+
+/// Formats a value in hundredths with two decimals
+///
+/// - param value: The value in hundredths
+///
+/// returns a [String] of length 8 containing the formatted value
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::format_hundredths;
+///
+/// assert_eq!(format_hundredths(158).as_str(), "1.58");
+/// assert_eq!(format_hundredths(5).as_str(), "0.05"); // Padded hundredths
+/// ```
+pub fn format_hundredths(value: u16) -> String<8> {
+    let mut str: String<8> = String::new();
+    uwrite!(
+        str,
+        "{}.{}",
+        value / 100,
+        pad_number((value % 100) as u8).as_str()
+    )
+    .unwrap();
+    str
 }
 
 /// Gets atmospheric pressure in millibars
@@ -74,24 +607,850 @@ pub fn get_pressure(data: &FieldData) -> u16 {
     data.pressure_hpa() as u16
 }
 
+/// The unit pressure is shown in
+///
+/// - **Hectopascals**: Hectopascals, the same as millibars: `1013 mb`
+/// - **InchesOfMercury**: Inches of mercury, as used in the US: `29.91 in`
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::PressureUnit;
+///
+/// assert_eq!(PressureUnit::Hectopascals.convert(1013), 1013);
+/// assert_eq!(PressureUnit::InchesOfMercury.convert(1013), 2991); // 29.91 inHg
+/// assert_eq!(PressureUnit::InchesOfMercury.convert(1000), 2953);
+/// assert_eq!(PressureUnit::InchesOfMercury.format(1013).as_str(), "29.91");
+/// assert_eq!(PressureUnit::InchesOfMercury.format(34).as_str(), "1.00"); // Padded hundredths
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PressureUnit {
+    Hectopascals,
+    InchesOfMercury,
+}
+
+impl PressureUnit {
+    /// Converts a pressure into this unit
+    ///
+    /// - param millibars: pressure in millibars, e.g. from [get_pressure()]
+    ///
+    /// returns the pressure in hectopascals, or in hundredths of an inch of mercury
+    pub fn convert(self, millibars: u16) -> u16 {
+        match self {
+            PressureUnit::Hectopascals => millibars,
+            PressureUnit::InchesOfMercury => ((millibars as u32 * 2953 + 500) / 1000) as u16,
+        }
+    }
+
+    /// Formats a pressure in this unit, without the suffix
+    ///
+    /// - param millibars: pressure in millibars, e.g. from [get_pressure()]
+    ///
+    /// returns a [String] of length 8 containing the formatted pressure
+    pub fn format(self, millibars: u16) -> String<8> {
+        let value = self.convert(millibars);
+        match self {
+            PressureUnit::Hectopascals => {
+                let mut str: String<8> = String::new();
+                uwrite!(str, "{}", value).unwrap();
+                str
+            }
+            PressureUnit::InchesOfMercury => format_hundredths(value),
+        }
+    }
+
+    /// Gets the unit's suffix for the LCD
+    ///
+    /// returns the suffix; Ex: mb
+    pub fn suffix(self) -> &'static str {
+        match self {
+            PressureUnit::Hectopascals => MILLIBARS,
+            PressureUnit::InchesOfMercury => INCHES_OF_MERCURY,
+        }
+    }
+}
+
+/// Gets atmospheric pressure in a [PressureUnit]
+///
+/// - param data: [FieldData] from [get_bme_data()]
+/// - param unit: The [PressureUnit] to convert to
+///
+/// returns the pressure in hectopascals, or in hundredths of an inch of mercury
+pub fn get_pressure_in(data: &FieldData, unit: PressureUnit) -> u16 {
+    match unit {
+        PressureUnit::Hectopascals => get_pressure(data),
+        PressureUnit::InchesOfMercury => (data.pressure_hpa() * 2.953 + 0.5) as u16,
+    }
+}
+
+/// One reading from the BME680, converted once so it can be displayed, acted on, and logged
+/// without redoing the float math
+///
+/// - **temperature**: Temperature in Fahrenheit
+/// - **humidity**: Relative humidity percentage
+/// - **pressure**: Pressure in millibars
+/// - **gas_resistance**: Gas resistance in ohms
+/// - **dew_point**: Dew point in Fahrenheit; see [get_dew_point()]
+/// - **heat_index**: How hot it feels in Fahrenheit; see [get_heat_index()]
+/// - **vpd**: Vapor pressure deficit in hundredths of a kPa; see [get_vpd()]
+///
+/// ## Example:
+/// ```rust
+/// use bme680::FieldData;
+/// use gem_rs::sensors::SensorReading;
+///
+/// let reading = SensorReading::from_field_data(&FieldData::default(), 0); // 0C, 0%, 0 mb
+/// assert_eq!(reading.temperature, 32);
+/// assert_eq!(reading.humidity, 0);
+/// assert_eq!(reading.dew_point, 0); // Undefined without humidity
+/// assert_eq!(reading.heat_index, 32);
+/// assert_eq!(reading.vpd, 61); // Bone dry
+/// assert!(!reading.is_condensation_risk());
+///
+/// // The humidity offset carries through to everything worked out from the humidity
+/// let reading = SensorReading::from_field_data(&FieldData::default(), 10);
+/// assert_eq!(reading.humidity, 10);
+/// assert_eq!(reading.vpd, 55);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SensorReading {
+    pub temperature: u8,
+    pub humidity: u8,
+    pub pressure: u16,
+    pub gas_resistance: u32,
+    pub dew_point: u8,
+    pub heat_index: u8,
+    pub vpd: u16,
+}
+
+impl SensorReading {
+    /// Converts a reading from the BME680
+    ///
+    /// - param data: [FieldData] from [get_bme_data()]
+    /// - param humidity_offset: The humidity correction in percent; see [offset_humidity()]
+    ///
+    /// returns a new instance of SensorReading
+    pub fn from_field_data(data: &FieldData, humidity_offset: i8) -> SensorReading {
+        let temperature = get_temperature(data);
+        let humidity = get_humidity(data, humidity_offset);
+        Self {
+            temperature,
+            humidity,
+            pressure: get_pressure(data),
+            gas_resistance: data.gas_resistance_ohm(),
+            dew_point: get_dew_point(data, humidity_offset),
+            heat_index: heat_index(temperature, humidity),
+            vpd: get_vpd(data, humidity_offset),
+        }
+    }
+
+    /// Checks if the dew point is close enough to the air temperature for condensation to form
+    ///
+    /// returns if the dew point is within [DEW_POINT_WARNING] degrees of the temperature
+    pub fn is_condensation_risk(&self) -> bool {
+        self.temperature.saturating_sub(self.dew_point) <= DEW_POINT_WARNING
+    }
+}
+
+/// The size of a buffer that fits the longest one-line summary of a [SensorReading] from its [uDisplay] implementation
+pub const READING_SUMMARY_BYTES: usize = 96;
+
+/// A one-line summary of the reading, for logs and the serial port. The format is kept stable
+/// so tools can parse it: space separated `key=value` pairs with their units, always in this order
+///
+/// - **temp**: Temperature: `72F`
+/// - **humidity**: Relative humidity: `55%`
+/// - **pressure**: Pressure: `1013mb`
+/// - **gas**: Gas resistance: `12000ohm`
+/// - **dew**: Dew point: `55F`
+/// - **feels**: Heat index: `72F`
+/// - **vpd**: Vapor pressure deficit: `1.23kPa`
+///
+/// The buffer should hold [READING_SUMMARY_BYTES]
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::{SensorReading, READING_SUMMARY_BYTES};
+/// use heapless::String;
+/// use ufmt::uwrite;
+///
+/// let reading = SensorReading {
+///     temperature: 72,
+///     humidity: 55,
+///     pressure: 1013,
+///     gas_resistance: 12000,
+///     dew_point: 55,
+///     heat_index: 72,
+///     vpd: 123,
+/// };
+/// let mut line: String<READING_SUMMARY_BYTES> = String::new();
+/// uwrite!(line, "{}", reading).unwrap();
+/// assert_eq!(
+///     line.as_str(),
+///     "temp=72F humidity=55% pressure=1013mb gas=12000ohm dew=55F feels=72F vpd=1.23kPa"
+/// );
+/// ```
+impl uDisplay for SensorReading {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        uwrite!(
+            f,
+            "temp={}F humidity={}% pressure={}mb gas={}ohm dew={}F feels={}F vpd={}kPa",
+            self.temperature,
+            self.humidity,
+            self.pressure,
+            self.gas_resistance,
+            self.dew_point,
+            self.heat_index,
+            format_hundredths(self.vpd).as_str(),
+        )
+    }
+}
+
+/// Averages the readings of several sensors into one, for following the whole space rather than
+/// one spot. The heat index is worked out again from the averaged temperature and humidity
+///
+/// - param readings: The readings to average, e.g. from [SensorGroup::read_all()]
+///
+/// returns the averaged [SensorReading], or None if there are no readings
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::{averaged, SensorReading};
+///
+/// let corner = SensorReading {
+///     temperature: 80,
+///     humidity: 50,
+///     pressure: 1012,
+///     gas_resistance: 10000,
+///     dew_point: 60,
+///     heat_index: 81,
+///     vpd: 175,
+/// };
+/// let middle = SensorReading {
+///     temperature: 74,
+///     humidity: 61,
+///     pressure: 1013,
+///     gas_resistance: 14000,
+///     dew_point: 59,
+///     heat_index: 74,
+///     vpd: 112,
+/// };
+///
+/// let average = averaged(&[corner, middle]).unwrap();
+/// assert_eq!(average.temperature, 77);
+/// assert_eq!(average.humidity, 56); // Rounded up from 55.5
+/// assert_eq!(average.pressure, 1013);
+/// assert_eq!(average.gas_resistance, 12000);
+/// assert_eq!(average.dew_point, 60);
+/// assert_eq!(average.vpd, 144);
+///
+/// // One sensor is its own average, and none has none
+/// assert_eq!(averaged(&[corner]), Some(corner));
+/// assert_eq!(averaged(&[]), None);
+/// ```
+pub fn averaged(readings: &[SensorReading]) -> Option<SensorReading> {
+    let count = readings.len() as u32;
+    if count == 0 {
+        return None;
+    }
+    // Rounded to the nearest, rather than down
+    let average = |field: fn(&SensorReading) -> u32| {
+        (readings.iter().map(field).sum::<u32>() + count / 2) / count
+    };
+    let temperature = average(|reading| reading.temperature as u32) as u8;
+    let humidity = average(|reading| reading.humidity as u32) as u8;
+    Some(SensorReading {
+        temperature,
+        humidity,
+        pressure: average(|reading| reading.pressure as u32) as u16,
+        gas_resistance: average(|reading| reading.gas_resistance),
+        dew_point: average(|reading| reading.dew_point as u32) as u8,
+        heat_index: match readings {
+            [reading] => reading.heat_index,
+            _ => heat_index(temperature, humidity),
+        },
+        vpd: average(|reading| reading.vpd as u32) as u16,
+    })
+}
+
+/// Formats one sensor's reading for [crate::screen::Screen::Sensors]: `0x76 72°F 55%`, or `0x77 --` without one
+///
+/// - param address: The sensor's address
+/// - param reading: The sensor's last reading, or None if there is none
+///
+/// returns a [String] of length 16 containing the formatted reading
+///
+/// ## Example:
+/// ```rust
+/// use bme680::I2CAddress;
+/// use gem_rs::sensors::{format_sensor_reading, SensorReading};
+///
+/// let reading = SensorReading { temperature: 72, humidity: 55, ..SensorReading::default() };
+/// assert_eq!(format_sensor_reading(I2CAddress::Primary, Some(&reading)).as_str(), "0x76 72°F 55%");
+/// assert_eq!(format_sensor_reading(I2CAddress::Secondary, None).as_str(), "0x77 --");
+/// ```
+pub fn format_sensor_reading(address: I2CAddress, reading: Option<&SensorReading>) -> String<16> {
+    const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut str: String<16> = String::new();
+    let address = address.addr() as usize;
+    uwrite!(
+        str,
+        "0x{}{} ",
+        HEX_DIGITS[address >> 4] as char,
+        HEX_DIGITS[address & 0xF] as char
+    )
+    .unwrap();
+    match reading {
+        Some(reading) => uwrite!(str, "{}°F {}%", reading.temperature, reading.humidity),
+        None => uwrite!(str, "--"),
+    }
+    .unwrap();
+    str
+}
+
+/// The fractional bits [Ema] keeps, so small steps are not rounded away
+const EMA_FRACTION_BITS: u32 = 8;
+
+/// An exponential moving average: each new value moves the average
+/// `weight / 16` of the way towards it. It uses fixed-point math, so no floats are needed
+///
+/// - **value**: The average with [EMA_FRACTION_BITS] fractional bits, or None before the first value
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::Ema;
+///
+/// // Full weight follows every value as is
+/// let mut ema = Ema::new();
+/// ema.update(60, 16);
+/// assert_eq!(ema.update(80, 16), 80);
+///
+/// // Half weight halves the distance left every step
+/// let mut ema = Ema::new();
+/// ema.update(60, 8);
+/// let steps: [u8; 6] = core::array::from_fn(|_| ema.update(80, 8));
+/// assert_eq!(steps, [70, 75, 78, 79, 79, 80]);
+///
+/// // A quarter weight takes longer to settle
+/// let mut ema = Ema::new();
+/// ema.update(60, 4);
+/// let settled = (1..).find(|_| ema.update(80, 4) == 80).unwrap();
+/// assert_eq!(settled, 13);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ema {
+    value: Option<u16>,
+}
+
+impl Ema {
+    /// Creates a new, empty Ema
+    ///
+    /// returns a new instance of Ema
+    pub fn new() -> Ema {
+        Self { value: None }
+    }
+
+    /// Moves the average towards a new value. The first value is taken as is
+    ///
+    /// - param value: The new value
+    /// - param weight: How much the new value counts, in sixteenths, from 1 to [MAX_SMOOTHING]
+    ///
+    /// returns the rounded average
+    pub fn update(&mut self, value: u8, weight: u8) -> u8 {
+        let weight = weight.clamp(1, MAX_SMOOTHING) as u32;
+        let value = (value as u32) << EMA_FRACTION_BITS;
+        let average = match self.value {
+            Some(previous) => {
+                (weight * value + (MAX_SMOOTHING as u32 - weight) * previous as u32)
+                    / MAX_SMOOTHING as u32
+            }
+            None => value,
+        };
+        self.value = Some(average as u16);
+        self.get()
+    }
+
+    /// Gets the rounded average
+    ///
+    /// returns the average, or 0 before the first value
+    pub fn get(&self) -> u8 {
+        match self.value {
+            Some(average) => {
+                ((average as u32 + (1 << (EMA_FRACTION_BITS - 1))) >> EMA_FRACTION_BITS) as u8
+            }
+            None => 0,
+        }
+    }
+}
+
+/// A moving-average filter over the last `N` sensor readings.
+/// Temperature and humidity are smoothed further by an [Ema] with a weight from
+/// [crate::preferences::Preferences::smoothing]
+///
+/// - **temperatures**, **humidities**, **pressures**: The last `N` readings
+/// - **index**: Where the next reading goes
+/// - **count**: How many readings there are, up to `N`
+/// - **smoothing**: The [Ema] weight of each new average, in sixteenths
+/// - **temperature**, **humidity**: The smoothed averages
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::SensorFilter;
+///
+/// let mut filter: SensorFilter<4> = SensorFilter::new(); // Averages the last 4 readings
+/// filter.push_values(70, 60, 1000);
+/// filter.push_values(72, 64, 1004);
+/// assert_eq!(filter.temperature(), 71);
+/// assert_eq!(filter.humidity(), 62);
+/// assert_eq!(filter.pressure(), 1002);
+///
+/// let mut filter: SensorFilter<1> = SensorFilter::with_smoothing(8); // Half weight
+/// filter.push_values(70, 60, 1000);
+/// filter.push_values(80, 70, 1000);
+/// assert_eq!(filter.temperature(), 75);
+/// assert_eq!(filter.humidity(), 65);
+/// ```
+pub struct SensorFilter<const N: usize> {
+    temperatures: [u8; N],
+    humidities: [u8; N],
+    pressures: [u16; N],
+    index: usize,
+    count: usize,
+    smoothing: u8,
+    temperature: Ema,
+    humidity: Ema,
+}
+
+impl<const N: usize> Default for SensorFilter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> SensorFilter<N> {
+    /// Creates a new, empty SensorFilter without extra smoothing
+    ///
+    /// returns a new instance of SensorFilter
+    pub fn new() -> SensorFilter<N> {
+        Self::with_smoothing(MAX_SMOOTHING)
+    }
+
+    /// Creates a new, empty SensorFilter
+    ///
+    /// - param smoothing: How much each new average counts, in sixteenths; see [Ema::update()]
+    ///
+    /// returns a new instance of SensorFilter
+    pub fn with_smoothing(smoothing: u8) -> SensorFilter<N> {
+        Self {
+            temperatures: [0; N],
+            humidities: [0; N],
+            pressures: [0; N],
+            index: 0,
+            count: 0,
+            smoothing,
+            temperature: Ema::new(),
+            humidity: Ema::new(),
+        }
+    }
+
+    /// Changes how much each new average counts, keeping the readings
+    ///
+    /// - param smoothing: How much each new average counts, in sixteenths; see [Ema::update()]
+    pub fn set_smoothing(&mut self, smoothing: u8) {
+        self.smoothing = smoothing;
+    }
+
+    /// Adds a reading to the filter, replacing the oldest one once full
+    ///
+    /// - param reading: The [SensorReading] to add
+    pub fn push(&mut self, reading: &SensorReading) {
+        self.push_values(reading.temperature, reading.humidity, reading.pressure);
+    }
+
+    /// Adds already converted values to the filter, replacing the oldest ones once full
+    ///
+    /// - param temperature: temperature in Fahrenheit
+    /// - param humidity: relative humidity percentage
+    /// - param pressure: pressure in millibars
+    pub fn push_values(&mut self, temperature: u8, humidity: u8, pressure: u16) {
+        if N == 0 {
+            return;
+        }
+        self.temperatures[self.index] = temperature;
+        self.humidities[self.index] = humidity;
+        self.pressures[self.index] = pressure;
+        self.index = (self.index + 1) % N;
+        if self.count < N {
+            self.count += 1;
+        }
+
+        let temperature = self.average(self.temperatures.iter().map(|&t| t as u32)) as u8;
+        self.temperature.update(temperature, self.smoothing);
+        let humidity = self.average(self.humidities.iter().map(|&h| h as u32)) as u8;
+        self.humidity.update(humidity, self.smoothing);
+    }
+
+    /// Gets the averaged temperature in Fahrenheit
+    ///
+    /// returns the smoothed temperature, or 0 if there are no readings
+    pub fn temperature(&self) -> u8 {
+        self.temperature.get()
+    }
+
+    /// Gets the averaged relative humidity percentage
+    ///
+    /// returns the smoothed humidity, or 0 if there are no readings
+    pub fn humidity(&self) -> u8 {
+        self.humidity.get()
+    }
+
+    /// Gets the averaged pressure in millibars
+    ///
+    /// returns the smoothed pressure, or 0 if there are no readings
+    pub fn pressure(&self) -> u16 {
+        self.average(self.pressures.iter().map(|&p| p as u32)) as u16
+    }
+
+    /// Averages the filled part of a buffer
+    ///
+    /// - param values: the buffer's values
+    ///
+    /// returns the rounded average
+    fn average(&self, values: impl Iterator<Item = u32>) -> u32 {
+        if self.count == 0 {
+            return 0;
+        }
+        let sum: u32 = values.take(self.count).sum();
+        (sum + self.count as u32 / 2) / self.count as u32
+    }
+}
+
+/// How many seconds apart pressure samples are kept by [PressureTrend]
+pub const PRESSURE_SAMPLE_INTERVAL: u32 = 300;
+/// How many pressure samples [PressureTrend] keeps; enough to span one hour
+pub const PRESSURE_HISTORY: usize = 13;
+/// The change in millibars over the history needed for the pressure to be rising or falling
+pub const PRESSURE_TREND_THRESHOLD: u16 = 2;
+/// The drop in millibars over the history at which the pressure is falling rapidly
+pub const PRESSURE_STORM_THRESHOLD: u16 = 4;
+
+/// The direction the barometric pressure is heading
+///
+/// - **Rising**: Usually fair weather is coming
+/// - **Steady**: No significant change
+/// - **Falling**: Usually a storm is coming
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trend {
+    Rising,
+    Steady,
+    Falling,
+}
+
+/// Tracks the barometric pressure over the last hour to classify its [Trend]
+///
+/// - **history**: Samples of (seconds since boot, pressure in millibars), oldest first
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::{PressureTrend, Trend};
+///
+/// let mut pressure_trend = PressureTrend::new();
+/// pressure_trend.push(1013, 0);
+/// pressure_trend.push(1012, 1800); // 30 minutes later
+/// pressure_trend.push(1010, 3600); // 1 hour later
+/// assert_eq!(pressure_trend.trend(), Trend::Falling);
+/// assert!(!pressure_trend.is_falling_rapidly());
+/// ```
+pub struct PressureTrend {
+    history: Deque<(u32, u16), PRESSURE_HISTORY>,
+}
+
+impl Default for PressureTrend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PressureTrend {
+    /// Creates a new PressureTrend without any history
+    ///
+    /// returns a new instance of PressureTrend
+    pub fn new() -> PressureTrend {
+        Self {
+            history: Deque::new(),
+        }
+    }
+
+    /// Records a pressure reading. Readings less than [PRESSURE_SAMPLE_INTERVAL] seconds
+    /// after the last recorded one are ignored, and the oldest sample is dropped once full
+    ///
+    /// - param pressure: pressure in millibars, e.g. from [SensorFilter::pressure()]
+    /// - param now: the current time in seconds since boot
+    pub fn push(&mut self, pressure: u16, now: u32) {
+        if let Some(&(last, _)) = self.history.back() {
+            if now.wrapping_sub(last) < PRESSURE_SAMPLE_INTERVAL {
+                return;
+            }
+        }
+        if self.history.is_full() {
+            self.history.pop_front();
+        }
+        self.history.push_back((now, pressure)).ok();
+    }
+
+    /// Classifies how the pressure changed over the history
+    ///
+    /// returns the [Trend], or [Trend::Steady] if there is not enough history yet
+    pub fn trend(&self) -> Trend {
+        let change = self.change();
+        if change >= PRESSURE_TREND_THRESHOLD as i32 {
+            Trend::Rising
+        } else if change <= -(PRESSURE_TREND_THRESHOLD as i32) {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        }
+    }
+
+    /// Checks if the pressure dropped by at least [PRESSURE_STORM_THRESHOLD] over the history
+    ///
+    /// returns true if a storm is likely coming
+    pub fn is_falling_rapidly(&self) -> bool {
+        self.change() <= -(PRESSURE_STORM_THRESHOLD as i32)
+    }
+
+    /// Gets the change between the oldest and newest samples
+    ///
+    /// returns the change in millibars, or 0 without at least 2 samples
+    fn change(&self) -> i32 {
+        match (self.history.front(), self.history.back()) {
+            (Some(&(_, oldest)), Some(&(_, newest))) => newest as i32 - oldest as i32,
+            _ => 0,
+        }
+    }
+}
+
+/// How many seconds apart temperature samples are kept by [TemperatureRate]
+pub const TEMPERATURE_SAMPLE_INTERVAL: u32 = 30;
+/// How many temperature samples [TemperatureRate] keeps; enough to span three minutes,
+/// so one noisy reading is spread over the whole history
+pub const TEMPERATURE_HISTORY: usize = 7;
+/// The shortest history in seconds [TemperatureRate] works out a rate from
+pub const TEMPERATURE_RATE_MIN_SPAN: u32 = 60;
+
+/// Tracks the temperature over the last few minutes to tell how fast it is changing,
+/// e.g. when direct sun hits the greenhouse
+///
+/// - **history**: Samples of (seconds since boot, temperature in Fahrenheit), oldest first
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::sensors::TemperatureRate;
+///
+/// let mut rate = TemperatureRate::new();
+/// rate.push(70, 0);
+/// rate.push(75, 10); // Too soon after the last sample, ignored
+/// rate.push(71, 30);
+/// assert_eq!(rate.temperature_rate_per_min(), 0); // Spans less than a minute
+///
+/// // The sun comes out: 6°F in 3 minutes
+/// for (index, temp) in [72, 73, 74, 75, 76].into_iter().enumerate() {
+///     rate.push(temp, 60 + index as u32 * 30);
+/// }
+/// assert_eq!(rate.temperature_rate_per_min(), 20); // 2.0°F per minute
+///
+/// // It levels off, and the rise rolls out of the history
+/// for index in 0..7 {
+///     rate.push(76, 210 + index * 30);
+/// }
+/// assert_eq!(rate.temperature_rate_per_min(), 0);
+///
+/// // A single noisy reading barely counts
+/// rate.push(79, 420);
+/// assert_eq!(rate.temperature_rate_per_min(), 10); // 3°F over 3 minutes
+///
+/// // Cooling down is negative
+/// rate.push(70, 450);
+/// assert_eq!(rate.temperature_rate_per_min(), -20);
+/// ```
+pub struct TemperatureRate {
+    history: Deque<(u32, u8), TEMPERATURE_HISTORY>,
+}
+
+impl Default for TemperatureRate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemperatureRate {
+    /// Creates a new TemperatureRate without any history
+    ///
+    /// returns a new instance of TemperatureRate
+    pub fn new() -> TemperatureRate {
+        Self {
+            history: Deque::new(),
+        }
+    }
+
+    /// Records a temperature reading. Readings less than [TEMPERATURE_SAMPLE_INTERVAL] seconds
+    /// after the last recorded one are ignored, and the oldest sample is dropped once full
+    ///
+    /// - param temperature: temperature in Fahrenheit, e.g. from [SensorFilter::temperature()]
+    /// - param now: the current time in seconds since boot
+    pub fn push(&mut self, temperature: u8, now: u32) {
+        if let Some(&(last, _)) = self.history.back() {
+            if now.wrapping_sub(last) < TEMPERATURE_SAMPLE_INTERVAL {
+                return;
+            }
+        }
+        if self.history.is_full() {
+            self.history.pop_front();
+        }
+        self.history.push_back((now, temperature)).ok();
+    }
+
+    /// Gets how fast the temperature changed between the oldest and newest samples
+    ///
+    /// returns the rate in tenths of a degree Fahrenheit per minute; negative while cooling.
+    /// Returns 0 until the history spans [TEMPERATURE_RATE_MIN_SPAN] seconds
+    pub fn temperature_rate_per_min(&self) -> i16 {
+        let (Some(&(start, oldest)), Some(&(end, newest))) =
+            (self.history.front(), self.history.back())
+        else {
+            return 0;
+        };
+        let span = end.wrapping_sub(start);
+        if span < TEMPERATURE_RATE_MIN_SPAN {
+            return 0;
+        }
+        // Tenths of a degree per minute: the change * 10 * 60 seconds / the span
+        let rate = (newest as i32 - oldest as i32) * 600 / span as i32;
+        rate.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+}
+
+/// Counts how BME sensor reads turned out, for troubleshooting a flaky bus in the field
+///
+/// - **successes**: Reads that returned data
+/// - **bus_errors**: Reads the sensor answered but that failed partway, e.g. on a NACK
+/// - **absences**: Reads skipped because no sensor answered
+/// - **last_success**: When the last read succeeded, in seconds since boot
+///
+/// ## Example:
+/// ```rust
+/// use bme680::FieldData;
+/// use gem_rs::sensors::{SensorError, SensorStats};
+///
+/// let mut stats = SensorStats::new();
+/// assert_eq!(stats.since_success(10), None); // Never read
+///
+/// stats.record(&Ok(FieldData::default()), 10);
+/// stats.record(&Err(SensorError::Read), 12);
+/// stats.record(&Err(SensorError::Absent), 14);
+/// assert_eq!(stats.successes(), 1);
+/// assert_eq!(stats.bus_errors(), 1);
+/// assert_eq!(stats.absences(), 1);
+/// assert_eq!(stats.since_success(15), Some(5));
+/// ```
+pub struct SensorStats {
+    successes: u32,
+    bus_errors: u32,
+    absences: u32,
+    last_success: Option<u32>,
+}
+
+impl Default for SensorStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SensorStats {
+    /// Creates a new SensorStats with no reads counted
+    ///
+    /// returns a new instance of SensorStats
+    pub fn new() -> SensorStats {
+        Self {
+            successes: 0,
+            bus_errors: 0,
+            absences: 0,
+            last_success: None,
+        }
+    }
+
+    /// Counts the result of a read
+    ///
+    /// - param result: The result of [get_bme_data()] or the zone's [averaged()] reading,
+    ///   or [SensorError::Absent] if there was no sensor
+    /// - param now: The current time in seconds since boot
+    pub fn record<T>(&mut self, result: &Result<T, SensorError>, now: u32) {
+        match result {
+            Ok(_) => {
+                self.successes = self.successes.saturating_add(1);
+                self.last_success = Some(now);
+            }
+            Err(SensorError::Absent | SensorError::NoAddress) => {
+                self.absences = self.absences.saturating_add(1)
+            }
+            Err(_) => self.bus_errors = self.bus_errors.saturating_add(1),
+        }
+    }
+
+    /// Gets how many reads returned data
+    ///
+    /// returns the amount of successful reads
+    pub fn successes(&self) -> u32 {
+        self.successes
+    }
+
+    /// Gets how many reads failed although the sensor answered
+    ///
+    /// returns the amount of failed reads
+    pub fn bus_errors(&self) -> u32 {
+        self.bus_errors
+    }
+
+    /// Gets how many reads were skipped because no sensor answered
+    ///
+    /// returns the amount of skipped reads
+    pub fn absences(&self) -> u32 {
+        self.absences
+    }
+
+    /// Gets the time since the last successful read
+    ///
+    /// - param now: The current time in seconds since boot
+    ///
+    /// returns the seconds since the last successful read, or None if there has not been one
+    pub fn since_success(&self, now: u32) -> Option<u32> {
+        self.last_success.map(|last| now.wrapping_sub(last))
+    }
+}
+
 /// Sets the sensor's mode to Forced.
 /// This should be called before getting data.
-/// If there is an error setting up, an alarm is sounded.
 ///
 /// - param bme: [Bme] sensor reference
 /// - param delayer: BME delay
+///
+/// returns [SensorError::ModeSet] if the sensor could not be set up
+pub fn prep_bme<I2C: I2c, D: DelayNs>(
+    bme: &mut Bme680<I2C, D>,
+    delayer: &mut D,
+) -> Result<(), SensorError> {
+    bme.set_sensor_mode(delayer, PowerMode::ForcedMode)
+        .map_err(|_| SensorError::ModeSet)
+}
+
+/// Sounds the buzzer forever to signal a sensor failure
+///
+/// **NOTE:** This does not feed the watchdog on purpose,
+/// so if it is running the board resets and retries the sensor
+///
+/// - param delayer: BME delay
 /// - param alarm: Buzzer Pin
-pub fn prep_bme(
-    bme: &mut Bme,
-    delayer: &mut Timer,
-    alarm: &mut Pin<Gpio6, FunctionSio<SioOutput>, PullDown>,
-) {
-    if bme.set_sensor_mode(delayer, PowerMode::ForcedMode).is_err() {
-        loop {
-            alarm.set_high().unwrap();
-            delayer.delay_ms(500);
-            alarm.set_low().unwrap();
-            delayer.delay_ms(1000);
-        }
+pub fn sensor_alarm(delayer: &mut impl DelayNs, alarm: &mut impl OutputPin) -> ! {
+    loop {
+        play_pattern(alarm, &SENSOR_FAULT_PATTERN, delayer);
     }
 }
@@ -0,0 +1,54 @@
+use embedded_hal_0_2::adc::{Channel, OneShot};
+use rp_pico::hal::adc::AdcPin;
+use rp_pico::hal::gpio::bank0::Gpio28;
+use rp_pico::hal::gpio::{FunctionSioInput, Pin, PullNone};
+
+use panic_probe as _;
+
+/// The soil moisture sensor's pin. GPIO 26 and 27 are taken by the RTC, leaving ADC2
+pub type SoilPin = AdcPin<Pin<Gpio28, FunctionSioInput, PullNone>>;
+
+/// Typical raw readings of a capacitive sensor on 3.3V in dry air and in water: Dry, Wet.
+/// Used until the sensor is calibrated from the soil screen
+pub const DEFAULT_CALIBRATION: (u16, u16) = (3500, 1500);
+
+/// Reads the soil moisture sensor's raw 12 bit value.
+/// Capacitive sensors read lower the wetter the soil is
+///
+/// - param adc: The ADC the sensor is on; Ex: [rp_pico::hal::Adc]
+/// - param pin: The sensor's pin; Ex: [SoilPin]
+///
+/// returns the raw reading, or None if the ADC could not be read
+pub fn read_raw<ADC, P: Channel<ADC>>(
+    adc: &mut impl OneShot<ADC, u16, P>,
+    pin: &mut P,
+) -> Option<u16> {
+    adc.read(pin).ok()
+}
+
+/// Estimates the soil moisture by interpolating between the calibration points
+///
+/// - param raw: The raw reading from [read_raw()]
+/// - param calibration: The raw readings with the sensor in dry air and in water: Dry, Wet
+///
+/// returns the moisture percentage, clamped to 0 - 100
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::soil::moisture_percent;
+///
+/// let calibration = (3000, 1000); // Dry, Wet
+/// assert_eq!(moisture_percent(3000, calibration), 0);
+/// assert_eq!(moisture_percent(2000, calibration), 50);
+/// assert_eq!(moisture_percent(500, calibration), 100); // Wetter than the wet point
+/// assert_eq!(moisture_percent(3500, calibration), 0);  // Drier than the dry point
+/// assert_eq!(moisture_percent(2000, (2000, 2000)), 0); // Not calibrated
+/// ```
+pub fn moisture_percent(raw: u16, calibration: (u16, u16)) -> u8 {
+    let (dry, wet) = calibration;
+    if dry == wet {
+        return 0;
+    }
+    let percent = (raw as i32 - dry as i32) * 100 / (wet as i32 - dry as i32);
+    percent.clamp(0, 100) as u8
+}
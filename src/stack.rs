@@ -0,0 +1,125 @@
+use core::ptr::{read_volatile, write_volatile};
+
+use heapless::String;
+use ufmt::uwrite;
+
+use crate::messages::{STACK_FREE, STACK_PEAK};
+
+use panic_probe as _;
+
+/// The word written over the unused stack at boot. Any word that no longer holds it was used since
+pub const STACK_PAINT: u32 = 0xCCCC_CCCC;
+/// Where RAM starts; see `memory.x`
+pub const RAM_START: usize = 0x2000_0000;
+/// How many bytes below [paint()]'s own frame are left alone, so it does not paint over itself
+const PAINT_MARGIN: usize = 64;
+
+extern "C" {
+    /// The top of the stack, set by cortex-m-rt (or flip-link, which moves it below the statics)
+    static _stack_start: u32;
+}
+
+/// How much of the stack has been used since boot
+///
+/// - **used**: The most bytes the stack has ever held, its high-water mark
+/// - **size**: The bytes the stack can grow to before it overflows
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::stack::StackUsage;
+///
+/// let usage = StackUsage { used: 3412, size: 262144 };
+/// assert_eq!(usage.free(), 258732);
+/// assert_eq!(usage.format_peak().as_str(), "Stack peak 3412B");
+/// assert_eq!(usage.format_free().as_str(), "Free 252KB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StackUsage {
+    pub used: usize,
+    pub size: usize,
+}
+
+impl StackUsage {
+    /// Gets how much of the stack has never been used
+    ///
+    /// returns the bytes left
+    pub fn free(&self) -> usize {
+        self.size.saturating_sub(self.used)
+    }
+
+    /// Formats the high-water mark: `Stack peak 3412B`
+    ///
+    /// returns a [String] of length 24 containing the formatted high-water mark
+    pub fn format_peak(&self) -> String<24> {
+        let mut str: String<24> = String::new();
+        uwrite!(str, "{} {}B", STACK_PEAK, self.used).unwrap();
+        str
+    }
+
+    /// Formats how much of the stack has never been used, rounded down to the kilobyte: `Free 252KB`
+    ///
+    /// returns a [String] of length 16 containing the formatted free stack
+    pub fn format_free(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        uwrite!(str, "{} {}KB", STACK_FREE, self.free() / 1024).unwrap();
+        str
+    }
+}
+
+/// Finds where the stack is. With flip-link it sits at the bottom of RAM, below the statics;
+/// otherwise it sits at the top, above them
+///
+/// returns the lowest address the stack can grow down to, and its top
+fn stack_bounds() -> (usize, usize) {
+    // SAFETY: Only the symbol's address is taken, it is never read
+    let top = unsafe { &_stack_start as *const u32 as usize };
+    let heap_start = cortex_m_rt::heap_start() as usize;
+    let bottom = if heap_start < top {
+        heap_start
+    } else {
+        RAM_START
+    };
+    (bottom, top)
+}
+
+/// Paints the unused stack with [STACK_PAINT], so [measure()] can later find how deep it grew.
+///
+/// Painting writes the pattern over every word from the bottom of the stack up to just below this call,
+/// and [measure()] scans up from the bottom for the first word that was overwritten. Its limitations:
+/// - The result is only an estimate. A frame that reserves stack but never writes all of it is not counted,
+///   and a variable that happens to hold [STACK_PAINT] looks unused
+/// - Interrupt handlers share the stack, so their use is only counted if one fired at its deepest point
+/// - Everything above this call, like `main()`'s own locals, is counted as used
+/// - It only works once, at boot; painting again would hide how deep the stack already grew
+///
+/// **NOTE:** This function should be called once, first thing in `main()`
+#[inline(never)]
+pub fn paint() {
+    let (bottom, _) = stack_bounds();
+    let marker = 0u8;
+    let current = core::hint::black_box(&marker) as *const u8 as usize;
+    let end = current.saturating_sub(PAINT_MARGIN) & !3;
+
+    let mut address = bottom;
+    while address < end {
+        // SAFETY: The words between the bottom of the stack and this frame are not in use yet
+        unsafe { write_volatile(address as *mut u32, STACK_PAINT) };
+        address += 4;
+    }
+}
+
+/// Finds how deep the stack has grown since it was painted. See [paint()] for how and its limitations
+///
+/// returns the [StackUsage]
+pub fn measure() -> StackUsage {
+    let (bottom, top) = stack_bounds();
+    let mut address = bottom;
+    // SAFETY: Every word between the bottom and the top of the stack is in RAM
+    while address < top && unsafe { read_volatile(address as *const u32) } == STACK_PAINT {
+        address += 4;
+    }
+    StackUsage {
+        used: top - address,
+        size: top - bottom,
+    }
+}
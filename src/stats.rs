@@ -0,0 +1,96 @@
+use crate::datetime::DateTime;
+
+use panic_probe as _;
+
+/// Today's lowest and highest readings. Everything resets when the day changes
+///
+/// - **day**: The day the readings were taken: Day, Month, Year
+/// - **temperature**: The lowest and highest temperature in Fahrenheit, or None before the first reading
+/// - **humidity**: The lowest and highest relative humidity percentage, or None before the first reading
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::datetime::DateTime;
+/// use gem_rs::stats::DailyStats;
+///
+/// let mut stats = DailyStats::new();
+/// let mut date = DateTime::new(2024, 5, 31, 23, 59, 58);
+/// stats.update(55, 80, &date);
+/// stats.update(61, 72, &date);
+/// stats.update(58, 90, &date);
+/// assert_eq!(stats.temperature(), Some((55, 61)));
+/// assert_eq!(stats.humidity(), Some((72, 90)));
+///
+/// // Midnight
+/// date.tick();
+/// date.tick();
+/// stats.update(57, 85, &date);
+/// assert_eq!(stats.temperature(), Some((57, 57)));
+/// assert_eq!(stats.humidity(), Some((85, 85)));
+/// ```
+pub struct DailyStats {
+    day: (u8, u8, u16),
+    temperature: Option<(u8, u8)>,
+    humidity: Option<(u8, u8)>,
+}
+
+impl Default for DailyStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DailyStats {
+    /// Creates a new instance of DailyStats with no readings
+    ///
+    /// returns a new instance of DailyStats
+    pub fn new() -> DailyStats {
+        Self {
+            day: (0, 0, 0),
+            temperature: None,
+            humidity: None,
+        }
+    }
+
+    /// Adds a reading, starting over if it was taken on a different day than the last one
+    ///
+    /// - param temperature: The temperature in Fahrenheit
+    /// - param humidity: The relative humidity percentage
+    /// - param date: The local [DateTime] of the reading
+    pub fn update(&mut self, temperature: u8, humidity: u8, date: &DateTime) {
+        let day = (date.day, date.month, date.year);
+        if day != self.day {
+            *self = Self::new();
+            self.day = day;
+        }
+        self.temperature = Some(Self::extend(self.temperature, temperature));
+        self.humidity = Some(Self::extend(self.humidity, humidity));
+    }
+
+    /// Gets today's temperature extremes
+    ///
+    /// returns the lowest and highest temperature in Fahrenheit, or None if there are no readings
+    pub fn temperature(&self) -> Option<(u8, u8)> {
+        self.temperature
+    }
+
+    /// Gets today's humidity extremes
+    ///
+    /// returns the lowest and highest relative humidity percentage, or None if there are no readings
+    pub fn humidity(&self) -> Option<(u8, u8)> {
+        self.humidity
+    }
+
+    /// Widens a range to include a value
+    ///
+    /// - param range: The current range: Low, High
+    /// - param value: The new value
+    ///
+    /// returns the widened range
+    fn extend(range: Option<(u8, u8)>, value: u8) -> (u8, u8) {
+        match range {
+            Some((low, high)) => (low.min(value), high.max(value)),
+            None => (value, value),
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use panic_probe as _;
+
+/// Sensor readings, logged at the debug level, and sensors connecting, logged at the info level.
+///
+/// Every reading is logged, so readings are only compiled in with the `verbose-logs` feature.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::sensor=debug`
+pub mod sensor {
+    use bme680::I2CAddress;
+
+    use crate::sensors::SensorReading;
+
+    /// Logs the address a BME680 was set up at, so a sensor strapped to the other address is easy to spot
+    ///
+    /// - param address: The sensor's address
+    pub fn connected(address: I2CAddress) {
+        defmt::info!("BME680 connected at {=u8:#x}", address.addr());
+    }
+
+    /// Logs a BME680 having warmed up, from when its gas resistance is trusted
+    ///
+    /// - param address: The sensor's address
+    pub fn warmed_up(address: I2CAddress) {
+        defmt::info!("BME680 at {=u8:#x} warmed up", address.addr());
+    }
+
+    /// Logs a reading from the BME680
+    ///
+    /// - param reading: The [SensorReading]
+    #[allow(unused_variables)]
+    pub fn reading(reading: &SensorReading) {
+        #[cfg(feature = "verbose-logs")]
+        {
+            use heapless::String;
+            use ufmt::uwrite;
+
+            use crate::sensors::READING_SUMMARY_BYTES;
+
+            let mut line: String<READING_SUMMARY_BYTES> = String::new();
+            uwrite!(line, "{}", reading).unwrap();
+            defmt::debug!("{=str}", line.as_str());
+        }
+    }
+}
+
+/// Actuator state changes, logged at the info level.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::actuator=info`
+pub mod actuator {
+    use crate::datetime::DateTime;
+    use crate::observer::{Actuator, ActuatorObserver};
+
+    /// Logs an actuator turning on or off
+    ///
+    /// - param name: The actuator's name; Ex: vent
+    /// - param on: Whether the actuator is now running
+    pub fn changed(name: &str, on: bool) {
+        defmt::info!("{=str} {=str}", name, if on { "on" } else { "off" });
+    }
+
+    /// An [ActuatorObserver] that logs every actuator turning on or off
+    pub struct ActuatorLog;
+
+    impl ActuatorObserver for ActuatorLog {
+        fn on_change(&mut self, actuator: Actuator, new_state: bool, _: &DateTime) {
+            changed(actuator.name(), new_state);
+        }
+    }
+}
+
+/// Watering schedule events, logged at the info level.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::watering=info`
+pub mod watering {
+    /// Logs entering or leaving a watering window
+    ///
+    /// - param active: Whether it is now watering time
+    pub fn window(active: bool) {
+        if active {
+            defmt::info!("watering window started");
+        } else {
+            defmt::info!("watering window ended");
+        }
+    }
+
+    /// Logs watering by hand starting or ending
+    ///
+    /// - param active: Whether it is now watering by hand
+    pub fn manual(active: bool) {
+        if active {
+            defmt::info!("manual watering started");
+        } else {
+            defmt::info!("manual watering ended");
+        }
+    }
+}
+
+/// The battery running low, logged at the warn level, and recovering, logged at the info level.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::power=info`
+pub mod power {
+    use crate::sensors::format_hundredths;
+
+    /// Logs the battery running low or recovering
+    ///
+    /// - param low: Whether the battery is now low
+    /// - param volts: The battery voltage in hundredths of a volt
+    pub fn battery_low(low: bool, volts: u16) {
+        let volts = format_hundredths(volts);
+        if low {
+            defmt::warn!("battery low at {=str}V", volts.as_str());
+        } else {
+            defmt::info!("battery recovered at {=str}V", volts.as_str());
+        }
+    }
+}
+
+/// Readings crossing their alarm bounds, logged at the warn level, and recovering, logged at the info level.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::alarm=info`
+pub mod alarm {
+    use crate::alarm::Alarm;
+
+    /// Logs a reading crossing its alarm bounds, or every reading coming back within them
+    ///
+    /// - param alarm: The [Alarm] now sounding, or None once the readings recovered
+    /// - param temp: The temperature in Fahrenheit
+    /// - param humidity: The relative humidity percentage
+    pub fn changed(alarm: Option<Alarm>, temp: u8, humidity: u8) {
+        match alarm {
+            Some(alarm) => defmt::warn!("{=str} at {=u8}F {=u8}%", alarm.name(), temp, humidity),
+            None => defmt::info!(
+                "readings back within alarm bounds at {=u8}F {=u8}%",
+                temp,
+                humidity
+            ),
+        }
+    }
+}
+
+/// Which zone the following lines are about, logged at the info level when there are several zones.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::zone=info`
+pub mod zone {
+    use crate::zone::ZONE_COUNT;
+
+    /// Logs that the following lines belong to a zone
+    ///
+    /// - param index: The zone's index, starting at 0
+    pub fn start(index: usize) {
+        if ZONE_COUNT > 1 {
+            defmt::info!("zone {=usize}", index + 1);
+        }
+    }
+}
+
+/// Setting up the LCD, logged at the warn level, or at the error level once it is given up on.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::display=warn`
+pub mod display {
+    use crate::rendering::LCD_INIT_RETRIES;
+
+    /// Logs a failed attempt at setting up the LCD
+    ///
+    /// - param attempt: Which attempt failed, starting at 1
+    pub fn init_failed(attempt: u8) {
+        defmt::warn!(
+            "LCD setup failed, attempt {=u8}/{=u8}",
+            attempt,
+            LCD_INIT_RETRIES
+        );
+    }
+
+    /// Logs that the LCD could not be set up, so the controller runs without it
+    pub fn headless() {
+        defmt::error!("no LCD, running headless");
+    }
+}
+
+/// The boot self-test, logged at the info level, or at the error level for a subsystem that failed.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::boot=info`
+pub mod boot {
+    use crate::selftest::{FIRMWARE_NAME, FIRMWARE_VERSION};
+
+    /// Logs the firmware starting up
+    pub fn started() {
+        defmt::info!("{=str} {=str} starting", FIRMWARE_NAME, FIRMWARE_VERSION);
+    }
+
+    /// Logs one self-test result
+    ///
+    /// - param result: The result's line; Ex: BME680: PASS
+    /// - param passed: Whether the subsystem passed
+    pub fn self_test(result: &str, passed: bool) {
+        if passed {
+            defmt::info!("{=str}", result);
+        } else {
+            defmt::error!("{=str}", result);
+        }
+    }
+}
+
+/// Faults the controller recovered from, logged at the warn level.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::fault=warn`
+pub mod fault {
+    use crate::error::GemError;
+
+    /// Logs a fault, after the outputs were put in their safe state
+    ///
+    /// - param error: The [GemError]
+    pub fn occurred(error: GemError) {
+        defmt::warn!("fault E{=u8}", error.code());
+    }
+}
+
+/// Changed settings, logged at the info level as the bytes from [crate::preferences::Preferences::to_bytes()],
+/// so a bridge can keep them, and failures to save them, logged at the warn level.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::preferences=info`
+pub mod preferences {
+    use heapless::String;
+    use ufmt::uwrite;
+
+    use crate::eeprom::EepromError;
+    use crate::preferences::{Preferences, PREFERENCES_SUMMARY_BYTES};
+
+    /// Logs the settings after they changed, as bytes and as a readable summary
+    ///
+    /// - param preferences: [Preferences] instance
+    pub fn changed(preferences: &Preferences) {
+        defmt::info!("preferences {=[u8]}", &preferences.to_bytes()[..]);
+        let mut summary: String<PREFERENCES_SUMMARY_BYTES> = String::new();
+        uwrite!(summary, "{}", preferences).unwrap();
+        defmt::info!("{=str}", summary.as_str());
+    }
+
+    /// Logs that the settings could not be saved to the EEPROM, so they are lost on a restart
+    ///
+    /// - param error: The [EepromError]
+    pub fn not_saved(error: EepromError) {
+        defmt::warn!(
+            "preferences not saved: {=str}",
+            match error {
+                EepromError::Bus => "no EEPROM",
+                EepromError::Verify => "read back differs",
+            }
+        );
+    }
+}
+
+/// The current readings and actuator states as one line of JSON, for bridges like Home Assistant.
+/// Filter with `DEFMT_LOG=gem_rs::telemetry::state=info`
+pub mod state {
+    use heapless::String;
+    use ufmt::uwrite;
+
+    use crate::logger::ActuatorStates;
+    use crate::preferences::Preferences;
+    use crate::sensors::SensorReading;
+
+    /// The size of the buffer [to_json()] writes into; it fits the longest possible state
+    pub const JSON_BYTES: usize = 128;
+
+    /// Formats the current state as a JSON object.
+    /// Every value is a number or boolean, so nothing needs escaping
+    ///
+    /// - **t**, **h**, **p**: Temperature in Fahrenheit, relative humidity, and pressure in mb
+    /// - **vent**, **water**, **heat**, **light**: Whether the vent, sprinklers, heater, and grow light are running
+    /// - **t_lo**, **t_hi**, **h_lo**, **h_hi**: The configured temperature and humidity ranges
+    ///
+    /// - param reading: The [SensorReading]
+    /// - param actuators: [ActuatorStates] after the reading was acted on
+    /// - param preferences: [Preferences] instance
+    ///
+    /// returns the JSON object without a trailing newline
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use bme680::FieldData;
+    /// use gem_rs::logger::ActuatorStates;
+    /// use gem_rs::preferences::Preferences;
+    /// use gem_rs::sensors::SensorReading;
+    /// use gem_rs::telemetry::state::to_json;
+    ///
+    /// let reading = SensorReading::from_field_data(&FieldData::default(), 0); // 0C, 0%, 0 mb
+    /// let actuators = ActuatorStates {
+    ///     vent: true,
+    ///     sprinklers: false,
+    ///     heater: false,
+    ///     light: false,
+    /// };
+    /// assert_eq!(
+    ///     to_json(&reading, &actuators, &Preferences::default()).as_str(),
+    ///     r#"{"t":32,"h":0,"p":0,"vent":true,"water":false,"heat":false,"light":false,"t_lo":60,"t_hi":80,"h_lo":60,"h_hi":70}"#
+    /// );
+    /// ```
+    pub fn to_json(
+        reading: &SensorReading,
+        actuators: &ActuatorStates,
+        preferences: &Preferences,
+    ) -> String<JSON_BYTES> {
+        let mut json: String<JSON_BYTES> = String::new();
+        // Braces are pushed separately since they delimit the format arguments
+        json.push('{').unwrap();
+        uwrite!(
+            json,
+            r#""t":{},"h":{},"p":{},"vent":{},"water":{},"heat":{},"light":{},"t_lo":{},"t_hi":{},"h_lo":{},"h_hi":{}"#,
+            reading.temperature,
+            reading.humidity,
+            reading.pressure,
+            actuators.vent,
+            actuators.sprinklers,
+            actuators.heater,
+            actuators.light,
+            preferences.temperature.0,
+            preferences.temperature.1,
+            preferences.humidity.0,
+            preferences.humidity.1,
+        )
+        .unwrap();
+        json.push('}').unwrap();
+        json
+    }
+
+    /// Logs the current state as a JSON line
+    ///
+    /// - param reading: The [SensorReading]
+    /// - param actuators: [ActuatorStates] after the reading was acted on
+    /// - param preferences: [Preferences] instance
+    pub fn report(reading: &SensorReading, actuators: &ActuatorStates, preferences: &Preferences) {
+        defmt::info!("{=str}", to_json(reading, actuators, preferences).as_str());
+    }
+}
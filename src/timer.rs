@@ -1,8 +1,16 @@
+use heapless::String;
+use ufmt::uwrite;
+
+use crate::datetime::pad_number;
+
 use panic_probe as _;
 
 /// Contains a value that is decremented every millisecond
 ///
 /// - **target_ms**: The current milliseconds remaining
+/// - **initial_ms**: The milliseconds the CountDownTimer was last set to
+/// - **period_ms**: The milliseconds reloaded when a repeating CountDownTimer hits 0, or None if it is one-shot
+/// - **expired**: Whether the CountDownTimer hit 0 since [CountDownTimer::expired()] was last called
 ///
 /// ## Example:
 /// ```rust
@@ -18,16 +26,174 @@ use panic_probe as _;
 ///     // The CountDownTimer has reached 0
 /// }
 /// ```
+///
+/// A repeating CountDownTimer reloads itself instead of staying at 0:
+/// ```rust
+/// use gem_rs::timer::CountDownTimer;
+///
+/// let mut countdown = CountDownTimer::new_repeating(1000); // Expires every 1000ms
+///
+/// let mut expirations = 0;
+/// for _ in 0..3500 {
+///     countdown.tick();
+///     if countdown.expired() {
+///         expirations += 1;
+///     }
+/// }
+/// assert_eq!(expirations, 3);
+/// assert!(!countdown.expired()); // The edge is only reported once
+/// ```
 pub struct CountDownTimer {
-    target_ms: u16,
+    target_ms: u32,
+    initial_ms: u32,
+    period_ms: Option<u32>,
+    expired: bool,
+}
+
+/// Counts whole seconds on a hardware timer, so the software clock does not
+/// lose time when the main loop stalls, e.g. on a slow sensor read
+///
+/// - **last_us**: The timer reading the last counted second ended at, in microseconds
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::timer::SecondCounter;
+///
+/// let mut seconds = SecondCounter::new(0);
+/// assert_eq!(seconds.elapsed(999_999), 0);
+/// assert_eq!(seconds.elapsed(1_000_000), 1);
+/// assert_eq!(seconds.elapsed(4_500_000), 3); // A stall is caught up at once
+/// assert_eq!(seconds.elapsed(5_000_000), 1); // The leftover half second is kept
+/// ```
+pub struct SecondCounter {
+    last_us: u64,
+}
+
+impl SecondCounter {
+    /// Creates a new SecondCounter starting at the current time
+    ///
+    /// - param now_us: The hardware timer's reading in microseconds
+    ///
+    /// returns a new instance of SecondCounter
+    pub fn new(now_us: u64) -> SecondCounter {
+        Self { last_us: now_us }
+    }
+
+    /// Counts the whole seconds passed since the last counted second
+    ///
+    /// - param now_us: The hardware timer's reading in microseconds
+    ///
+    /// returns the amount of whole seconds that passed
+    pub fn elapsed(&mut self, now_us: u64) -> u32 {
+        let seconds = now_us.saturating_sub(self.last_us) / 1_000_000;
+        self.last_us += seconds * 1_000_000;
+        seconds as u32
+    }
+
+    /// Starts counting from the current time, dropping any partial or uncounted seconds.
+    /// Use this after a loop that kept the clock ticking itself
+    ///
+    /// - param now_us: The hardware timer's reading in microseconds
+    pub fn reset(&mut self, now_us: u64) {
+        self.last_us = now_us;
+    }
+}
+
+/// Time since boot, kept apart from the user-set date so editing the clock does not change it.
+/// It counts its own seconds on the hardware timer, so loops that tick the clock themselves don't lose any
+///
+/// - **counter**: The [SecondCounter] counting seconds on the hardware timer
+/// - **seconds**: Seconds since boot
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::timer::Uptime;
+///
+/// let mut uptime = Uptime::new(0);
+/// assert_eq!(uptime.format().as_str(), "0d 00:00:00");
+///
+/// uptime.update(59_500_000);
+/// assert_eq!(uptime.seconds(), 59);
+/// uptime.update(((3 * 24 + 4) * 3600 + 12 * 60 + 7) * 1_000_000);
+/// assert_eq!(uptime.format().as_str(), "3d 04:12:07");
+/// ```
+pub struct Uptime {
+    counter: SecondCounter,
+    seconds: u32,
+}
+
+impl Uptime {
+    /// Creates a new Uptime starting at boot
+    ///
+    /// - param now_us: The hardware timer's reading in microseconds
+    ///
+    /// returns a new instance of Uptime
+    pub fn new(now_us: u64) -> Uptime {
+        Self {
+            counter: SecondCounter::new(now_us),
+            seconds: 0,
+        }
+    }
+
+    /// Counts the seconds passed since the last update
+    ///
+    /// - param now_us: The hardware timer's reading in microseconds
+    pub fn update(&mut self, now_us: u64) {
+        self.seconds = self.seconds.saturating_add(self.counter.elapsed(now_us));
+    }
+
+    /// Gets the time since boot
+    ///
+    /// returns the seconds since boot
+    pub fn seconds(&self) -> u32 {
+        self.seconds
+    }
+
+    /// Formats the time since boot: `3d 04:12:07`
+    ///
+    /// returns a [String] of length 16 containing the formatted uptime
+    pub fn format(&self) -> String<16> {
+        let mut str: String<16> = String::new();
+        let time = self.seconds % 86_400;
+        uwrite!(
+            str,
+            "{}d {}:{}:{}",
+            self.seconds / 86_400,
+            pad_number((time / 3600) as u8).as_str(),
+            pad_number((time / 60 % 60) as u8).as_str(),
+            pad_number((time % 60) as u8).as_str(),
+        )
+        .unwrap();
+        str
+    }
 }
 
 /// The delay in milliseconds between changing screens
-pub const SCREEN_BUTTON_DELAY: u16 = 500;
+pub const SCREEN_BUTTON_DELAY: u32 = 500;
 /// The delay in milliseconds between updating uptime
-pub const TICK_TIME_DELAY: u16 = 1000;
+pub const TICK_TIME_DELAY: u32 = 1000;
 /// The delay in milliseconds between querying sensors
-pub const SENSOR_DELAY: u16 = 2000;
+pub const SENSOR_DELAY: u32 = 2000;
+/// The delay in milliseconds before a held button first repeats on editing screens
+pub const EDIT_DELAY: u32 = 500;
+/// The delay in milliseconds between repeats while a button is held
+pub const HOLD_REPEAT_DELAY: u32 = 150;
+/// The delay in milliseconds between repeats while a button is held for a long time
+pub const HOLD_FAST_REPEAT_DELAY: u32 = 50;
+/// How long in milliseconds a button must be held before values start repeating
+pub const HOLD_REPEAT_TIME: u32 = 1000;
+/// How long in milliseconds a button must be held before values repeat quickly
+pub const HOLD_FAST_REPEAT_TIME: u32 = 3000;
+/// How long in milliseconds pressing SELECT silences the fire alarm's buzzer
+pub const ALARM_SILENCE_TIME: u32 = 300_000;
+/// How long in milliseconds the buzzer is on, then off, while the frost alarm sounds
+pub const FROST_BEEP_TIME: u32 = 500;
+/// The delay in milliseconds without a button press before the backlight turns off
+pub const BACKLIGHT_TIMEOUT: u32 = 30_000;
+/// The delay in milliseconds after a button press before the screens start cycling on their own again
+pub const AUTO_CYCLE_PAUSE: u32 = 60_000;
+/// The delay in milliseconds after a fault before the sensors are read and the outputs driven again
+pub const FAULT_RETRY_DELAY: u32 = 5000;
 
 impl CountDownTimer {
     /// Creates a new instances of CountDownTimer
@@ -35,24 +201,107 @@ impl CountDownTimer {
     /// - param target_ms: The amount of milliseconds to wait when the CountDownTimer is created
     ///
     /// returns a new instances of CountDownTimer
-    pub fn new(target_ms: u16) -> CountDownTimer {
-        Self { target_ms }
+    pub fn new(target_ms: u32) -> CountDownTimer {
+        Self {
+            target_ms,
+            initial_ms: target_ms,
+            period_ms: None,
+            expired: false,
+        }
+    }
+
+    /// Creates a new instance of CountDownTimer that reloads itself every period
+    ///
+    /// - param period_ms: The amount of milliseconds between expirations
+    ///
+    /// returns a new instance of CountDownTimer
+    pub fn new_repeating(period_ms: u32) -> CountDownTimer {
+        Self {
+            target_ms: period_ms,
+            initial_ms: period_ms,
+            period_ms: Some(period_ms),
+            expired: false,
+        }
     }
 
-    /// Updates the CountDownTimer
+    /// Updates the CountDownTimer. A repeating CountDownTimer reloads its period when it hits 0
     ///
     /// **NOTE:** This function should be called every millisecond
     pub fn tick(&mut self) {
         if self.target_ms > 0 {
             self.target_ms -= 1;
+        } else if self.period_ms.is_none() {
+            // A one-shot CountDownTimer stays finished
+            return;
+        }
+
+        if self.target_ms == 0 {
+            self.expired = true;
+            if let Some(period_ms) = self.period_ms {
+                self.set_time(period_ms);
+            }
         }
     }
 
-    /// Sets the waiting time for the CountDownTimer
+    /// Sets the waiting time for the CountDownTimer.
+    /// For a repeating CountDownTimer, this is the time until it next expires
     ///
     /// - param ms: The amount of milliseconds to set
-    pub fn set_time(&mut self, ms: u16) {
+    pub fn set_time(&mut self, ms: u32) {
         self.target_ms = ms;
+        self.initial_ms = ms;
+    }
+
+    /// Sets the period of a repeating CountDownTimer.
+    /// It is used from the next reload on; the current countdown is unchanged
+    ///
+    /// - param ms: The amount of milliseconds between expirations
+    pub fn set_period(&mut self, ms: u32) {
+        self.period_ms = Some(ms);
+    }
+
+    /// Gets the time left until the CountDownTimer hits 0
+    ///
+    /// returns the remaining milliseconds
+    pub fn remaining_ms(&self) -> u32 {
+        self.target_ms
+    }
+
+    /// Gets the time passed since the CountDownTimer was last set or reloaded
+    ///
+    /// returns the elapsed milliseconds
+    pub fn elapsed_ms(&self) -> u32 {
+        self.initial_ms - self.target_ms
+    }
+
+    /// Gets how far the CountDownTimer is through its countdown
+    ///
+    /// returns the progress from 0 to 100 percent; 100 if it was set to 0
+    ///
+    /// ## Example:
+    /// ```rust
+    /// use gem_rs::timer::CountDownTimer;
+    ///
+    /// let mut countdown = CountDownTimer::new(2000);
+    /// for _ in 0..500 {
+    ///     countdown.tick();
+    /// }
+    /// assert_eq!(countdown.elapsed_ms(), 500);
+    /// assert_eq!(countdown.remaining_ms(), 1500);
+    /// assert_eq!(countdown.progress(), 25);
+    /// ```
+    pub fn progress(&self) -> u8 {
+        if self.initial_ms == 0 {
+            return 100;
+        }
+        (self.elapsed_ms() as u64 * 100 / self.initial_ms as u64) as u8
+    }
+
+    /// Checks if the CountDownTimer hit 0 since this was last called
+    ///
+    /// returns true once per expiration
+    pub fn expired(&mut self) -> bool {
+        core::mem::take(&mut self.expired)
     }
 
     /// Checks if the CountDownTimer has hit 0
@@ -62,3 +311,281 @@ impl CountDownTimer {
         self.target_ms == 0
     }
 }
+
+/// Repeats UP and DOWN while they are held on editing screens, faster the longer they are held.
+/// It is ticked by the main loop, so nothing else waits on it
+///
+/// - **held_ms**: How long the button has been held
+/// - **waited_ms**: Time passed since the last repeat
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::timer::{EditTimer, EDIT_DELAY, HOLD_REPEAT_DELAY, HOLD_REPEAT_TIME};
+///
+/// let mut edit_timer = EditTimer::new();
+/// // The press itself counts once; holding it repeats after a pause
+/// let repeats = (0..HOLD_REPEAT_TIME).filter(|_| edit_timer.repeat(true)).count();
+/// assert_eq!(repeats as u32, HOLD_REPEAT_TIME / EDIT_DELAY);
+///
+/// // Then it repeats faster
+/// let repeats = (0..HOLD_REPEAT_TIME).filter(|_| edit_timer.repeat(true)).count();
+/// assert_eq!(repeats as u32, HOLD_REPEAT_TIME / HOLD_REPEAT_DELAY);
+///
+/// // Letting go starts over
+/// assert!(!edit_timer.repeat(false));
+/// assert!(!(1..EDIT_DELAY).any(|_| edit_timer.repeat(true)));
+/// ```
+pub struct EditTimer {
+    held_ms: u32,
+    waited_ms: u32,
+}
+
+impl Default for EditTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EditTimer {
+    /// Creates a new instance of EditTimer
+    ///
+    /// returns a new instance of EditTimer
+    pub fn new() -> EditTimer {
+        Self {
+            held_ms: 0,
+            waited_ms: 0,
+        }
+    }
+
+    /// Checks if a held button should repeat.
+    /// It first repeats after [EDIT_DELAY]; after [HOLD_REPEAT_TIME] and
+    /// [HOLD_FAST_REPEAT_TIME] of holding the repeats get closer together
+    ///
+    /// **NOTE:** This function should be called every tick
+    ///
+    /// - param held: Whether UP or DOWN is being held
+    ///
+    /// returns true on the ticks the button repeats
+    pub fn repeat(&mut self, held: bool) -> bool {
+        if !held {
+            self.held_ms = 0;
+            self.waited_ms = 0;
+            return false;
+        }
+        let delay = if self.held_ms >= HOLD_FAST_REPEAT_TIME {
+            HOLD_FAST_REPEAT_DELAY
+        } else if self.held_ms >= HOLD_REPEAT_TIME {
+            HOLD_REPEAT_DELAY
+        } else {
+            EDIT_DELAY
+        };
+        self.held_ms += 1;
+        self.waited_ms += 1;
+        if self.waited_ms >= delay {
+            self.waited_ms = 0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Watering started by hand, regardless of the schedule. The sprinklers run until its
+/// [CountDownTimer] runs out, then go back to following the schedule and the sensors
+///
+/// - **countdown**: The time left to water
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::timer::ManualWatering;
+///
+/// let mut manual = ManualWatering::new();
+/// assert!(!manual.sprinklers_or(false)); // The automatic logic decides
+///
+/// manual.start(3000);
+/// assert!(manual.sprinklers_or(false)); // Watering, even if it is not watering time
+/// assert_eq!(manual.format_remaining().as_str(), "00:03");
+///
+/// // It hands control back once the time is up
+/// assert_eq!((0..3000).filter(|_| manual.tick()).count(), 1);
+/// assert!(!manual.is_active());
+/// assert!(!manual.sprinklers_or(false));
+/// assert!(manual.sprinklers_or(true)); // A watering window still waters
+/// assert!(!manual.tick()); // The end is only reported once
+/// ```
+pub struct ManualWatering {
+    countdown: CountDownTimer,
+}
+
+impl Default for ManualWatering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualWatering {
+    /// Creates a new instance of ManualWatering that is not watering
+    ///
+    /// returns a new instance of ManualWatering
+    pub fn new() -> ManualWatering {
+        Self {
+            countdown: CountDownTimer::new(0),
+        }
+    }
+
+    /// Starts watering, or restarts it if it is already running
+    ///
+    /// - param ms: How long to water in milliseconds; see [crate::preferences::Preferences::manual_watering_ms()]
+    pub fn start(&mut self, ms: u32) {
+        self.countdown.set_time(ms);
+    }
+
+    /// Stops watering early, without reporting an end from [ManualWatering::tick()]
+    pub fn stop(&mut self) {
+        self.countdown.set_time(0);
+    }
+
+    /// Counts down the watering time
+    ///
+    /// **NOTE:** This function should be called every millisecond
+    ///
+    /// returns true on the tick the watering time runs out
+    pub fn tick(&mut self) -> bool {
+        if self.countdown.is_finished() {
+            return false;
+        }
+        self.countdown.tick();
+        self.countdown.expired()
+    }
+
+    /// Checks if the sprinklers were started by hand and are still running
+    ///
+    /// returns true while there is watering time left
+    pub fn is_active(&self) -> bool {
+        !self.countdown.is_finished()
+    }
+
+    /// Gets the sprinkler state to apply. Watering by hand wins while it is active
+    ///
+    /// - param automatic: The state the automatic logic decided on
+    ///
+    /// returns true while watering by hand, otherwise the automatic state
+    pub fn sprinklers_or(&self, automatic: bool) -> bool {
+        self.is_active() || automatic
+    }
+
+    /// Formats the watering time left, rounded up to the second: `MM:SS`
+    ///
+    /// returns a [String] of length 8 containing the formatted time
+    pub fn format_remaining(&self) -> String<8> {
+        format_minutes_seconds(self.countdown.remaining_ms())
+    }
+}
+
+/// Waits out the BME680 warming up after it is set up. Its gas heater takes minutes to settle,
+/// and the gas resistance is not worth trusting until it has; the temperature and pressure are
+/// fine right away
+///
+/// - **countdown**: The time left until the sensor is warmed up
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::timer::SensorWarmup;
+///
+/// let mut warmup = SensorWarmup::new();
+/// assert!(warmup.is_warmed_up()); // Nothing to wait for yet
+///
+/// warmup.start(90_000); // The sensor was just set up
+/// assert!(!warmup.is_warmed_up());
+/// assert_eq!(warmup.format_remaining().as_str(), "01:30");
+///
+/// // Warming up is reported once, when the time is up
+/// assert_eq!((0..90_000).filter(|_| warmup.tick()).count(), 1);
+/// assert!(warmup.is_warmed_up());
+/// assert!(!warmup.tick());
+///
+/// // No warm-up at all
+/// warmup.start(0);
+/// assert!(warmup.is_warmed_up());
+/// ```
+pub struct SensorWarmup {
+    countdown: CountDownTimer,
+}
+
+impl Default for SensorWarmup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SensorWarmup {
+    /// Creates a new instance of SensorWarmup with nothing to wait for
+    ///
+    /// returns a new instance of SensorWarmup
+    pub fn new() -> SensorWarmup {
+        Self {
+            countdown: CountDownTimer::new(0),
+        }
+    }
+
+    /// Starts waiting, or starts over if it was already waiting. Call this whenever the sensor is set up
+    ///
+    /// - param ms: How long the sensor takes to warm up in milliseconds;
+    ///   see [crate::preferences::Preferences::gas_warmup_ms()]
+    pub fn start(&mut self, ms: u32) {
+        self.countdown.set_time(ms);
+    }
+
+    /// Counts down the warm-up time
+    ///
+    /// **NOTE:** This function should be called every millisecond
+    ///
+    /// returns true on the tick the sensor is warmed up
+    pub fn tick(&mut self) -> bool {
+        if self.countdown.is_finished() {
+            return false;
+        }
+        self.countdown.tick();
+        self.countdown.expired()
+    }
+
+    /// Checks if the warm-up time is over
+    ///
+    /// returns true once the gas readings can be trusted
+    pub fn is_warmed_up(&self) -> bool {
+        self.countdown.is_finished()
+    }
+
+    /// Formats the warm-up time left, rounded up to the second: `MM:SS`
+    ///
+    /// returns a [String] of length 8 containing the formatted time
+    pub fn format_remaining(&self) -> String<8> {
+        format_minutes_seconds(self.countdown.remaining_ms())
+    }
+}
+
+/// Formats a duration, rounded up to the second: `MM:SS`
+///
+/// - param ms: The duration in milliseconds
+///
+/// returns a [String] of length 8 containing the formatted duration
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::timer::format_minutes_seconds;
+///
+/// assert_eq!(format_minutes_seconds(600_000).as_str(), "10:00");
+/// assert_eq!(format_minutes_seconds(1).as_str(), "00:01"); // Rounded up
+/// ```
+pub fn format_minutes_seconds(ms: u32) -> String<8> {
+    let seconds = ms.div_ceil(1000);
+    let mut str: String<8> = String::new();
+    uwrite!(
+        str,
+        "{}:{}",
+        pad_number((seconds / 60).min(u8::MAX as u32) as u8).as_str(),
+        pad_number((seconds % 60) as u8).as_str(),
+    )
+    .unwrap();
+    str
+}
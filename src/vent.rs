@@ -0,0 +1,114 @@
+#[cfg(not(feature = "relay-vent"))]
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::preferences::MAX_VENT_ANGLE;
+#[cfg(feature = "relay-vent")]
+use crate::relay::Relay;
+
+use panic_probe as _;
+
+/// The pulse width in microseconds that turns the servo to 0 degrees (closed)
+pub const SERVO_MIN_PULSE_US: u16 = 1000;
+/// The pulse width in microseconds that turns the servo to [MAX_VENT_ANGLE] degrees (fully open)
+pub const SERVO_MAX_PULSE_US: u16 = 2000;
+/// The servo's PWM period in microseconds (50Hz)
+pub const SERVO_PERIOD_US: u16 = 20_000;
+
+/// Converts a vent angle to the pulse width that turns the servo to it
+///
+/// - param angle: The vent angle in degrees, clamped to [MAX_VENT_ANGLE]
+///
+/// returns the pulse width in microseconds
+///
+/// ## Example:
+/// ```rust
+/// use gem_rs::vent::pulse_width_us;
+///
+/// assert_eq!(pulse_width_us(0), 1000);   // Closed
+/// assert_eq!(pulse_width_us(90), 1500);  // Halfway
+/// assert_eq!(pulse_width_us(180), 2000); // Fully open
+/// assert_eq!(pulse_width_us(255), 2000); // Clamped
+/// ```
+pub fn pulse_width_us(angle: u8) -> u16 {
+    let angle = angle.min(MAX_VENT_ANGLE) as u32;
+    let span = (SERVO_MAX_PULSE_US - SERVO_MIN_PULSE_US) as u32;
+    SERVO_MIN_PULSE_US + (span * angle / MAX_VENT_ANGLE as u32) as u16
+}
+
+/// An output that can move the roof vent.
+///
+/// By default this is a servo on a 50Hz PWM channel.
+/// With the `relay-vent` feature it is a [Relay],
+/// which fully opens the vent at any angle above 0
+pub trait VentOutput {
+    /// Moves the vent
+    ///
+    /// - param angle: The vent angle in degrees, from 0 (closed) to [MAX_VENT_ANGLE]
+    fn apply(&mut self, angle: u8);
+}
+
+#[cfg(not(feature = "relay-vent"))]
+impl<P: SetDutyCycle> VentOutput for P {
+    fn apply(&mut self, angle: u8) {
+        self.set_duty_cycle_fraction(pulse_width_us(angle), SERVO_PERIOD_US)
+            .unwrap();
+    }
+}
+
+#[cfg(feature = "relay-vent")]
+impl VentOutput for &mut Relay<'_> {
+    fn apply(&mut self, angle: u8) {
+        self.set_active(angle > 0);
+    }
+}
+
+/// The roof vent
+///
+/// - **output**: The [VentOutput] moving the vent
+/// - **angle**: The last commanded angle in degrees
+pub struct Vent<P: VentOutput> {
+    output: P,
+    angle: u8,
+}
+
+impl<P: VentOutput> Vent<P> {
+    /// Creates a new Vent and closes it
+    ///
+    /// - param output: The [VentOutput] moving the vent
+    ///
+    /// returns a new instance of Vent
+    pub fn new(output: P) -> Vent<P> {
+        let mut vent = Self { output, angle: 0 };
+        vent.set_angle(0);
+        vent
+    }
+
+    /// Moves the vent
+    ///
+    /// - param angle: The vent angle in degrees, clamped to [MAX_VENT_ANGLE]
+    pub fn set_angle(&mut self, angle: u8) {
+        self.angle = angle.min(MAX_VENT_ANGLE);
+        self.output.apply(self.angle);
+    }
+
+    /// Fully opens or closes the vent
+    ///
+    /// - param open: Whether the vent should be fully open
+    pub fn set_open(&mut self, open: bool) {
+        self.set_angle(if open { MAX_VENT_ANGLE } else { 0 });
+    }
+
+    /// Gets the last commanded angle
+    ///
+    /// returns the vent angle in degrees
+    pub fn angle(&self) -> u8 {
+        self.angle
+    }
+
+    /// Checks if the vent is open at all
+    ///
+    /// returns true if the angle is above 0
+    pub fn is_open(&self) -> bool {
+        self.angle > 0
+    }
+}
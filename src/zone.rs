@@ -0,0 +1,485 @@
+use bme680::I2CAddress;
+use core::cell::RefCell;
+use core::convert::Infallible;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::pwm::SetDutyCycle;
+use rp_pico::hal::Timer;
+
+use crate::air::{AirQuality, AIR_VENT_ANGLE};
+use crate::alarm::ReadingAlarms;
+use crate::graph::TrendGraphs;
+use crate::history::{watering_reason, WateringHistory};
+use crate::logger::ActuatorStates;
+use crate::observer::{notify_changes, ActuatorObserver};
+use crate::overrides::Overrides;
+use crate::preferences::{
+    fan_duty, is_rising_fast, sprinklers_should_run, vent_angle, Hysteresis, Preferences,
+    VENT_HOLD_ANGLE,
+};
+use crate::relay::Relay;
+use crate::selftest::TOGGLE_MS;
+#[cfg(not(feature = "two-zones"))]
+use crate::sensors::BME680_ADDRESSES;
+use crate::sensors::{
+    BmeBus, PressureTrend, SensorError, SensorFilter, SensorGroup, SensorReading, SensorStats,
+    TemperatureRate,
+};
+#[cfg(feature = "two-zones")]
+use crate::sensors::{BME680_ADDRESS, BME680_ALT_ADDRESS};
+use crate::stats::DailyStats;
+use crate::telemetry;
+use crate::timer::{ManualWatering, SensorWarmup};
+use crate::vent::Vent;
+
+use panic_probe as _;
+
+/// How many greenhouse zones are managed. The second zone needs the `two-zones` feature
+#[cfg(not(feature = "two-zones"))]
+pub const ZONE_COUNT: usize = 1;
+/// How many greenhouse zones are managed. The second zone needs the `two-zones` feature
+#[cfg(feature = "two-zones")]
+pub const ZONE_COUNT: usize = 2;
+
+/// The addresses of each zone's BME680s. A single zone averages the sensors at both addresses
+#[cfg(not(feature = "two-zones"))]
+pub const ZONE_SENSORS: [&[I2CAddress]; ZONE_COUNT] = [&BME680_ADDRESSES];
+/// The addresses of each zone's BME680s. With two zones, each has one sensor at its own address
+#[cfg(feature = "two-zones")]
+pub const ZONE_SENSORS: [&[I2CAddress]; ZONE_COUNT] = [&[BME680_ADDRESS], &[BME680_ALT_ADDRESS]];
+
+/// The vent angle held after a fault; see [Zone::fail_safe()]. Closed keeps rain and cold out.
+/// Raise it towards [crate::preferences::MAX_VENT_ANGLE] if overheating is the bigger risk
+pub const FAIL_SAFE_VENT_ANGLE: u8 = 0;
+
+/// A PWM output, e.g. the cooling fan
+pub type Pwm<'a> = &'a mut dyn SetDutyCycle<Error = Infallible>;
+
+/// The output moving the roof vent; see [crate::vent::VentOutput]
+#[cfg(not(feature = "relay-vent"))]
+pub type VentPin<'a> = Pwm<'a>;
+/// The output moving the roof vent; see [crate::vent::VentOutput]
+#[cfg(feature = "relay-vent")]
+pub type VentPin<'a> = &'a mut Relay<'a>;
+
+/// The outputs one zone drives
+///
+/// - **vent**: The roof [Vent]
+/// - **fan**: The cooling fan
+/// - **heater**: The heater relay
+/// - **sprinklers**: The sprinkler relay
+/// - **grow_light**: The grow light relay, or None if the zone has no grow light
+pub struct ZoneOutputs<'a> {
+    pub vent: Vent<VentPin<'a>>,
+    pub fan: Pwm<'a>,
+    pub heater: Relay<'a>,
+    pub sprinklers: Relay<'a>,
+    pub grow_light: Option<Relay<'a>>,
+}
+
+impl<'a> ZoneOutputs<'a> {
+    /// Creates a new instance of ZoneOutputs and closes the vent
+    ///
+    /// - param vent: The roof vent's output
+    /// - param fan: The cooling fan
+    /// - param heater: The heater [Relay]
+    /// - param sprinklers: The sprinkler [Relay]
+    /// - param grow_light: The grow light [Relay], if there is one
+    ///
+    /// returns a new instance of ZoneOutputs
+    pub fn new(
+        vent: VentPin<'a>,
+        fan: Pwm<'a>,
+        heater: Relay<'a>,
+        sprinklers: Relay<'a>,
+        grow_light: Option<Relay<'a>>,
+    ) -> ZoneOutputs<'a> {
+        Self {
+            vent: Vent::new(vent),
+            fan,
+            heater,
+            sprinklers,
+            grow_light,
+        }
+    }
+}
+
+/// One greenhouse zone: a bench with its own sensor, outputs, and [Preferences].
+/// Every zone shares the clock, buttons, display, buzzer, and smoke detector
+///
+/// - **preferences**: The zone's [Preferences]. Only the first zone's clock is kept; see [share_clock()]
+/// - **sensors**: The zone's BME680s. With a single zone, a sensor may be at either address and both
+///   are averaged; with several zones each has one at its own address. See [Zone::connect_sensor()]
+/// - **outputs**: The [ZoneOutputs] the zone drives
+/// - **data**: The last reading
+/// - **filter**: The recent readings, smoothed
+/// - **pressure_trend**: The pressure history, for storm warnings
+/// - **temperature_rate**: The temperature history, for warnings when it rises fast
+/// - **sensor_stats**: How the sensor's reads went
+/// - **daily_stats**: Today's lowest and highest readings
+/// - **trends**: The last hour of readings, for [crate::screen::Screen::Graph]
+/// - **soil_moisture**: The last soil moisture percentage, or None without a soil sensor
+/// - **frost**: Whether the frost alarm is active
+/// - **alarms**: The readings against their alarm bounds, separate from the ranges the outputs follow
+/// - **rising_fast**: Whether the temperature is rising too fast; see [is_rising_fast()]
+/// - **watering**: Whether it is watering time
+/// - **manual_watering**: Watering started by hand, which runs the sprinklers whatever the schedule
+/// - **watering_history**: The last times the sprinklers ran, and why
+/// - **gas_warmup**: The sensor warming up since it was last set up; see [Zone::is_warmed_up()]
+/// - **air_quality**: The gas resistance against its learned baseline, for airing out stale air
+/// - **saving_power**: Whether the battery is low, so the grow light and fan are kept off;
+///   see [crate::preferences::BatterySettings::power_saving]
+/// - **daylight**: Whether it is bright enough outside that the grow light is skipped;
+///   see [crate::light_sensor::AmbientLight::is_bright()]
+/// - **vent_control**, **heater_control**, **frost_control**, **humidity_control**, **soil_control**:
+///   One [Hysteresis] per controlled output, so readings near a threshold don't cause chatter
+/// - **actuators**: The [ActuatorStates] after the last reading
+pub struct Zone<'a, D = Timer> {
+    pub preferences: Preferences,
+    pub sensors: SensorGroup<'a, D>,
+    pub outputs: ZoneOutputs<'a>,
+    pub data: SensorReading,
+    pub filter: SensorFilter<4>,
+    pub pressure_trend: PressureTrend,
+    pub temperature_rate: TemperatureRate,
+    pub sensor_stats: SensorStats,
+    pub daily_stats: DailyStats,
+    pub trends: TrendGraphs,
+    pub soil_moisture: Option<u8>,
+    pub frost: bool,
+    pub alarms: ReadingAlarms,
+    pub rising_fast: bool,
+    pub watering: bool,
+    pub manual_watering: ManualWatering,
+    pub watering_history: WateringHistory,
+    pub gas_warmup: SensorWarmup,
+    pub air_quality: AirQuality,
+    pub saving_power: bool,
+    pub daylight: bool,
+    pub vent_control: Hysteresis<u8>,
+    pub heater_control: Hysteresis<u8>,
+    pub frost_control: Hysteresis<u8>,
+    pub humidity_control: Hysteresis<u8>,
+    pub soil_control: Hysteresis<u8>,
+    pub actuators: ActuatorStates,
+}
+
+impl<'a, D: DelayNs> Zone<'a, D> {
+    /// Creates a new Zone with no readings. The sensor is set up on the first reading
+    ///
+    /// - param preferences: The zone's [Preferences]
+    /// - param addresses: The addresses of the zone's BME680s, e.g. [crate::sensors::BME680_ADDRESSES]
+    /// - param outputs: The [ZoneOutputs] the zone drives
+    ///
+    /// returns a new instance of Zone
+    pub fn new(
+        preferences: Preferences,
+        addresses: &[I2CAddress],
+        outputs: ZoneOutputs<'a>,
+    ) -> Zone<'a, D> {
+        Self {
+            vent_control: Hysteresis::rising(preferences.vent_points()),
+            heater_control: Hysteresis::falling(preferences.heater_points()),
+            frost_control: Hysteresis::falling(preferences.frost_points()),
+            humidity_control: Hysteresis::falling(preferences.humidity_points()),
+            soil_control: Hysteresis::falling(preferences.soil_points()),
+            filter: SensorFilter::with_smoothing(preferences.smoothing),
+            alarms: ReadingAlarms::new(&preferences),
+            preferences,
+            sensors: SensorGroup::new(addresses),
+            outputs,
+            data: SensorReading::default(),
+            pressure_trend: PressureTrend::new(),
+            temperature_rate: TemperatureRate::new(),
+            sensor_stats: SensorStats::new(),
+            daily_stats: DailyStats::new(),
+            trends: TrendGraphs::new(),
+            soil_moisture: None,
+            frost: false,
+            rising_fast: false,
+            watering: false,
+            manual_watering: ManualWatering::new(),
+            watering_history: WateringHistory::new(),
+            gas_warmup: SensorWarmup::new(),
+            air_quality: AirQuality::new(),
+            saving_power: false,
+            daylight: false,
+            actuators: ActuatorStates::default(),
+        }
+    }
+
+    /// Moves every [Hysteresis] to the thresholds in the [Preferences], and the filter to their smoothing.
+    /// Call this after the preferences were edited
+    pub fn refresh_points(&mut self) {
+        self.vent_control.set_points(self.preferences.vent_points());
+        self.heater_control
+            .set_points(self.preferences.heater_points());
+        self.frost_control
+            .set_points(self.preferences.frost_points());
+        self.humidity_control
+            .set_points(self.preferences.humidity_points());
+        self.soil_control.set_points(self.preferences.soil_points());
+        self.alarms.set_bounds(&self.preferences);
+        self.filter.set_smoothing(self.preferences.smoothing);
+    }
+
+    /// Sets the zone's sensors up if they are not already. With a single zone they may be
+    /// at either address, so both are tried; with several zones each has its own address
+    ///
+    /// - param bus: The shared [BmeBus]
+    /// - param delayer: BME sensor delay
+    ///
+    /// returns [SensorError::NoAddress] or [SensorError::Absent] if no sensor answered,
+    /// or [SensorError::Setup] if one answered but could not be set up
+    pub fn connect_sensor(
+        &mut self,
+        bus: &'a RefCell<BmeBus<'a>>,
+        delayer: &mut D,
+    ) -> Result<(), SensorError> {
+        if self
+            .sensors
+            .connect(bus, delayer, self.preferences.temp_offset)?
+        {
+            // The gas heater starts cold every time a sensor is set up,
+            // and a new sensor's gas resistance shifts the average
+            self.gas_warmup.start(self.preferences.gas_warmup_ms());
+            self.air_quality.reset();
+        }
+        Ok(())
+    }
+
+    /// Checks if the sensors have warmed up enough for their gas resistance to be trusted.
+    /// Anything worked out from the gas resistance should wait for this; the other readings don't have to
+    ///
+    /// returns true if there is a sensor and its warm-up time is over
+    pub fn is_warmed_up(&self) -> bool {
+        self.sensors.is_connected() && self.gas_warmup.is_warmed_up()
+    }
+
+    /// Turns the grow light on or off by its schedule, or off while saving power or while it is already bright outside.
+    /// It only follows the clock and the light sensor, so it does not need a reading
+    pub fn update_light(&mut self) {
+        if let Some(light) = self.outputs.grow_light.as_mut() {
+            let lit = self.preferences.is_light_time() && !self.saving_power && !self.daylight;
+            light.set_active(lit);
+        }
+    }
+
+    /// Holds the outputs in a safe state, for when there is no reading to act on.
+    /// Watering by hand carries on, since it does not need a reading
+    ///
+    /// - param overrides: The manual [Overrides]
+    /// - param observer: The [ActuatorObserver] told about outputs that changed
+    pub fn hold_safe(&mut self, overrides: &Overrides, observer: &mut impl ActuatorObserver) {
+        self.outputs.vent.set_open(overrides.vent_or(false));
+        self.outputs.heater.set_active(overrides.heater_or(false));
+        self.outputs.fan.set_duty_cycle_percent(0).unwrap();
+        self.outputs
+            .sprinklers
+            .set_active(overrides.sprinklers_or(self.manual_watering.sprinklers_or(false)));
+        self.sync_actuators(observer);
+    }
+
+    /// Drives the outputs to their safe state after a [crate::error::GemError]: sprinklers, heater,
+    /// and fan off, and the vent at [FAIL_SAFE_VENT_ANGLE]. Overrides are ignored, so nothing is
+    /// left running while the controller cannot be seen or trusted. The grow light follows its
+    /// schedule, which only needs the clock
+    ///
+    /// - param observer: The [ActuatorObserver] told about outputs that changed
+    pub fn fail_safe(&mut self, observer: &mut impl ActuatorObserver) {
+        self.outputs.vent.set_angle(FAIL_SAFE_VENT_ANGLE);
+        self.outputs.heater.deactivate();
+        self.outputs.fan.set_duty_cycle_percent(0).unwrap();
+        self.outputs.sprinklers.deactivate();
+        self.sync_actuators(observer);
+    }
+
+    /// Reads back which outputs are running into [Zone::actuators], and tells the observer
+    /// about each one that changed since they were last read
+    ///
+    /// - param observer: The [ActuatorObserver] to tell
+    pub fn sync_actuators(&mut self, observer: &mut impl ActuatorObserver) {
+        let previous = self.actuators;
+        self.actuators = ActuatorStates {
+            vent: self.outputs.vent.is_open(),
+            sprinklers: self.outputs.sprinklers.is_active(),
+            heater: self.outputs.heater.is_active(),
+            light: match self.outputs.grow_light.as_mut() {
+                Some(light) => light.is_active(),
+                None => false,
+            },
+        };
+        if previous != self.actuators {
+            notify_changes(
+                &previous,
+                &self.actuators,
+                &self.preferences.local_date(),
+                observer,
+            );
+        }
+    }
+
+    /// Switches each relay on and off, and spins the fan, so an installer can hear every output work.
+    /// Each relay is read back to check it switched. The vent is left alone, since a servo takes
+    /// a while to travel. Everything ends up off
+    ///
+    /// - param delay: Delay instance
+    ///
+    /// returns true if every relay switched on and off
+    pub fn test_outputs(&mut self, delay: &mut impl DelayNs) -> bool {
+        let mut passed = true;
+        let outputs = &mut self.outputs;
+        for relay in [&mut outputs.heater, &mut outputs.sprinklers]
+            .into_iter()
+            .chain(outputs.grow_light.as_mut())
+        {
+            relay.activate();
+            passed &= relay.is_active();
+            delay.delay_ms(TOGGLE_MS);
+            relay.deactivate();
+            passed &= !relay.is_active();
+        }
+        outputs.fan.set_duty_cycle_percent(100).unwrap();
+        delay.delay_ms(TOGGLE_MS);
+        outputs.fan.set_duty_cycle_percent(0).unwrap();
+        passed
+    }
+
+    /// Acts on a new reading: vents, cools, heats, and waters the zone as needed
+    ///
+    /// - param data: The new [SensorReading]
+    /// - param now: The current time in seconds since boot
+    /// - param soil_moisture: The soil moisture percentage, or None without a soil sensor
+    /// - param overrides: The manual [Overrides]
+    /// - param observer: The [ActuatorObserver] told about outputs that changed
+    pub fn regulate(
+        &mut self,
+        data: SensorReading,
+        now: u32,
+        soil_moisture: Option<u8>,
+        overrides: &Overrides,
+        observer: &mut impl ActuatorObserver,
+    ) {
+        self.data = data;
+        self.filter.push(&data);
+        telemetry::sensor::reading(&data);
+        self.pressure_trend.push(self.filter.pressure(), now);
+
+        let temp = self.filter.temperature();
+        self.temperature_rate.push(temp, now);
+        let rate = self.temperature_rate.temperature_rate_per_min();
+        self.daily_stats
+            .update(temp, self.filter.humidity(), &self.preferences.local_date());
+        self.trends
+            .record(temp, self.filter.humidity(), self.filter.pressure(), now);
+
+        // Warn if it is freezing, in case the heater failed
+        let was_frost = self.frost;
+        self.frost = self.frost_control.update(temp);
+        if self.frost != was_frost {
+            telemetry::actuator::changed("frost alarm", self.frost);
+        }
+
+        // Warn if a reading is past its alarm bounds, e.g. because the vent or heater failed
+        let humidity = self.filter.humidity();
+        let was_alarm = self.alarms.active();
+        let alarm = self.alarms.update(temp, humidity);
+        if alarm != was_alarm {
+            telemetry::alarm::changed(alarm, temp, humidity);
+        }
+
+        // Warn if it is heating up fast, e.g. from direct sun, before it gets too hot
+        let was_rising_fast = self.rising_fast;
+        self.rising_fast = is_rising_fast(rate, &self.preferences);
+        if self.rising_fast != was_rising_fast {
+            telemetry::actuator::changed("rate alarm", self.rising_fast);
+        }
+
+        // Air out stale air once the sensor can be trusted, unless it is freezing
+        let was_stale = self.air_quality.is_venting();
+        if self.is_warmed_up() {
+            self.air_quality
+                .update(data.gas_resistance, now, self.preferences.air_venting);
+        }
+        let stale = self.air_quality.is_venting();
+        if stale != was_stale {
+            telemetry::actuator::changed("stale air", stale);
+        }
+        let airing = stale && !self.frost;
+
+        // Open the vent further the hotter it is, or early if it heats up fast,
+        // and keep it open a little until it has cooled through its margin
+        // or the air is fresh again; keep it shut if a storm is coming
+        let venting = self.vent_control.update(temp);
+        let mut angle = vent_angle(temp, rate, &self.preferences);
+        if venting {
+            angle = angle.max(VENT_HOLD_ANGLE);
+        }
+        if airing {
+            angle = angle.max(AIR_VENT_ANGLE);
+        }
+        if self.pressure_trend.is_falling_rapidly() {
+            angle = 0;
+        }
+        self.outputs.vent.set_angle(overrides.vent_angle_or(angle));
+
+        // Cool proportionally to how hot it is, unless the battery is too low to spare;
+        // the vent still cools it
+        let duty = if self.saving_power {
+            0
+        } else {
+            fan_duty(temp, &self.preferences)
+        };
+        self.outputs.fan.set_duty_cycle_percent(duty).unwrap();
+
+        // Check if it is too cold
+        let heating = overrides.heater_or(self.heater_control.update(temp));
+        self.outputs.heater.set_active(heating);
+
+        // Check if it is too dry
+        let humidifying = self.humidity_control.update(self.filter.humidity());
+
+        // Check if the soil is too dry, if there is a soil sensor
+        self.soil_moisture = soil_moisture;
+        let soaking = match soil_moisture {
+            Some(moisture) => self.soil_control.update(moisture),
+            None => {
+                self.soil_control.reset();
+                false
+            }
+        };
+
+        // Water if it is too dry, it is watering time, or watering was started by hand
+        if self.watering != self.preferences.is_watering_time() {
+            self.watering = !self.watering;
+            telemetry::watering::window(self.watering);
+        }
+        let sprinkling = overrides.sprinklers_or(self.manual_watering.sprinklers_or(
+            sprinklers_should_run(humidifying || soaking, soil_moisture, &self.preferences),
+        ));
+        self.outputs.sprinklers.set_active(sprinkling);
+        let manual = self.manual_watering.is_active() || overrides.sprinklers == Some(true);
+        self.watering_history.update(
+            sprinkling,
+            watering_reason(manual, self.watering, soaking),
+            self.preferences.local_date(),
+            now,
+        );
+
+        self.sync_actuators(observer);
+        telemetry::state::report(&data, &self.actuators, &self.preferences);
+    }
+}
+
+/// Copies the clock from one zone to every other, after it ticked or was edited
+///
+/// - param zones: Every [Zone]
+/// - param from: The index of the zone with the up-to-date clock
+pub fn share_clock(zones: &mut [Zone], from: usize) {
+    let (before, rest) = zones.split_at_mut(from);
+    let Some((source, after)) = rest.split_first_mut() else {
+        return;
+    };
+    for zone in before.iter_mut().chain(after) {
+        zone.preferences.copy_clock_from(&source.preferences);
+    }
+}